@@ -0,0 +1,76 @@
+use std::{borrow, fmt::Write};
+
+/// The value of an accessor returning `Option<&'static str>` for a constant of type
+/// `datatype` and value `value`, mapping empty strings to `None`. `None` if the
+/// constant is not a string.
+fn normalize<'a>(datatype: &str, value: &'a str) -> Option<borrow::Cow<'a, str>> {
+    match datatype {
+        "&str" if value == r#""""# => Some(borrow::Cow::Borrowed("None")),
+        "&str" => Some(borrow::Cow::Owned(format!("Some({value})"))),
+        "Option<&str>" if value == r#"Some("")"# => Some(borrow::Cow::Borrowed("None")),
+        "Option<&str>" => Some(borrow::Cow::Borrowed(value)),
+        _ => None,
+    }
+}
+
+/// Renders a `const fn` for every string-constant declared in the content of a
+/// `built.rs`, e.g. `rustdoc_version()` for `RUSTDOC_VERSION`, which returns `None`
+/// if the constant is empty or `None`.
+pub fn render(built: &str) -> String {
+    let mut out = String::new();
+    for item in crate::json::items(built) {
+        if let Some(value) = normalize(item.datatype, item.value) {
+            let _ = writeln!(
+                out,
+                "/// `{}`, or `None` if it is empty.\n#[allow(dead_code)]\n\
+                pub const fn {}() -> Option<&'static str> {{\n    {value}\n}}",
+                item.name,
+                item.name.to_lowercase()
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn render() {
+        let built = r##"#[doc=r#"The name of the package."#]
+#[allow(dead_code)]
+pub static PKG_NAME: &str = "foo";
+#[allow(dead_code)]
+pub static RUSTDOC_VERSION: &str = "";
+#[allow(dead_code)]
+pub static GIT_VERSION: Option<&str> = Some("");
+#[allow(dead_code)]
+pub static GIT_HEAD_REF: Option<&str> = None;
+#[allow(dead_code)]
+pub static GIT_DIRTY: Option<bool> = Some(true);
+"##;
+        assert_eq!(
+            super::render(built),
+            r#"/// `PKG_NAME`, or `None` if it is empty.
+#[allow(dead_code)]
+pub const fn pkg_name() -> Option<&'static str> {
+    Some("foo")
+}
+/// `RUSTDOC_VERSION`, or `None` if it is empty.
+#[allow(dead_code)]
+pub const fn rustdoc_version() -> Option<&'static str> {
+    None
+}
+/// `GIT_VERSION`, or `None` if it is empty.
+#[allow(dead_code)]
+pub const fn git_version() -> Option<&'static str> {
+    None
+}
+/// `GIT_HEAD_REF`, or `None` if it is empty.
+#[allow(dead_code)]
+pub const fn git_head_ref() -> Option<&'static str> {
+    None
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use crate::util::ArrayDisplay;
+use crate::write_variable;
+use std::{fs, io, path};
+
+/// A vulnerability-advisory from the RustSec advisory-db.
+#[derive(Debug)]
+struct Advisory {
+    id: String,
+    package: String,
+    patched: Vec<semver::VersionReq>,
+    unaffected: Vec<semver::VersionReq>,
+}
+
+impl Advisory {
+    /// Parses an advisory from the advisory-db, either in Markdown-format with a
+    /// TOML front-matter or in plain TOML. Informational and withdrawn advisories
+    /// are skipped.
+    fn parse(content: &str) -> io::Result<Option<Self>> {
+        let front_matter = match content.trim_start().strip_prefix("```toml") {
+            Some(rest) => rest
+                .split_once("\n```")
+                .map(|(front_matter, _)| front_matter)
+                .ok_or_else(|| io::Error::other("unterminated front-matter"))?,
+            None => content,
+        };
+        let doc: toml::Table = front_matter.parse().map_err(io::Error::other)?;
+        let advisory = doc
+            .get("advisory")
+            .and_then(toml::Value::as_table)
+            .ok_or_else(|| io::Error::other("missing `[advisory]`"))?;
+        if advisory.contains_key("informational") || advisory.contains_key("withdrawn") {
+            return Ok(None);
+        }
+        let field = |key| {
+            advisory
+                .get(key)
+                .and_then(toml::Value::as_str)
+                .map(ToOwned::to_owned)
+                .ok_or_else(|| io::Error::other(format!("missing `advisory.{key}`")))
+        };
+        let reqs = |key| -> io::Result<Vec<semver::VersionReq>> {
+            doc.get("versions")
+                .and_then(|versions| versions.get(key))
+                .and_then(toml::Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(toml::Value::as_str)
+                .map(|req| req.parse().map_err(io::Error::other))
+                .collect()
+        };
+        Ok(Some(Self {
+            id: field("id")?,
+            package: field("package")?,
+            patched: reqs("patched")?,
+            unaffected: reqs("unaffected")?,
+        }))
+    }
+
+    /// Whether `version` of the package is affected, which is the case if it is
+    /// neither patched nor unaffected.
+    fn affects(&self, version: &semver::Version) -> bool {
+        !self
+            .patched
+            .iter()
+            .chain(&self.unaffected)
+            .any(|req| req.matches(version))
+    }
+}
+
+/// Loads all advisories from the `crates`-directory of the advisory-db at `db`.
+fn load_advisories(db: &path::Path) -> io::Result<Vec<Advisory>> {
+    let mut advisories = Vec::new();
+    for package_dir in fs::read_dir(db.join("crates"))? {
+        let package_dir = package_dir?.path();
+        if !package_dir.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(&package_dir)? {
+            let path = entry?.path();
+            if !path
+                .extension()
+                .is_some_and(|ext| ext == "md" || ext == "toml")
+            {
+                continue;
+            }
+            let advisory = Advisory::parse(&fs::read_to_string(&path)?).map_err(|e| {
+                io::Error::other(format!("failed to parse `{}`: {e}", path.display()))
+            })?;
+            advisories.extend(advisory);
+        }
+    }
+    Ok(advisories)
+}
+
+/// Retrieves `(name, version, advisory id)` of all packages in `lockfile` which
+/// are affected by any of the `advisories`.
+fn vulnerable_packages(
+    lockfile: &cargo_lock::Lockfile,
+    advisories: &[Advisory],
+) -> Vec<(String, String, String)> {
+    let mut vulnerable = lockfile
+        .packages
+        .iter()
+        .flat_map(|package| {
+            advisories
+                .iter()
+                .filter(|advisory| {
+                    advisory.package == package.name.as_str() && advisory.affects(&package.version)
+                })
+                .map(|advisory| {
+                    (
+                        package.name.to_string(),
+                        package.version.to_string(),
+                        advisory.id.clone(),
+                    )
+                })
+        })
+        .collect::<Vec<_>>();
+    vulnerable.sort_unstable();
+    vulnerable.dedup();
+    vulnerable
+}
+
+pub fn write_vulnerabilities(
+    manifest_location: &path::Path,
+    advisory_db: &path::Path,
+    mut w: impl crate::report::Backend,
+) -> io::Result<()> {
+    let lockfile: cargo_lock::Lockfile =
+        fs::read_to_string(crate::dependencies::find_lockfile(manifest_location)?)?
+            .parse()
+            .map_err(io::Error::other)?;
+    let vulnerable = vulnerable_packages(&lockfile, &load_advisories(advisory_db)?);
+    write_variable!(
+        w,
+        "VULNERABLE_DEPENDENCIES",
+        format_args!("[(&str, &str, &str); {}]", vulnerable.len()),
+        ArrayDisplay(&vulnerable, |(name, version, id), fmt| write!(
+            fmt,
+            r#"("{}", "{}", "{}")"#,
+            name.escape_default(),
+            version.escape_default(),
+            id.escape_default()
+        )),
+        "The dependencies' names, versions and the ids of the RustSec-advisories \
+        affecting them, as found in the local advisory-db."
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    static ADVISORY: &str = r#"```toml
+[advisory]
+id = "RUSTSEC-2024-0001"
+package = "foo"
+date = "2024-01-01"
+
+[versions]
+patched = [">= 1.2.3"]
+unaffected = ["< 1.0.0"]
+```
+
+# Everything is broken
+
+Details.
+"#;
+
+    static LOCKFILE: &str = r#"
+version = 3
+
+[[package]]
+name = "bar"
+version = "1.0.0"
+
+[[package]]
+name = "foo"
+version = "0.9.0"
+
+[[package]]
+name = "foo"
+version = "1.2.0"
+"#;
+
+    #[test]
+    fn parse_advisory() {
+        let advisory = super::Advisory::parse(ADVISORY).unwrap().unwrap();
+        assert_eq!(advisory.id, "RUSTSEC-2024-0001");
+        assert_eq!(advisory.package, "foo");
+        assert!(advisory.affects(&"1.2.2".parse().unwrap()));
+        assert!(!advisory.affects(&"1.2.3".parse().unwrap()));
+        assert!(!advisory.affects(&"0.9.0".parse().unwrap()));
+
+        let informational = ADVISORY.replace("date =", "informational = \"unmaintained\"\ndate =");
+        assert!(super::Advisory::parse(&informational).unwrap().is_none());
+        assert!(super::Advisory::parse("```toml\n[advisory]").is_err());
+    }
+
+    #[test]
+    fn vulnerable_packages() {
+        let db = tempfile::tempdir().unwrap();
+        fs::create_dir_all(db.path().join("crates/foo")).unwrap();
+        fs::write(db.path().join("crates/foo/RUSTSEC-2024-0001.md"), ADVISORY).unwrap();
+        fs::write(db.path().join("crates/foo/README"), "").unwrap();
+        let advisories = super::load_advisories(db.path()).unwrap();
+        let lockfile = LOCKFILE.parse().unwrap();
+        assert_eq!(
+            super::vulnerable_packages(&lockfile, &advisories),
+            [(
+                "foo".to_owned(),
+                "1.2.0".to_owned(),
+                "RUSTSEC-2024-0001".to_owned()
+            )]
+        );
+    }
+}
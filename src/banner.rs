@@ -0,0 +1,164 @@
+use crate::util::JsonStr;
+use std::fmt;
+
+/// The layout of a `Banner`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BannerStyle {
+    /// All fields on a single line as `key=value`, e.g. for log-files.
+    #[default]
+    OneLine,
+    /// One field per line, aligned and surrounded by a box.
+    Boxed,
+    /// All fields as a single-line JSON-object, e.g. for structured logging.
+    Json,
+}
+
+/// Renders the generated `BUILD_TAGS` as a startup-banner, by means of `Display`.
+///
+/// ```
+/// # mod built_info {
+/// #    pub static BUILD_TAGS: [(&str, &str); 3] = [("pkg.name", "foo"), ("pkg.version", "1.2.3"), ("rustc.version", "rustc 1.85.0")];
+/// # }
+/// let mut banner = built::Banner::new(&built_info::BUILD_TAGS);
+/// assert_eq!(
+///     banner.to_string(),
+///     r#"pkg.name=foo pkg.version=1.2.3 rustc.version="rustc 1.85.0""#
+/// );
+///
+/// banner
+///     .set_fields(["pkg.name", "pkg.version"])
+///     .set_style(built::BannerStyle::Json);
+/// assert_eq!(
+///     banner.to_string(),
+///     r#"{"pkg.name": "foo", "pkg.version": "1.2.3"}"#
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Banner<'a> {
+    tags: &'a [(&'a str, &'a str)],
+    fields: Option<Vec<&'a str>>,
+    style: BannerStyle,
+}
+
+impl<'a> Banner<'a> {
+    /// A banner showing all of `tags` as `BannerStyle::OneLine`.
+    #[must_use]
+    pub fn new(tags: &'a [(&'a str, &'a str)]) -> Self {
+        Self {
+            tags,
+            fields: None,
+            style: BannerStyle::default(),
+        }
+    }
+
+    /// Only show the tags with the given keys, in the given order. Keys which are not
+    /// present in the tags, e.g. `git.commit` outside of a git-repository, are skipped.
+    ///
+    /// Defaults to showing all tags.
+    pub fn set_fields<I>(&mut self, fields: I) -> &mut Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.fields = Some(fields.into_iter().collect());
+        self
+    }
+
+    /// Select the layout. Defaults to `BannerStyle::OneLine`.
+    pub fn set_style(&mut self, style: BannerStyle) -> &mut Self {
+        self.style = style;
+        self
+    }
+
+    /// The tags to be shown.
+    fn entries(&self) -> Vec<(&'a str, &'a str)> {
+        match &self.fields {
+            Some(fields) => fields
+                .iter()
+                .filter_map(|field| self.tags.iter().find(|(key, _)| key == field).copied())
+                .collect(),
+            None => self.tags.to_vec(),
+        }
+    }
+}
+
+impl fmt::Display for Banner<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self.entries();
+        match self.style {
+            BannerStyle::OneLine => {
+                for (idx, (key, value)) in entries.iter().enumerate() {
+                    if idx != 0 {
+                        f.write_str(" ")?;
+                    }
+                    if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '"') {
+                        write!(f, "{key}=\"{}\"", value.escape_default())?;
+                    } else {
+                        write!(f, "{key}={value}")?;
+                    }
+                }
+                Ok(())
+            }
+            BannerStyle::Boxed => {
+                let key_width = entries
+                    .iter()
+                    .map(|(key, _)| key.chars().count())
+                    .max()
+                    .unwrap_or(0);
+                let width = entries
+                    .iter()
+                    .map(|(_, value)| key_width + 3 + value.chars().count())
+                    .max()
+                    .unwrap_or(0);
+                let border = "-".repeat(width + 2);
+                writeln!(f, "+{border}+")?;
+                for (key, value) in &entries {
+                    let line = format!("{key:key_width$} : {value}");
+                    writeln!(f, "| {line:width$} |")?;
+                }
+                write!(f, "+{border}+")
+            }
+            BannerStyle::Json => {
+                f.write_str("{")?;
+                for (idx, (key, value)) in entries.iter().enumerate() {
+                    if idx != 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", JsonStr(key), JsonStr(value))?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Banner, BannerStyle};
+
+    static TAGS: [(&str, &str); 3] = [
+        ("pkg.name", "foo"),
+        ("pkg.version", "1.2.3"),
+        ("git.dirty", "false"),
+    ];
+
+    #[test]
+    fn boxed() {
+        let mut banner = Banner::new(&TAGS);
+        banner.set_style(BannerStyle::Boxed);
+        assert_eq!(
+            banner.to_string(),
+            "+---------------------+
+| pkg.name    : foo   |
+| pkg.version : 1.2.3 |
+| git.dirty   : false |
++---------------------+"
+        );
+    }
+
+    #[test]
+    fn fields() {
+        let mut banner = Banner::new(&TAGS);
+        banner.set_fields(["git.dirty", "git.commit", "pkg.name"]);
+        assert_eq!(banner.to_string(), "git.dirty=false pkg.name=foo");
+    }
+}
@@ -0,0 +1,96 @@
+use std::io;
+
+/// How values deviating from the baseline set by `Options::add_baseline()` are
+/// handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BaselineDeviation {
+    /// Emit a `cargo:warning` for every deviating value.
+    #[default]
+    Warn,
+    /// Fail the build if any value deviates.
+    Error,
+}
+
+/// Compares the constants in the content of a `built.rs` with the `baseline`, a
+/// list of constants and the glob-patterns their values are expected to match.
+///
+/// String-values are compared as they are, `None` as an empty string and all other
+/// values as Rust-expressions, with `Some(...)` removed.
+pub fn check(
+    built: &str,
+    baseline: &[(String, Vec<String>)],
+    deviation: BaselineDeviation,
+) -> io::Result<()> {
+    let items = crate::json::items(built).collect::<Vec<_>>();
+    let mut deviations = Vec::new();
+    for (name, patterns) in baseline {
+        let Some(item) = items.iter().find(|item| item.name == name) else {
+            deviations.push(format!("`{name}` is expected, but was not generated"));
+            continue;
+        };
+        let value = crate::tags::tag_value(item.datatype, item.value).unwrap_or_default();
+        if !patterns
+            .iter()
+            .any(|pattern| crate::util::glob_match(pattern, &value))
+        {
+            deviations.push(format!(
+                "`{name}` is `{value}`, which deviates from the baseline `{}`",
+                patterns.join("`, `")
+            ));
+        }
+    }
+    match deviation {
+        BaselineDeviation::Warn => {
+            for msg in &deviations {
+                crate::self_check::warn(msg);
+            }
+            Ok(())
+        }
+        BaselineDeviation::Error if deviations.is_empty() => Ok(()),
+        BaselineDeviation::Error => Err(io::Error::other(deviations.join("; "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BaselineDeviation;
+
+    static BUILT: &str = r#"#[allow(dead_code)]
+pub static TARGET: &str = "x86_64-unknown-linux-gnu";
+#[allow(dead_code)]
+pub static RUSTC_VERSION: &str = "rustc 1.85.0 (4d91de4e4 2025-02-17)";
+#[allow(dead_code)]
+pub static CI_PLATFORM: Option<&str> = None;
+"#;
+
+    fn baseline(baseline: &[(&str, &[&str])]) -> Vec<(String, Vec<String>)> {
+        baseline
+            .iter()
+            .map(|(name, patterns)| {
+                (
+                    (*name).to_owned(),
+                    patterns.iter().map(|p| (*p).to_owned()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn check() {
+        let expected = baseline(&[
+            ("TARGET", &["x86_64-*-linux-gnu", "aarch64-*-linux-gnu"]),
+            ("RUSTC_VERSION", &["rustc 1.85.*"]),
+        ]);
+        assert!(super::check(BUILT, &expected, BaselineDeviation::Error).is_ok());
+
+        let expected = baseline(&[("CI_PLATFORM", &["GitHub Actions"]), ("GIT_DIRTY", &[])]);
+        let err = super::check(BUILT, &expected, BaselineDeviation::Error)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("`CI_PLATFORM` is ``, which deviates from the baseline `GitHub Actions`")
+        );
+        assert!(err.contains("`GIT_DIRTY` is expected, but was not generated"));
+        assert!(super::check(BUILT, &expected, BaselineDeviation::Warn).is_ok());
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt::Write;
+
+/// Renders `s` as a C string-literal. Non-ASCII and non-printable bytes are written
+/// as octal escape-sequences, which, unlike hexadecimal ones, can't swallow the
+/// characters following them.
+fn c_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for b in s.bytes() {
+        match b {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            b'\n' => out.push_str("\\n"),
+            b'\t' => out.push_str("\\t"),
+            b' '..=b'~' => out.push(char::from(b)),
+            b => {
+                let _ = write!(out, "\\{b:03o}");
+            }
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// The replacement-text of the macro for a constant of `datatype` and `value`, if
+/// it can be represented in C. `None`-values are not represented, so their macro
+/// is not defined at all; arrays and tuples are not supported.
+fn c_value(datatype: &str, value: &str) -> Option<String> {
+    if let Some(inner) = datatype
+        .strip_prefix("Option<")
+        .and_then(|s| s.strip_suffix('>'))
+    {
+        let value = value.strip_prefix("Some(")?.strip_suffix(')')?;
+        return c_value(inner, value);
+    }
+    match datatype {
+        "&str" => crate::json::unquote(value).map(|s| c_str(&s)),
+        "bool" => Some(if value == "true" { "1" } else { "0" }.to_owned()),
+        "u8" | "u16" | "u32" => Some(format!("{value}u")),
+        "u64" | "usize" => Some(format!("{value}ull")),
+        _ => None,
+    }
+}
+
+/// Renders the constants in the content of a `built.rs` as a C-header, defining a
+/// `BUILT_`-prefixed macro for every constant which can be represented in C.
+pub fn render(built: &str) -> String {
+    let mut header = String::from(
+        "/* EVERYTHING IN THIS FILE WAS AUTO-GENERATED DURING COMPILATION. DO NOT MODIFY. */
+#ifndef BUILT_H
+#define BUILT_H
+
+",
+    );
+    for item in crate::json::items(built) {
+        let Some(value) = c_value(item.datatype, item.value) else {
+            continue;
+        };
+        if let Some(doc) = item.doc {
+            let _ = writeln!(header, "/* {} */", doc.replace("*/", "* /"));
+        }
+        let _ = writeln!(header, "#define BUILT_{} {value}", item.name);
+    }
+    header.push_str("\n#endif /* BUILT_H */\n");
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn c_str() {
+        assert_eq!(super::c_str("foo"), r#""foo""#);
+        assert_eq!(super::c_str("a\"b\\c\n"), r#""a\"b\\c\n""#);
+        assert_eq!(super::c_str("é1"), r#""\303\2511""#);
+    }
+
+    #[test]
+    fn render() {
+        let built = r##"#[doc=r#"The full version."#]
+#[allow(dead_code)]
+pub static PKG_VERSION: &str = "1.2.3";
+#[allow(dead_code)]
+pub static NUM_JOBS: u32 = 8;
+#[allow(dead_code)]
+pub static GIT_DIRTY: Option<bool> = Some(true);
+#[allow(dead_code)]
+pub static GIT_VERSION: Option<&str> = None;
+#[allow(dead_code)]
+pub static GIT_COMMIT_HASH: Option<&str> = Some("abc");
+#[allow(dead_code)]
+pub static FEATURES: [&str; 1] = ["FOO"];
+"##;
+        assert_eq!(
+            super::render(built),
+            r#"/* EVERYTHING IN THIS FILE WAS AUTO-GENERATED DURING COMPILATION. DO NOT MODIFY. */
+#ifndef BUILT_H
+#define BUILT_H
+
+/* The full version. */
+#define BUILT_PKG_VERSION "1.2.3"
+#define BUILT_NUM_JOBS 8u
+#define BUILT_GIT_DIRTY 1
+#define BUILT_GIT_COMMIT_HASH "abc"
+
+#endif /* BUILT_H */
+"#
+        );
+    }
+}
@@ -0,0 +1,96 @@
+/// The keys of `BUILD_TAGS` which determine the build's output, as opposed to e.g.
+/// `build.time`.
+static PROVENANCE: [&str; 9] = [
+    "pkg.name",
+    "pkg.version",
+    "git.commit",
+    "git.dirty",
+    "rustc.version",
+    "build.target",
+    "build.profile",
+    "build.opt_level",
+    "build.features",
+];
+
+/// The 128-bit FNV-1a hash, which, unlike the `std`-hashers, never changes.
+struct Fnv1a(u128);
+
+impl Fnv1a {
+    const OFFSET_BASIS: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+    const PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= u128::from(*b);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Writes `s`, prefixed by its length so that adjacent strings can't be confused.
+    fn write_str(&mut self, s: &str) {
+        self.write(&(s.len() as u64).to_le_bytes());
+        self.write(s.as_bytes());
+    }
+}
+
+/// Computes a key from the generated `BUILD_TAGS` and `extra`, suitable for keying
+/// caches of artifacts by build provenance.
+///
+/// Only the tags which determine the build's output are considered: the package's
+/// name and version, git's commit and dirty-state, the compiler's version, the
+/// target, the profile, the optimization-level and the features. The key is a
+/// 32-character hexadecimal string, which only changes if any of these or `extra`
+/// change; it is the same across platforms and versions of `built`.
+///
+/// ```
+/// # mod built_info {
+/// #    pub static BUILD_TAGS: [(&str, &str); 2] = [("pkg.name", "foo"), ("build.time", "Wed, 27 May 2020 18:12:39 +0000")];
+/// # }
+/// let key = built::cache_key(&built_info::BUILD_TAGS, &["linux-musl"]);
+/// assert_eq!(key.len(), 32);
+/// assert_ne!(key, built::cache_key(&built_info::BUILD_TAGS, &[]));
+/// ```
+#[must_use]
+pub fn cache_key(tags: &[(&str, &str)], extra: &[&str]) -> String {
+    let mut hasher = Fnv1a(Fnv1a::OFFSET_BASIS);
+    for key in PROVENANCE {
+        let value = tags.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+        hasher.write_str(key);
+        match value {
+            Some(value) => {
+                hasher.write(&[1]);
+                hasher.write_str(value);
+            }
+            None => hasher.write(&[0]),
+        }
+    }
+    for s in extra {
+        hasher.write_str(s);
+    }
+    format!("{:032x}", hasher.0)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn fnv1a() {
+        let mut hasher = super::Fnv1a(super::Fnv1a::OFFSET_BASIS);
+        hasher.write(b"a");
+        assert_eq!(hasher.0, 0xd228_cb69_6f1a_8caf_7891_2b70_4e4a_8964);
+    }
+
+    #[test]
+    fn cache_key() {
+        let tags = [
+            ("pkg.name", "foo"),
+            ("build.time", "Wed, 27 May 2020 18:12:39 +0000"),
+        ];
+        let key = super::cache_key(&tags, &[]);
+        assert_eq!(key, super::cache_key(&tags[..1], &[]));
+        assert_ne!(key, super::cache_key(&[("pkg.name", "bar")], &[]));
+        assert_ne!(
+            super::cache_key(&[], &["ab", "c"]),
+            super::cache_key(&[], &["a", "bc"])
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use std::fmt;
+
+/// The keys of `BUILD_TAGS` which must be equal for a plugin to be compatible with
+/// its host: Rust has no stable ABI, and unwinding across a boundary between
+/// binaries using different panic-strategies is undefined behavior.
+static MUST_MATCH: [&str; 3] = ["rustc.version", "build.target", "build.panic"];
+
+/// A difference between the `BUILD_TAGS` of a host and a plugin which makes them
+/// incompatible, as found by `check_compatibility()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Incompatibility<'a> {
+    /// The key of the tag which differs, e.g. `rustc.version`.
+    pub key: &'a str,
+    /// The host's value; `None` if it is unknown.
+    pub host: Option<&'a str>,
+    /// The plugin's value; `None` if it is unknown.
+    pub plugin: Option<&'a str>,
+}
+
+impl fmt::Display for Incompatibility<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` differs: {} (host) vs. {} (plugin)",
+            self.key,
+            self.host.unwrap_or("unknown"),
+            self.plugin.unwrap_or("unknown")
+        )
+    }
+}
+
+/// The value of the tag `key` in `tags`.
+fn get<'a>(tags: &[(&str, &'a str)], key: &str) -> Option<&'a str> {
+    tags.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+/// The components of `version` which must be equal for versions to be compatible as
+/// per Cargo's rules, i.e. up to and including the leftmost non-zero one.
+fn compatible_part(version: &str) -> Vec<&str> {
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    let mut part = Vec::new();
+    for component in core.split('.') {
+        part.push(component);
+        if component != "0" {
+            break;
+        }
+    }
+    part
+}
+
+/// Compares the `BUILD_TAGS` of a host with those of a plugin it is about to load,
+/// e.g. as exported by a dynamic library, and returns the differences which make them
+/// incompatible: different versions of `rustc`, targets or panic-strategies, and
+/// semver-incompatible versions of the package, e.g. of the crate defining the
+/// plugin-interface.
+///
+/// A tag which is known to only one of them counts as a difference, as compatibility
+/// can't be verified.
+///
+/// ```
+/// # mod built_info {
+/// #    pub static BUILD_TAGS: [(&str, &str); 3] = [("pkg.version", "1.2.3"), ("rustc.version", "rustc 1.85.0"), ("build.panic", "unwind")];
+/// # }
+/// let plugin = [("pkg.version", "1.4.0"), ("rustc.version", "rustc 1.85.0"), ("build.panic", "abort")];
+/// let incompatibilities = built::check_compatibility(&built_info::BUILD_TAGS, &plugin);
+/// assert_eq!(
+///     incompatibilities[0].to_string(),
+///     "`build.panic` differs: unwind (host) vs. abort (plugin)"
+/// );
+/// assert_eq!(incompatibilities.len(), 1);
+/// ```
+#[must_use]
+pub fn check_compatibility<'a>(
+    host: &[(&str, &'a str)],
+    plugin: &[(&str, &'a str)],
+) -> Vec<Incompatibility<'a>> {
+    let mut incompatibilities = MUST_MATCH
+        .into_iter()
+        .map(|key| Incompatibility {
+            key,
+            host: get(host, key),
+            plugin: get(plugin, key),
+        })
+        .filter(|i| i.host != i.plugin)
+        .collect::<Vec<_>>();
+    if let (Some(host_version), Some(plugin_version)) =
+        (get(host, "pkg.version"), get(plugin, "pkg.version"))
+    {
+        if compatible_part(host_version) != compatible_part(plugin_version) {
+            incompatibilities.push(Incompatibility {
+                key: "pkg.version",
+                host: Some(host_version),
+                plugin: Some(plugin_version),
+            });
+        }
+    }
+    incompatibilities
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn compatible_part() {
+        assert_eq!(super::compatible_part("1.2.3"), ["1"]);
+        assert_eq!(super::compatible_part("0.7.5-rc1"), ["0", "7"]);
+        assert_eq!(super::compatible_part("0.0.1+build"), ["0", "0", "1"]);
+    }
+
+    #[test]
+    fn check_compatibility() {
+        let host = [
+            ("pkg.version", "0.7.5"),
+            ("rustc.version", "rustc 1.85.0"),
+            ("build.target", "x86_64-unknown-linux-gnu"),
+        ];
+        assert_eq!(super::check_compatibility(&host, &host), []);
+
+        let plugin = [
+            ("pkg.version", "0.8.0"),
+            ("rustc.version", "rustc 1.85.0"),
+            ("build.target", "x86_64-unknown-linux-gnu"),
+            ("build.panic", "unwind"),
+        ];
+        let incompatibilities = super::check_compatibility(&host, &plugin);
+        assert_eq!(
+            incompatibilities
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "`build.panic` differs: unknown (host) vs. unwind (plugin)",
+                "`pkg.version` differs: 0.7.5 (host) vs. 0.8.0 (plugin)"
+            ]
+        );
+    }
+}
@@ -1,6 +1,17 @@
-use crate::util::TupleArrayDisplay;
+use crate::util::{ArrayDisplay, JsonStr, TupleArrayDisplay};
 use crate::{write_str_variable, write_variable};
-use std::{collections, fs, io, path};
+use std::{collections, fmt, fs, io, path};
+
+/// The formats in which a software bill of materials can be written.
+///
+/// See `Options::set_sbom_output()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbomFormat {
+    /// A [CycloneDX](https://cyclonedx.org) 1.5 JSON-document.
+    CycloneDx,
+    /// An [SPDX](https://spdx.dev) 2.3 JSON-document.
+    Spdx,
+}
 
 fn package_names<'a, I>(packages: I) -> Vec<(String, String)>
 where
@@ -16,7 +27,45 @@ where
     res
 }
 
-fn find_lockfile(base: &path::Path) -> io::Result<path::PathBuf> {
+/// The dependencies in `deps` which are to be serialized, as per
+/// `Options::set_dependency_include()` and `Options::set_dependency_exclude()`.
+fn filter_dependencies(
+    deps: &[(String, String)],
+    options: &crate::Options,
+) -> Vec<(String, String)> {
+    let matches_any = |patterns: &[String], name: &str| {
+        patterns
+            .iter()
+            .any(|pattern| crate::util::glob_match(pattern, name))
+    };
+    deps.iter()
+        .filter(|(name, _)| {
+            (options.dependency_include.is_empty()
+                || matches_any(&options.dependency_include, name))
+                && !matches_any(&options.dependency_exclude, name)
+        })
+        .cloned()
+        .collect()
+}
+
+/// The packages which no other package in the lockfile depends upon, i.e. the
+/// root-package and other workspace-members.
+#[cfg(not(feature = "dependency-tree"))]
+fn root_packages(lockfile: &cargo_lock::Lockfile) -> Vec<&cargo_lock::Package> {
+    let depended_upon = lockfile
+        .packages
+        .iter()
+        .flat_map(|package| &package.dependencies)
+        .map(|dep| (&dep.name, &dep.version))
+        .collect::<collections::HashSet<_>>();
+    lockfile
+        .packages
+        .iter()
+        .filter(|package| !depended_upon.contains(&(&package.name, &package.version)))
+        .collect()
+}
+
+pub(crate) fn find_lockfile(base: &path::Path) -> io::Result<path::PathBuf> {
     base.ancestors()
         .find_map(|p| {
             let lockfile = p.join("Cargo.lock");
@@ -25,141 +74,429 @@ fn find_lockfile(base: &path::Path) -> io::Result<path::PathBuf> {
         .ok_or(io::Error::other("Cargo.lock not found"))
 }
 
+/// Which packages the dependency-graph is resolved relative to.
+///
+/// See `Options::set_dependency_roots()`.
 #[cfg(feature = "dependency-tree")]
-struct Dependencies {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DependencyRoots {
+    /// All root-packages, i.e. the packages no other package depends upon.
+    #[default]
+    All,
+    /// The package currently being built, as identified by `CARGO_PKG_NAME` and
+    /// `CARGO_PKG_VERSION`.
+    CurrentPackage,
+}
+
+/// The dependencies documented by `Cargo.lock`, as resolved by `built`.
+///
+/// This type is only available if `built` was compiled with the
+/// `dependency-tree` feature.
+///
+/// ```no_run
+/// let deps = built::Dependencies::from_lockfile("Cargo.lock".as_ref()).unwrap();
+/// for (name, version) in deps.direct() {
+///     println!("{name} {version}");
+/// }
+/// ```
+#[cfg(feature = "dependency-tree")]
+#[derive(Debug, Clone)]
+pub struct Dependencies {
+    roots: Vec<(String, String)>,
     deps: Vec<(String, String)>,
     direct_deps: Vec<(String, String)>,
     indirect_deps: Vec<(String, String)>,
+    depths: collections::HashMap<(String, String), usize>,
 }
 
 #[cfg(feature = "dependency-tree")]
 impl Dependencies {
-    fn new(lockfile: &cargo_lock::Lockfile) -> Self {
+    /// Parses the lockfile at `path` and resolves its dependency-graph.
+    ///
+    /// # Errors
+    /// If the lockfile can't be read or parsed, or its dependency-graph can't be resolved.
+    pub fn from_lockfile(path: &path::Path) -> io::Result<Self> {
+        let lockfile: cargo_lock::Lockfile = fs::read_to_string(path)?
+            .parse()
+            .map_err(io::Error::other)?;
+        Self::new(&lockfile, None)
+    }
+
+    /// Parses the lockfile at `path` and resolves the dependency-graph of the package
+    /// `name` at `version`, which is treated as the only root-package. This is useful
+    /// if a workspace has multiple root-packages, e.g. several binaries.
+    ///
+    /// If the package is not found, all root-packages are considered, as with
+    /// `from_lockfile()`.
+    ///
+    /// # Errors
+    /// If the lockfile can't be read or parsed, or its dependency-graph can't be resolved.
+    pub fn from_lockfile_for_package(
+        path: &path::Path,
+        name: &str,
+        version: &str,
+    ) -> io::Result<Self> {
+        let lockfile: cargo_lock::Lockfile = fs::read_to_string(path)?
+            .parse()
+            .map_err(io::Error::other)?;
+        Self::new(&lockfile, Some((name, version)))
+    }
+
+    /// The root-package(s), i.e. the packages no other package depends upon.
+    #[must_use]
+    pub fn roots(&self) -> &[(String, String)] {
+        &self.roots
+    }
+
+    /// All dependencies, excluding the root-package(s), as `(name, version)`, sorted by name.
+    #[must_use]
+    pub fn all(&self) -> &[(String, String)] {
+        &self.deps
+    }
+
+    /// The dependencies the root-package(s) directly depend on.
+    #[must_use]
+    pub fn direct(&self) -> &[(String, String)] {
+        &self.direct_deps
+    }
+
+    /// The dependencies which are not direct dependencies.
+    #[must_use]
+    pub fn indirect(&self) -> &[(String, String)] {
+        &self.indirect_deps
+    }
+
+    /// The length of the shortest path from the root-package(s) to the package `name`
+    /// at `version`; `0` for the root-package(s), `1` for direct dependencies.
+    #[must_use]
+    pub fn depth(&self, name: &str, version: &str) -> Option<usize> {
+        self.depths
+            .get(&(name.to_owned(), version.to_owned()))
+            .copied()
+    }
+
+    /// The dependencies in `deps` which are at most `max_depth` away from the
+    /// root-package(s), and the number of those which are not.
+    fn prune(
+        &self,
+        deps: Vec<(String, String)>,
+        max_depth: Option<usize>,
+    ) -> (Vec<(String, String)>, usize) {
+        let Some(max_depth) = max_depth else {
+            return (deps, 0);
+        };
+        let total = deps.len();
+        let deps = deps
+            .into_iter()
+            .filter(|(name, version)| {
+                self.depth(name, version)
+                    .is_some_and(|depth| depth <= max_depth)
+            })
+            .collect::<Vec<_>>();
+        let pruned = total - deps.len();
+        (deps, pruned)
+    }
+
+    fn new(lockfile: &cargo_lock::Lockfile, package: Option<(&str, &str)>) -> io::Result<Self> {
         use cargo_lock::dependency::graph::EdgeDirection;
 
-        let tree = lockfile
-            .dependency_tree()
-            .expect("properly formed lockfile");
+        let tree = lockfile.dependency_tree().map_err(io::Error::other)?;
         let graph = tree.graph();
 
-        let root_pkg_idx = graph
-            .externals(EdgeDirection::Incoming)
-            .collect::<collections::HashSet<_>>();
-        let deps = package_names(graph.node_indices().filter_map(|idx| {
-            if root_pkg_idx.contains(&idx) {
-                None
-            } else {
-                Some(&graph[idx])
+        let package_idx = package.and_then(|(name, version)| {
+            graph.node_indices().find(|idx| {
+                graph[*idx].name.as_str() == name && graph[*idx].version.to_string() == version
+            })
+        });
+        let root_pkg_idx = match package_idx {
+            Some(idx) => collections::HashSet::from([idx]),
+            None => graph
+                .externals(EdgeDirection::Incoming)
+                .collect::<collections::HashSet<_>>(),
+        };
+        let deps_idx = match package_idx {
+            // Only what is reachable from the package itself
+            Some(idx) => {
+                let mut reachable = collections::HashSet::new();
+                let mut stack = vec![idx];
+                while let Some(idx) = stack.pop() {
+                    for dep_idx in graph.neighbors_directed(idx, EdgeDirection::Outgoing) {
+                        if reachable.insert(dep_idx) {
+                            stack.push(dep_idx);
+                        }
+                    }
+                }
+                reachable.remove(&idx);
+                reachable
             }
-        }));
+            None => graph
+                .node_indices()
+                .filter(|idx| !root_pkg_idx.contains(idx))
+                .collect(),
+        };
+        let roots = package_names(root_pkg_idx.iter().map(|idx| &graph[*idx]));
+        let deps = package_names(deps_idx.iter().map(|idx| &graph[*idx]));
         let direct_deps_idx = root_pkg_idx
             .iter()
             .flat_map(|idx| graph.neighbors_directed(*idx, EdgeDirection::Outgoing))
+            .filter(|idx| !root_pkg_idx.contains(idx))
             .collect::<collections::HashSet<_>>();
         let direct_deps = package_names(direct_deps_idx.iter().map(|dep_idx| &graph[*dep_idx]));
-        let indirect_deps = package_names(graph.node_indices().filter_map(|idx| {
-            if root_pkg_idx.contains(&idx) | direct_deps_idx.contains(&idx) {
+        let indirect_deps = package_names(deps_idx.iter().filter_map(|idx| {
+            if direct_deps_idx.contains(idx) {
                 None
             } else {
-                Some(&graph[idx])
+                Some(&graph[*idx])
             }
         }));
 
-        Self {
+        let mut depths = collections::HashMap::new();
+        let mut queue = root_pkg_idx
+            .iter()
+            .map(|idx| (*idx, 0))
+            .collect::<collections::VecDeque<_>>();
+        let mut visited = root_pkg_idx.clone();
+        while let Some((idx, depth)) = queue.pop_front() {
+            depths
+                .entry((graph[idx].name.to_string(), graph[idx].version.to_string()))
+                .or_insert(depth);
+            for dep_idx in graph.neighbors_directed(idx, EdgeDirection::Outgoing) {
+                if visited.insert(dep_idx) {
+                    queue.push_back((dep_idx, depth + 1));
+                }
+            }
+        }
+
+        Ok(Self {
+            roots,
             deps,
             direct_deps,
             indirect_deps,
-        }
+            depths,
+        })
     }
 }
 
-#[cfg(feature = "dependency-tree")]
-pub fn write_dependencies(manifest_location: &path::Path, mut w: &fs::File) -> io::Result<()> {
-    use io::{Read, Write};
-
-    let mut lock_buf = String::new();
-    fs::File::open(find_lockfile(manifest_location)?)?.read_to_string(&mut lock_buf)?;
-    let lockfile = lock_buf.parse().expect("Failed to parse lockfile");
-
-    let dependencies = Dependencies::new(&lockfile);
+/// Writes `deps` as the array `name`, or, if `packed`, as the string `{name}_PACKED`
+/// holding all names and versions back to back, the offsets `{name}_OFFSETS` into it,
+/// and a function `{name}()` iterating over the `(name, version)`-pairs. The packed
+/// representation requires only one relocation instead of two per dependency.
+fn write_dependency_list(
+    mut w: impl crate::report::Backend,
+    name: &str,
+    deps: &[(String, String)],
+    kind: &str,
+    packed: bool,
+) -> io::Result<()> {
+    if !packed {
+        write_variable!(
+            w,
+            name,
+            format_args!("[(&str, &str); {}]", deps.len()),
+            TupleArrayDisplay(deps),
+            format_args!("An array of {kind} dependencies as documented by `Cargo.lock`.")
+        );
+        return Ok(());
+    }
 
+    let mut packed = String::new();
+    let mut offsets = vec![0];
+    for (dep_name, version) in deps {
+        for s in [dep_name, version] {
+            packed.push_str(s);
+            offsets.push(u32::try_from(packed.len()).map_err(io::Error::other)?);
+        }
+    }
+    write_str_variable!(
+        w,
+        format_args!("{name}_PACKED"),
+        packed,
+        format_args!(
+            "The names and versions of the {kind} dependencies, back to back. \
+            Use `{}()` to iterate over them.",
+            name.to_lowercase()
+        )
+    );
     write_variable!(
         w,
-        "DEPENDENCIES",
-        format_args!("[(&str, &str); {}]", dependencies.deps.len()),
-        TupleArrayDisplay(&dependencies.deps),
-        "An array of effective dependencies as documented by `Cargo.lock`."
+        format_args!("{name}_OFFSETS"),
+        format_args!("[u32; {}]", offsets.len()),
+        ArrayDisplay(&offsets, |o, fmt| write!(fmt, "{o}")),
+        format_args!("The byte-offsets of the names and versions in `{name}_PACKED`.")
+    );
+    writeln!(
+        w,
+        "#[doc=r#\"An iterator over the {kind} dependencies as documented by `Cargo.lock`.\"#]
+#[allow(dead_code)]
+pub fn {fn_name}() -> impl ExactSizeIterator<Item = (&'static str, &'static str)> {{
+    {name}_OFFSETS.windows(3).step_by(2).map(|o| {{
+        (
+            &{name}_PACKED[o[0] as usize..o[1] as usize],
+            &{name}_PACKED[o[1] as usize..o[2] as usize],
+        )
+    }})
+}}",
+        fn_name = name.to_lowercase()
+    )
+}
+
+#[cfg(feature = "dependency-tree")]
+pub fn write_dependencies(
+    manifest_location: &path::Path,
+    options: &crate::Options,
+    mut w: impl crate::report::Backend,
+) -> io::Result<()> {
+    let lockfile = find_lockfile(manifest_location)?;
+    let dependencies = match options.dependency_roots {
+        DependencyRoots::All => Dependencies::from_lockfile(&lockfile)?,
+        DependencyRoots::CurrentPackage => Dependencies::from_lockfile_for_package(
+            &lockfile,
+            &std::env::var("CARGO_PKG_NAME").unwrap_or_default(),
+            &std::env::var("CARGO_PKG_VERSION").unwrap_or_default(),
+        )?,
+    };
+    let deps = if options.include_root_packages {
+        let mut deps = [&dependencies.roots[..], &dependencies.deps[..]].concat();
+        deps.sort_unstable();
+        deps
+    } else {
+        dependencies.deps.clone()
+    };
+    let (deps, pruned) = dependencies.prune(
+        filter_dependencies(&deps, options),
+        options.dependency_depth,
+    );
+    let direct_deps = filter_dependencies(&dependencies.direct_deps, options);
+    let (indirect_deps, indirect_pruned) = dependencies.prune(
+        filter_dependencies(&dependencies.indirect_deps, options),
+        options.dependency_depth,
     );
+
+    write_dependency_list(
+        &mut w,
+        "DEPENDENCIES",
+        &deps,
+        "effective",
+        options.packed_dependencies,
+    )?;
     write_str_variable!(
         w,
         "DEPENDENCIES_STR",
-        dependencies
-            .deps
-            .iter()
+        deps.iter()
             .map(|(n, v)| format!("{n} {v}"))
             .collect::<Vec<_>>()
             .join(", "),
         "The effective dependencies as a comma-separated string."
     );
-
     write_variable!(
         w,
-        "DIRECT_DEPENDENCIES",
-        format_args!("[(&str, &str); {}]", dependencies.direct_deps.len()),
-        TupleArrayDisplay(&dependencies.direct_deps),
-        "An array of direct dependencies as documented by `Cargo.lock`."
+        "DEPENDENCIES_COUNT",
+        "usize",
+        deps.len(),
+        "The number of effective dependencies."
     );
+    write_variable!(
+        w,
+        "DEPENDENCIES_PRUNED_COUNT",
+        "usize",
+        pruned,
+        "The number of dependencies omitted from `DEPENDENCIES` because they are \
+        deeper in the dependency-graph than selected by `Options::set_dependency_depth()`."
+    );
+
+    write_dependency_list(
+        &mut w,
+        "DIRECT_DEPENDENCIES",
+        &direct_deps,
+        "direct",
+        options.packed_dependencies,
+    )?;
     write_str_variable!(
         w,
         "DIRECT_DEPENDENCIES_STR",
-        dependencies
-            .direct_deps
+        direct_deps
             .iter()
             .map(|(n, v)| format!("{n} {v}"))
             .collect::<Vec<_>>()
             .join(", "),
         "The direct dependencies as a comma-separated string."
     );
-
     write_variable!(
         w,
-        "INDIRECT_DEPENDENCIES",
-        format_args!("[(&str, &str); {}]", dependencies.indirect_deps.len()),
-        TupleArrayDisplay(&dependencies.indirect_deps),
-        "An array of indirect dependencies as documented by `Cargo.lock`."
+        "DIRECT_DEPENDENCIES_COUNT",
+        "usize",
+        direct_deps.len(),
+        "The number of direct dependencies."
     );
+
+    write_dependency_list(
+        &mut w,
+        "INDIRECT_DEPENDENCIES",
+        &indirect_deps,
+        "indirect",
+        options.packed_dependencies,
+    )?;
     write_str_variable!(
         w,
         "INDIRECT_DEPENDENCIES_STR",
-        dependencies
-            .indirect_deps
+        indirect_deps
             .iter()
             .map(|(n, v)| format!("{n} {v}"))
             .collect::<Vec<_>>()
             .join(", "),
         "The indirect dependencies as a comma-separated string."
     );
+    write_variable!(
+        w,
+        "INDIRECT_DEPENDENCIES_COUNT",
+        "usize",
+        indirect_deps.len(),
+        "The number of indirect dependencies."
+    );
+    write_variable!(
+        w,
+        "INDIRECT_DEPENDENCIES_PRUNED_COUNT",
+        "usize",
+        indirect_pruned,
+        "The number of dependencies omitted from `INDIRECT_DEPENDENCIES` because they \
+        are deeper in the dependency-graph than selected by `Options::set_dependency_depth()`."
+    );
 
     Ok(())
 }
 
 #[cfg(not(feature = "dependency-tree"))]
-pub fn write_dependencies(manifest_location: &path::Path, mut w: &fs::File) -> io::Result<()> {
-    use io::{Read, Write};
+pub fn write_dependencies(
+    manifest_location: &path::Path,
+    options: &crate::Options,
+    mut w: impl crate::report::Backend,
+) -> io::Result<()> {
+    use io::Read;
 
     let mut lock_buf = String::new();
     fs::File::open(find_lockfile(manifest_location)?)?.read_to_string(&mut lock_buf)?;
-    let lockfile: cargo_lock::Lockfile = lock_buf.parse().expect("Failed to parse lockfile");
+    let lockfile: cargo_lock::Lockfile = lock_buf.parse().map_err(io::Error::other)?;
 
-    let deps = package_names(&lockfile.packages);
+    let deps = if options.include_root_packages {
+        package_names(&lockfile.packages)
+    } else {
+        let roots = root_packages(&lockfile);
+        package_names(
+            lockfile
+                .packages
+                .iter()
+                .filter(|package| !roots.contains(package)),
+        )
+    };
+    let deps = filter_dependencies(&deps, options);
 
-    write_variable!(
-        w,
+    write_dependency_list(
+        &mut w,
         "DEPENDENCIES",
-        format_args!("[(&str, &str); {}]", deps.len()),
-        TupleArrayDisplay(&deps),
-        "An array of effective dependencies as documented by `Cargo.lock`."
-    );
+        &deps,
+        "effective",
+        options.packed_dependencies,
+    )?;
     write_str_variable!(
         w,
         "DEPENDENCIES_STR",
@@ -169,7 +506,419 @@ pub fn write_dependencies(manifest_location: &path::Path, mut w: &fs::File) -> i
             .join(", "),
         "The effective dependencies as a comma-separated string."
     );
+    write_variable!(
+        w,
+        "DEPENDENCIES_COUNT",
+        "usize",
+        deps.len(),
+        "The number of effective dependencies."
+    );
+
+    Ok(())
+}
+
+/// The `(name, version, checksum)` of all packages in `lockfile` which carry a checksum.
+fn package_checksums(lockfile: &cargo_lock::Lockfile) -> Vec<(String, String, String)> {
+    let mut checksums = lockfile
+        .packages
+        .iter()
+        .filter_map(|package| {
+            Some((
+                package.name.to_string(),
+                package.version.to_string(),
+                package.checksum.as_ref()?.to_string(),
+            ))
+        })
+        .collect::<Vec<_>>();
+    checksums.sort_unstable();
+    checksums.dedup();
+    checksums
+}
+
+/// The `(name, version, source)` of all packages in `lockfile`. The source is
+/// `crates.io` for the default registry, `path` for local packages and the source's
+/// URL (including the revision for git-sources) otherwise.
+fn package_sources(lockfile: &cargo_lock::Lockfile) -> Vec<(String, String, String)> {
+    let mut sources = lockfile
+        .packages
+        .iter()
+        .map(|package| {
+            let source = match &package.source {
+                None => "path".to_owned(),
+                Some(source) if source.is_default_registry() => "crates.io".to_owned(),
+                Some(source) => source.to_string(),
+            };
+            (
+                package.name.to_string(),
+                package.version.to_string(),
+                source,
+            )
+        })
+        .collect::<Vec<_>>();
+    sources.sort_unstable();
+    sources.dedup();
+    sources
+}
+
+/// The registries from which packages in `lockfile` originate, in the same form as
+/// in `package_sources()`.
+fn registries(lockfile: &cargo_lock::Lockfile) -> Vec<String> {
+    let mut registries = lockfile
+        .packages
+        .iter()
+        .filter_map(|package| package.source.as_ref())
+        .filter(|source| source.is_registry())
+        .map(|source| {
+            if source.is_default_registry() {
+                "crates.io".to_owned()
+            } else {
+                source.to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+    registries.sort_unstable();
+    registries.dedup();
+    registries
+}
+
+/// The names of packages which appear with more than one version in `lockfile`,
+/// and their versions in ascending order.
+fn duplicate_packages(lockfile: &cargo_lock::Lockfile) -> Vec<(String, Vec<String>)> {
+    let mut versions = collections::BTreeMap::<_, Vec<_>>::new();
+    for package in &lockfile.packages {
+        versions
+            .entry(package.name.as_str())
+            .or_default()
+            .push(&package.version);
+    }
+    versions
+        .into_iter()
+        .filter_map(|(name, mut versions)| {
+            versions.sort_unstable();
+            versions.dedup();
+            (versions.len() > 1).then(|| {
+                (
+                    name.to_owned(),
+                    versions.into_iter().map(ToString::to_string).collect(),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Writes the details of the individual packages in `Cargo.lock`.
+pub fn write_packages(
+    manifest_location: &path::Path,
+    mut w: impl crate::report::Backend,
+) -> io::Result<()> {
+    let lockfile: cargo_lock::Lockfile = fs::read_to_string(find_lockfile(manifest_location)?)?
+        .parse()
+        .map_err(io::Error::other)?;
+    let checksums = package_checksums(&lockfile);
+    write_variable!(
+        w,
+        "DEPENDENCIES_CHECKSUMS",
+        format_args!("[(&str, &str, &str); {}]", checksums.len()),
+        ArrayDisplay(&checksums, |(name, version, checksum), fmt| write!(
+            fmt,
+            r#"("{}", "{}", "{}")"#,
+            name.escape_default(),
+            version.escape_default(),
+            checksum.escape_default()
+        )),
+        "The names, versions and SHA-256 checksums of all packages in `Cargo.lock` \
+        which carry a checksum, i.e. those from a registry."
+    );
+
+    let sources = package_sources(&lockfile);
+    write_variable!(
+        w,
+        "DEPENDENCY_SOURCES",
+        format_args!("[(&str, &str, &str); {}]", sources.len()),
+        ArrayDisplay(&sources, |(name, version, source), fmt| write!(
+            fmt,
+            r#"("{}", "{}", "{}")"#,
+            name.escape_default(),
+            version.escape_default(),
+            source.escape_default()
+        )),
+        "The names, versions and sources of all packages in `Cargo.lock`. The source \
+        is `crates.io`, `path` for local packages, or the URL of the registry or \
+        git-repository, the latter including the revision."
+    );
+
+    let registries = registries(&lockfile);
+    write_variable!(
+        w,
+        "REGISTRIES_USED",
+        format_args!("[&str; {}]", registries.len()),
+        ArrayDisplay(&registries, |r, fmt| write!(
+            fmt,
+            "\"{}\"",
+            r.escape_default()
+        )),
+        "The registries from which packages in `Cargo.lock` originate; `crates.io` or \
+        the URL of an alternative registry. See `DEPENDENCY_SOURCES` for the registry \
+        of each package."
+    );
 
+    let duplicates = duplicate_packages(&lockfile);
+    write_variable!(
+        w,
+        "DUPLICATE_DEPENDENCIES",
+        format_args!("[(&str, &[&str]); {}]", duplicates.len()),
+        ArrayDisplay(&duplicates, |(name, versions), fmt| write!(
+            fmt,
+            r#"("{}", &{})"#,
+            name.escape_default(),
+            ArrayDisplay(versions, |v, fmt| write!(fmt, "\"{}\"", v.escape_default()))
+        )),
+        "The names of packages which appear with more than one version in `Cargo.lock`, \
+        and their versions."
+    );
+    Ok(())
+}
+
+fn bom_ref(name: &str, version: &str) -> String {
+    format!("{name}@{version}")
+}
+
+/// Renders the packages in `lockfile` as a CycloneDX JSON-document describing the
+/// package `name` at `version`.
+fn cyclonedx(lockfile: &cargo_lock::Lockfile, name: &str, version: &str) -> String {
+    use fmt::Write;
+
+    let mut packages = lockfile.packages.iter().collect::<Vec<_>>();
+    packages.sort_unstable_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+
+    // Writing to a `String` can't fail
+    let mut doc = String::new();
+    let _ = write!(
+        doc,
+        r#"{{
+  "bomFormat": "CycloneDX",
+  "specVersion": "1.5",
+  "version": 1,
+  "metadata": {{
+    "tools": {{
+      "components": [{{"type": "library", "name": "built", "version": {}}}]
+    }},
+    "component": {{"type": "application", "bom-ref": {}, "name": {}, "version": {}}}
+  }},
+  "components": ["#,
+        JsonStr(env!("CARGO_PKG_VERSION")),
+        JsonStr(&bom_ref(name, version)),
+        JsonStr(name),
+        JsonStr(version),
+    );
+    let components = packages.iter().filter(|package| {
+        !(package.name.as_str() == name && package.version.to_string() == version)
+    });
+    for (idx, package) in components.enumerate() {
+        let (name, version) = (package.name.as_str(), package.version.to_string());
+        let _ = write!(
+            doc,
+            r#"{}
+    {{"type": "library", "bom-ref": {}, "name": {}, "version": {}"#,
+            if idx == 0 { "" } else { "," },
+            JsonStr(&bom_ref(name, &version)),
+            JsonStr(name),
+            JsonStr(&version)
+        );
+        if package
+            .source
+            .as_ref()
+            .is_some_and(cargo_lock::SourceId::is_default_registry)
+        {
+            let purl = format!("pkg:cargo/{name}@{version}");
+            let _ = write!(doc, r#", "purl": {}"#, JsonStr(&purl));
+        }
+        if let Some(checksum) = &package.checksum {
+            let _ = write!(
+                doc,
+                r#", "hashes": [{{"alg": "SHA-256", "content": "{checksum}"}}]"#
+            );
+        }
+        doc.push('}');
+    }
+    doc.push_str("\n  ],\n  \"dependencies\": [");
+    for (idx, package) in packages.iter().enumerate() {
+        let depends_on = package
+            .dependencies
+            .iter()
+            .map(|dep| JsonStr(&bom_ref(dep.name.as_str(), &dep.version.to_string())).to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let _ = write!(
+            doc,
+            r#"{}
+    {{"ref": {}, "dependsOn": [{depends_on}]}}"#,
+            if idx == 0 { "" } else { "," },
+            JsonStr(&bom_ref(
+                package.name.as_str(),
+                &package.version.to_string()
+            )),
+        );
+    }
+    doc.push_str("\n  ]\n}\n");
+    doc
+}
+
+/// An identifier for `name` at `version`, as allowed in SPDX-documents.
+fn spdx_id(name: &str, version: &str) -> String {
+    let id = format!("SPDXRef-Package-{name}-{version}");
+    id.replace(
+        |c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '-',
+        "-",
+    )
+}
+
+/// The creation-time of the SBOM; `SOURCE_DATE_EPOCH` is honored according to
+/// `policy`.
+fn sbom_created(policy: crate::SourceDateEpochPolicy) -> io::Result<String> {
+    let secs =
+        crate::source_date_epoch::get(policy, |ts| u64::try_from(ts).ok())?.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs())
+        });
+    Ok(crate::source_date_epoch::rfc3339(secs))
+}
+
+/// Renders the packages in `lockfile` as an SPDX JSON-document describing the
+/// package `name` at `version`, created at `created`.
+fn spdx(lockfile: &cargo_lock::Lockfile, name: &str, version: &str, created: &str) -> String {
+    use fmt::Write;
+
+    let mut packages = lockfile.packages.iter().collect::<Vec<_>>();
+    packages.sort_unstable_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+
+    // Writing to a `String` can't fail
+    let mut doc = String::new();
+    let namespace = format!("https://spdx.org/spdxdocs/{}", bom_ref(name, version));
+    let _ = write!(
+        doc,
+        r#"{{
+  "spdxVersion": "SPDX-2.3",
+  "dataLicense": "CC0-1.0",
+  "SPDXID": "SPDXRef-DOCUMENT",
+  "name": {},
+  "documentNamespace": {},
+  "creationInfo": {{"created": {}, "creators": [{}]}},
+  "packages": [
+    {{"SPDXID": {}, "name": {}, "versionInfo": {}, "downloadLocation": "NOASSERTION", "filesAnalyzed": false}}"#,
+        JsonStr(&bom_ref(name, version)),
+        JsonStr(&namespace),
+        JsonStr(created),
+        JsonStr(&format!("Tool: built-{}", env!("CARGO_PKG_VERSION"))),
+        JsonStr(&spdx_id(name, version)),
+        JsonStr(name),
+        JsonStr(version),
+    );
+    let components = packages.iter().filter(|package| {
+        !(package.name.as_str() == name && package.version.to_string() == version)
+    });
+    for package in components {
+        let (name, version) = (package.name.as_str(), package.version.to_string());
+        let from_registry = package
+            .source
+            .as_ref()
+            .is_some_and(cargo_lock::SourceId::is_default_registry);
+        let download_location = if from_registry {
+            format!("https://crates.io/api/v1/crates/{name}/{version}/download")
+        } else {
+            "NOASSERTION".to_owned()
+        };
+        let _ = write!(
+            doc,
+            r#",
+    {{"SPDXID": {}, "name": {}, "versionInfo": {}, "downloadLocation": {}, "filesAnalyzed": false"#,
+            JsonStr(&spdx_id(name, &version)),
+            JsonStr(name),
+            JsonStr(&version),
+            JsonStr(&download_location)
+        );
+        if let Some(checksum) = &package.checksum {
+            let _ = write!(
+                doc,
+                r#", "checksums": [{{"algorithm": "SHA256", "checksumValue": "{checksum}"}}]"#
+            );
+        }
+        if from_registry {
+            let purl = format!("pkg:cargo/{name}@{version}");
+            let _ = write!(
+                doc,
+                r#", "externalRefs": [{{"referenceCategory": "PACKAGE-MANAGER", "referenceType": "purl", "referenceLocator": {}}}]"#,
+                JsonStr(&purl)
+            );
+        }
+        doc.push('}');
+    }
+    let _ = write!(
+        doc,
+        r#"
+  ],
+  "relationships": [
+    {{"spdxElementId": "SPDXRef-DOCUMENT", "relationshipType": "DESCRIBES", "relatedSpdxElement": {}}}"#,
+        JsonStr(&spdx_id(name, version))
+    );
+    for package in &packages {
+        let id = spdx_id(package.name.as_str(), &package.version.to_string());
+        for dep in &package.dependencies {
+            let _ = write!(
+                doc,
+                r#",
+    {{"spdxElementId": {}, "relationshipType": "DEPENDS_ON", "relatedSpdxElement": {}}}"#,
+                JsonStr(&id),
+                JsonStr(&spdx_id(dep.name.as_str(), &dep.version.to_string()))
+            );
+        }
+    }
+    doc.push_str("\n  ]\n}\n");
+    doc
+}
+
+/// Writes the software bill of materials as selected by `options`; the output-path
+/// is relative to `out_dir`. The file is not written if `out_dir` is `None`.
+pub fn write_sbom(
+    manifest_location: &path::Path,
+    options: &crate::Options,
+    out_dir: Option<&path::Path>,
+    mut w: impl crate::report::Backend,
+) -> io::Result<()> {
+    if options.sbom_output.is_none() && !options.embed_sbom {
+        return Ok(());
+    }
+    let lockfile: cargo_lock::Lockfile = fs::read_to_string(find_lockfile(manifest_location)?)?
+        .parse()
+        .map_err(io::Error::other)?;
+    let name = std::env::var("CARGO_PKG_NAME").unwrap_or_default();
+    let version = std::env::var("CARGO_PKG_VERSION").unwrap_or_default();
+    let format = options
+        .sbom_output
+        .as_ref()
+        .map_or(SbomFormat::CycloneDx, |(_, format)| *format);
+    let doc = match format {
+        SbomFormat::CycloneDx => cyclonedx(&lockfile, &name, &version),
+        SbomFormat::Spdx => spdx(
+            &lockfile,
+            &name,
+            &version,
+            &sbom_created(options.source_date_epoch_policy())?,
+        ),
+    };
+
+    if let (Some((path, _)), Some(out_dir)) = (&options.sbom_output, out_dir) {
+        fs::write(out_dir.join(path), &doc)?;
+    }
+    if options.embed_sbom {
+        write_str_variable!(
+            w,
+            "SBOM_JSON",
+            doc,
+            "The software bill of materials, as a CycloneDX- or SPDX-JSON-document."
+        );
+    }
     Ok(())
 }
 
@@ -242,11 +991,44 @@ dependencies = [
         );
     }
 
+    #[test]
+    #[cfg(not(feature = "dependency-tree"))]
+    fn root_packages() {
+        let lockfile: cargo_lock::Lockfile =
+            LOCK_TOML_BUFFER.parse().expect("Failed to parse lockfile");
+        let roots = super::root_packages(&lockfile);
+        assert_eq!(
+            super::package_names(roots),
+            [("dummy".to_owned(), "0.1.0".to_owned())]
+        );
+    }
+
+    #[test]
+    fn include_root_packages() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("Cargo.lock"), LOCK_TOML_BUFFER).unwrap();
+        let mut options = crate::Options::default();
+        for include in [false, true] {
+            options.set_include_root_packages(include);
+            let mut built = crate::report::Generated::default();
+            super::write_dependencies(root.path(), &options, &mut built).unwrap();
+            let deps = built
+                .constants()
+                .find(|constant| constant.name == "DEPENDENCIES")
+                .unwrap();
+            assert_eq!(deps.value.contains(r#"("dummy", "0.1.0")"#), include);
+        }
+    }
+
     #[test]
     #[cfg(feature = "dependency-tree")]
     fn direct_deps() {
         let lockfile = LOCK_TOML_BUFFER.parse().expect("Failed to parse lockfile");
-        let dependencies = super::Dependencies::new(&lockfile);
+        let dependencies = super::Dependencies::new(&lockfile, None).unwrap();
+        assert_eq!(
+            dependencies.roots,
+            [("dummy".to_owned(), "0.1.0".to_owned())]
+        );
         assert_eq!(
             dependencies.deps,
             [
@@ -273,4 +1055,263 @@ dependencies = [
             ]
         );
     }
+
+    #[test]
+    #[cfg(feature = "dependency-tree")]
+    fn multi_root_workspace() {
+        let lockfile: cargo_lock::Lockfile = format!(
+            "{LOCK_TOML_BUFFER}
+[[package]]
+name = \"other-bin\"
+version = \"0.2.0\"
+dependencies = [
+ \"foobar\",
+]
+"
+        )
+        .parse()
+        .expect("Failed to parse lockfile");
+
+        let dependencies = super::Dependencies::new(&lockfile, None).unwrap();
+        assert_eq!(dependencies.roots.len(), 2);
+        assert_eq!(dependencies.direct_deps.len(), 3);
+
+        let dependencies =
+            super::Dependencies::new(&lockfile, Some(("other-bin", "0.2.0"))).unwrap();
+        assert_eq!(
+            dependencies.roots,
+            [("other-bin".to_owned(), "0.2.0".to_owned())]
+        );
+        assert_eq!(
+            dependencies.deps,
+            [("foobar".to_owned(), "0.0.0".to_owned())]
+        );
+        assert_eq!(dependencies.deps, dependencies.direct_deps);
+        assert!(dependencies.indirect_deps.is_empty());
+
+        let dependencies = super::Dependencies::new(&lockfile, Some(("dummy", "0.1.0"))).unwrap();
+        assert_eq!(dependencies.deps.len(), 5);
+        assert_eq!(
+            dependencies.direct_deps,
+            [
+                ("foo".to_owned(), "0.0.0".to_owned()),
+                ("foobar".to_owned(), "0.0.0".to_owned()),
+                ("nom".to_owned(), "7.1.3".to_owned()),
+            ]
+        );
+        assert_eq!(dependencies.indirect_deps.len(), 2);
+
+        // Unknown packages fall back to all roots
+        let dependencies = super::Dependencies::new(&lockfile, Some(("dummy", "9.9.9"))).unwrap();
+        assert_eq!(dependencies.roots.len(), 2);
+    }
+
+    #[test]
+    fn write_dependency_list() {
+        let deps = [
+            ("libc".to_owned(), "0.2.1".to_owned()),
+            ("serde".to_owned(), "1.0.0".to_owned()),
+        ];
+        let mut packed = Vec::new();
+        super::write_dependency_list(&mut packed, "DEPENDENCIES", &deps, "effective", true)
+            .unwrap();
+        let packed = String::from_utf8(packed).unwrap();
+        assert!(packed.contains("pub static DEPENDENCIES_PACKED: &str = \"libc0.2.1serde1.0.0\";"));
+        assert!(packed.contains("pub static DEPENDENCIES_OFFSETS: [u32; 5] = [0, 4, 9, 14, 19];"));
+        assert!(packed.contains("pub fn dependencies() -> impl ExactSizeIterator"));
+
+        let mut unpacked = Vec::new();
+        super::write_dependency_list(&mut unpacked, "DEPENDENCIES", &deps, "effective", false)
+            .unwrap();
+        assert!(String::from_utf8(unpacked).unwrap().contains(
+            "pub static DEPENDENCIES: [(&str, &str); 2] = [(\"libc\", \"0.2.1\"), (\"serde\", \"1.0.0\")];"
+        ));
+    }
+
+    #[test]
+    fn filter_dependencies() {
+        let deps = [
+            "libc",
+            "serde",
+            "serde_derive",
+            "windows-sys",
+            "windows_x86_64_msvc",
+        ]
+        .map(|name| (name.to_owned(), "1.0.0".to_owned()));
+        let names = |options: &crate::Options| {
+            super::filter_dependencies(&deps, options)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+
+        let mut options = crate::Options::default();
+        assert_eq!(names(&options).len(), deps.len());
+        options.set_dependency_exclude(["windows*"]);
+        assert_eq!(names(&options), ["libc", "serde", "serde_derive"]);
+        options.set_dependency_include(["serde*", "windows-sys"]);
+        assert_eq!(names(&options), ["serde", "serde_derive"]);
+    }
+
+    #[test]
+    fn cyclonedx() {
+        let lockfile: cargo_lock::Lockfile =
+            LOCK_TOML_BUFFER.parse().expect("Failed to parse lockfile");
+        let doc = super::cyclonedx(&lockfile, "dummy", "0.1.0");
+        assert!(doc.starts_with("{\n  \"bomFormat\": \"CycloneDX\","));
+        assert!(doc.contains(
+            r#"{"type": "application", "bom-ref": "dummy@0.1.0", "name": "dummy", "version": "0.1.0"}"#
+        ));
+        assert!(doc.contains(
+            r#"{"type": "library", "bom-ref": "nom@7.1.3", "name": "nom", "version": "7.1.3", "purl": "pkg:cargo/nom@7.1.3", "hashes": [{"alg": "SHA-256", "content": "d273983c5a657a70a3e8f2a01329822f3b8c8172b73826411a55751e404a0a4a"}]}"#
+        ));
+        assert!(!doc.contains(r#""type": "library", "bom-ref": "dummy@0.1.0""#));
+        assert!(doc.contains(
+            r#"{"ref": "nom@7.1.3", "dependsOn": ["memchr@2.6.3", "minimal-lexical@0.2.1"]}"#
+        ));
+        assert!(doc.contains(r#"{"ref": "foo@0.0.0", "dependsOn": []}"#));
+        assert_eq!(doc.matches("\"bom-ref\"").count(), 6);
+    }
+
+    #[test]
+    fn package_checksums() {
+        let lockfile: cargo_lock::Lockfile =
+            LOCK_TOML_BUFFER.parse().expect("Failed to parse lockfile");
+        let checksums = super::package_checksums(&lockfile);
+        assert_eq!(checksums.len(), 5);
+        assert!(checksums.iter().all(|(name, _, _)| name != "dummy"));
+        assert_eq!(
+            checksums[4],
+            (
+                "nom".to_owned(),
+                "7.1.3".to_owned(),
+                "d273983c5a657a70a3e8f2a01329822f3b8c8172b73826411a55751e404a0a4a".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn package_sources() {
+        let lockfile: cargo_lock::Lockfile = format!(
+            r#"{LOCK_TOML_BUFFER}
+[[package]]
+name = "bar"
+version = "0.3.0"
+source = "git+https://example.com/bar.git?branch=main#0123456789abcdef0123456789abcdef01234567"
+"#
+        )
+        .parse()
+        .expect("Failed to parse lockfile");
+        let sources = super::package_sources(&lockfile);
+        assert_eq!(sources.len(), 7);
+        let source = |name| {
+            sources
+                .iter()
+                .find(|(n, _, _)| n == name)
+                .map(|(_, _, s)| s.as_str())
+        };
+        assert_eq!(
+            source("bar"),
+            Some("git+https://example.com/bar.git?branch=main#0123456789abcdef0123456789abcdef01234567")
+        );
+        assert_eq!(source("dummy"), Some("path"));
+        assert_eq!(source("nom"), Some("crates.io"));
+    }
+
+    #[test]
+    fn registries() {
+        let lockfile: cargo_lock::Lockfile = format!(
+            r#"{LOCK_TOML_BUFFER}
+[[package]]
+name = "bar"
+version = "0.3.0"
+source = "sparse+https://registry.example.com/index/"
+
+[[package]]
+name = "baz"
+version = "0.1.0"
+source = "git+https://example.com/baz.git#0123456789abcdef0123456789abcdef01234567"
+"#
+        )
+        .parse()
+        .expect("Failed to parse lockfile");
+        assert_eq!(
+            super::registries(&lockfile),
+            ["crates.io", "sparse+https://registry.example.com/index/"]
+        );
+    }
+
+    #[test]
+    fn duplicate_packages() {
+        let lockfile: cargo_lock::Lockfile = format!(
+            r#"{LOCK_TOML_BUFFER}
+[[package]]
+name = "memchr"
+version = "2.10.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#
+        )
+        .parse()
+        .expect("Failed to parse lockfile");
+        assert_eq!(
+            super::duplicate_packages(&lockfile),
+            [(
+                "memchr".to_owned(),
+                vec!["2.6.3".to_owned(), "2.10.0".to_owned()]
+            )]
+        );
+        let lockfile: cargo_lock::Lockfile =
+            LOCK_TOML_BUFFER.parse().expect("Failed to parse lockfile");
+        assert!(super::duplicate_packages(&lockfile).is_empty());
+    }
+
+    #[test]
+    fn spdx() {
+        let lockfile: cargo_lock::Lockfile =
+            LOCK_TOML_BUFFER.parse().expect("Failed to parse lockfile");
+        let doc = super::spdx(&lockfile, "dummy", "0.1.0", "2024-02-29T23:59:59Z");
+        assert!(doc.starts_with("{\n  \"spdxVersion\": \"SPDX-2.3\","));
+        assert!(doc.contains(r#""creationInfo": {"created": "2024-02-29T23:59:59Z", "#));
+        assert!(doc.contains(
+            r#"{"SPDXID": "SPDXRef-Package-nom-7.1.3", "name": "nom", "versionInfo": "7.1.3", "downloadLocation": "https://crates.io/api/v1/crates/nom/7.1.3/download", "filesAnalyzed": false, "checksums": [{"algorithm": "SHA256", "checksumValue": "d273983c5a657a70a3e8f2a01329822f3b8c8172b73826411a55751e404a0a4a"}], "externalRefs": [{"referenceCategory": "PACKAGE-MANAGER", "referenceType": "purl", "referenceLocator": "pkg:cargo/nom@7.1.3"}]}"#
+        ));
+        assert!(doc.contains(
+            r#"{"spdxElementId": "SPDXRef-DOCUMENT", "relationshipType": "DESCRIBES", "relatedSpdxElement": "SPDXRef-Package-dummy-0.1.0"}"#
+        ));
+        assert!(doc.contains(
+            r#"{"spdxElementId": "SPDXRef-Package-nom-7.1.3", "relationshipType": "DEPENDS_ON", "relatedSpdxElement": "SPDXRef-Package-memchr-2.6.3"}"#
+        ));
+        assert_eq!(doc.matches("\"SPDXID\": \"SPDXRef-Package-").count(), 6);
+    }
+
+    #[test]
+    #[cfg(feature = "dependency-tree")]
+    fn from_lockfile() {
+        let root = tempfile::tempdir().unwrap();
+        let lockfile = root.path().join("Cargo.lock");
+        assert!(super::Dependencies::from_lockfile(&lockfile).is_err());
+        // Parses, but refers to a package which is not in the lockfile
+        std::fs::write(
+            &lockfile,
+            "version = 3\n\n[[package]]\nname = \"a\"\nversion = \"0.1.0\"\ndependencies = [\"b 0.2.0\"]\n",
+        )
+        .unwrap();
+        assert!(super::Dependencies::from_lockfile(&lockfile).is_err());
+        std::fs::write(&lockfile, LOCK_TOML_BUFFER).unwrap();
+        let dependencies = super::Dependencies::from_lockfile(&lockfile).unwrap();
+        assert_eq!(dependencies.all().len(), 5);
+        assert_eq!(dependencies.direct().len(), 3);
+        assert_eq!(
+            dependencies.indirect(),
+            [
+                ("memchr".to_owned(), "2.6.3".to_owned()),
+                ("minimal-lexical".to_owned(), "0.2.1".to_owned()),
+            ]
+        );
+        assert_eq!(dependencies.depth("memchr", "2.6.3"), Some(2));
+        assert_eq!(dependencies.depth("memchr", "0.0.0"), None);
+        let (pruned, count) = dependencies.prune(dependencies.all().to_vec(), Some(1));
+        assert_eq!(pruned, dependencies.direct());
+        assert_eq!(count, 2);
+    }
 }
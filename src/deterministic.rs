@@ -0,0 +1,192 @@
+use std::io;
+
+/// How the value of a constant which depends on the machine or the user compiling the
+/// crate is made deterministic.
+enum Normalization {
+    /// Replace the value by the given Rust-expression.
+    Pin(&'static str),
+    /// Keep only the file name of the path; `None` stays `None`.
+    FileName,
+    /// Replace the `Option`-value by `None`.
+    Omit,
+    /// Keep the value, failing if it contains an absolute path.
+    RejectPaths,
+}
+
+/// The constants which are not deterministic and how they are normalized.
+static NORMALIZED: [(&str, Normalization); 15] = [
+    ("HOST", Normalization::Pin("\"unknown\"")),
+    ("NUM_JOBS", Normalization::Pin("1")),
+    ("RUSTC", Normalization::FileName),
+    ("RUSTDOC", Normalization::FileName),
+    ("LINKER", Normalization::FileName),
+    ("BUILD_HOST", Normalization::Omit),
+    ("BUILD_USER", Normalization::Omit),
+    ("BUILD_HOST_OS", Normalization::Omit),
+    ("WORKSPACE_ROOT", Normalization::Omit),
+    ("BUILT_SIGNATURE", Normalization::FileName),
+    ("CI_BUILD_NUMBER", Normalization::Omit),
+    ("CI_BUILD_URL", Normalization::Omit),
+    ("CI_PULL_REQUEST", Normalization::Omit),
+    ("CI_PIPELINE_ID", Normalization::Omit),
+    ("RUSTFLAGS", Normalization::RejectPaths),
+];
+
+/// Fails if the build can't produce a deterministic `built.rs`, i.e. if
+/// `SOURCE_DATE_EPOCH` is not set, `collection_stats` are requested or environment
+/// variables are captured.
+pub fn check(
+    source_date_epoch: Option<&str>,
+    collection_stats: bool,
+    captured_env_vars: &[String],
+) -> io::Result<()> {
+    if source_date_epoch.is_none() {
+        return Err(io::Error::other(
+            "deterministic output requires `SOURCE_DATE_EPOCH` to be set",
+        ));
+    }
+    if collection_stats {
+        return Err(io::Error::other(
+            "deterministic output is incompatible with `Options::set_collection_stats()`, \
+            as the timings vary from build to build",
+        ));
+    }
+    if !captured_env_vars.is_empty() {
+        return Err(io::Error::other(
+            "deterministic output is incompatible with `Options::set_captured_env_vars()`, \
+            as the environment varies from build to build",
+        ));
+    }
+    Ok(())
+}
+
+/// The last component of the path in the string-literal `value`.
+fn file_name(name: &str, value: &str) -> io::Result<String> {
+    let path = crate::json::unquote(value)
+        .ok_or_else(|| io::Error::other(format!("`{name}` can't be made deterministic")))?;
+    let file_name = path.rsplit(['/', '\\']).next().unwrap_or_default();
+    Ok(format!("\"{}\"", file_name.escape_default()))
+}
+
+/// Whether `path` is an absolute path, on any platform.
+fn is_absolute(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    path.starts_with(['/', '\\'])
+        || (bytes.first().is_some_and(u8::is_ascii_alphabetic)
+            && bytes.get(1) == Some(&b':')
+            && matches!(bytes.get(2), Some(b'/' | b'\\')))
+}
+
+/// Fails if the flags in the string-literal `value` contain an absolute path, e.g.
+/// `-L /usr/lib` or `--remap-path-prefix=/build/foo=.`.
+fn reject_paths(name: &str, value: &str) -> io::Result<()> {
+    let flags = crate::json::unquote(value)
+        .ok_or_else(|| io::Error::other(format!("`{name}` can't be made deterministic")))?;
+    match flags
+        .split_whitespace()
+        .flat_map(|flag| flag.split(['=', ',']))
+        .find(|part| is_absolute(part.strip_prefix("-L").unwrap_or(part)))
+    {
+        Some(path) => Err(io::Error::other(format!(
+            "`{name}` contains the absolute path `{path}` and can't be made deterministic"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Replaces the values of the `constants` which depend on the machine or the user
+/// compiling the crate by deterministic ones.
+pub(crate) fn apply<'a>(
+    constants: impl IntoIterator<Item = &'a mut crate::report::Constant>,
+) -> io::Result<()> {
+    for constant in constants {
+        let Some((name, normalization)) =
+            NORMALIZED.iter().find(|(name, _)| *name == constant.name)
+        else {
+            continue;
+        };
+        constant.value = match normalization {
+            Normalization::Pin(value) => (*value).to_owned(),
+            Normalization::Omit => "None".to_owned(),
+            Normalization::RejectPaths => {
+                reject_paths(name, &constant.value)?;
+                continue;
+            }
+            Normalization::FileName => match constant.value.strip_prefix("Some(") {
+                Some(inner) => format!(
+                    "Some({})",
+                    file_name(name, inner.strip_suffix(')').unwrap_or(inner))?
+                ),
+                None if constant.value == "None" => "None".to_owned(),
+                None => file_name(name, &constant.value)?,
+            },
+        };
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn apply() {
+        let constant = |name: &str, value: &str| crate::report::Constant {
+            name: name.to_owned(),
+            datatype: String::new(),
+            value: value.to_owned(),
+            doc: String::new(),
+        };
+        let mut constants = [
+            constant("HOST", r#""x86_64-unknown-linux-gnu""#),
+            constant(
+                "RUSTC",
+                r#""/home/joe/.rustup/toolchains/stable/bin/rustc""#,
+            ),
+            constant("LINKER", r#"Some("C:\\tools\\lld.exe")"#),
+            constant("RUSTDOC", "None"),
+            constant("NUM_JOBS", "16"),
+            constant("BUILD_USER", r#"Some("joe")"#),
+            constant("PKG_NAME", r#""foo""#),
+        ];
+        super::apply(&mut constants).unwrap();
+        assert_eq!(
+            constants.map(|constant| constant.value),
+            [
+                r#""unknown""#,
+                r#""rustc""#,
+                r#"Some("lld.exe")"#,
+                "None",
+                "1",
+                "None",
+                r#""foo""#
+            ]
+        );
+        assert!(super::apply(&mut [constant("RUSTC", "rustc")]).is_err());
+    }
+
+    #[test]
+    fn rustflags() {
+        let rustflags = |value: &str| {
+            super::apply(&mut [crate::report::Constant {
+                name: "RUSTFLAGS".to_owned(),
+                datatype: "&str".to_owned(),
+                value: format!("\"{}\"", value.escape_default()),
+                doc: String::new(),
+            }])
+        };
+        assert!(rustflags("").is_ok());
+        assert!(rustflags("-C target-cpu=native -L vendor/lib --cfg foo").is_ok());
+        assert!(rustflags("-L /usr/local/lib").is_err());
+        assert!(rustflags("-Lnative=/usr/local/lib").is_err());
+        assert!(rustflags("--remap-path-prefix=/build/a1b2c3=.").is_err());
+        assert!(rustflags("-C link-args=-Wl,-rpath,/opt/lib").is_err());
+        assert!(rustflags(r"-L C:\tools\lib").is_err());
+    }
+
+    #[test]
+    fn check() {
+        assert!(super::check(Some("1716639359"), false, &[]).is_ok());
+        assert!(super::check(None, false, &[]).is_err());
+        assert!(super::check(Some("1716639359"), true, &[]).is_err());
+        assert!(super::check(Some("1716639359"), false, &["USER".to_owned()]).is_err());
+    }
+}
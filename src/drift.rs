@@ -0,0 +1,126 @@
+use std::{fs, io, path};
+
+/// The constants whose change between two builds is reported.
+static TRACKED: [&str; 15] = [
+    "BUILT_VERSION",
+    "PKG_VERSION",
+    "RUSTC_VERSION",
+    "RUSTDOC_VERSION",
+    "CARGO_VERSION",
+    "HOST",
+    "TARGET",
+    "PROFILE",
+    "OPT_LEVEL",
+    "DEBUG",
+    "FEATURES",
+    "DEPENDENCIES",
+    "GIT_HEAD_REF",
+    "GIT_COMMIT_HASH",
+    "GIT_DIRTY",
+];
+
+/// Values longer than this are reported as changed, but not printed.
+static MAX_PRINTED_LEN: usize = 60;
+
+/// The tracked constants and their values, as declared in the content of a `built.rs`.
+fn snapshot(built: &str) -> Vec<(&str, &str)> {
+    crate::json::items(built)
+        .filter(|item| TRACKED.contains(&item.name))
+        .map(|item| (item.name, item.value))
+        .collect()
+}
+
+/// Describes the values which changed between the snapshot `old` and `new`. Values
+/// which are only present in one of them are not reported, as they are due to a
+/// change in configuration rather than in the environment.
+fn changes(old: &str, new: &[(&str, &str)]) -> Vec<String> {
+    let old = old
+        .lines()
+        .filter_map(|line| line.split_once(" = "))
+        .collect::<Vec<_>>();
+    new.iter()
+        .filter_map(|(name, new_value)| {
+            let (_, old_value) = old.iter().find(|(n, _)| n == name)?;
+            if old_value == new_value {
+                None
+            } else if old_value.len() <= MAX_PRINTED_LEN && new_value.len() <= MAX_PRINTED_LEN {
+                Some(format!(
+                    "`{name}` changed since the last build: {old_value} -> {new_value}"
+                ))
+            } else {
+                Some(format!("`{name}` changed since the last build"))
+            }
+        })
+        .collect()
+}
+
+/// Where the snapshot for `pkg_name` is kept, given the `out_dir` of its
+/// build-script. The snapshot is kept next to the build-scripts' directories, as
+/// Cargo uses a new `OUT_DIR` if e.g. features or the toolchain change.
+fn snapshot_path(out_dir: &path::Path, pkg_name: &str) -> path::PathBuf {
+    let build_dir = out_dir
+        .parent()
+        .and_then(path::Path::parent)
+        .unwrap_or(out_dir);
+    build_dir.join(format!("built-{pkg_name}.snapshot"))
+}
+
+/// Emits a `cargo:warning` for every tracked value in `built` which changed since
+/// the previous build, and persists the values for the next build.
+pub fn check(built: &str, out_dir: &path::Path) -> io::Result<()> {
+    let pkg_name = crate::json::items(built)
+        .find(|item| item.name == "PKG_NAME")
+        .map_or("unknown", |item| item.value.trim_matches('"'));
+    let path = snapshot_path(out_dir, pkg_name);
+    let new = snapshot(built);
+    match fs::read_to_string(&path) {
+        Ok(old) => {
+            for change in changes(&old, &new) {
+                crate::self_check::warn(change);
+            }
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    let content = new
+        .iter()
+        .map(|(name, value)| format!("{name} = {value}\n"))
+        .collect::<String>();
+    fs::write(path, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path;
+
+    #[test]
+    fn changes() {
+        let old =
+            "RUSTC_VERSION = \"rustc 1.80.0\"\nGIT_DIRTY = Some(false)\nPROFILE = \"debug\"\n";
+        let long = format!("[{}]", "(\"a\", \"0.1.0\"), ".repeat(10));
+        let new = [
+            ("RUSTC_VERSION", "\"rustc 1.81.0\""),
+            ("GIT_DIRTY", "Some(false)"),
+            ("PROFILE", long.as_str()),
+            ("FEATURES", "[]"),
+        ];
+        assert_eq!(
+            super::changes(old, &new),
+            [
+                "`RUSTC_VERSION` changed since the last build: \"rustc 1.80.0\" -> \"rustc 1.81.0\"",
+                "`PROFILE` changed since the last build",
+            ]
+        );
+    }
+
+    #[test]
+    fn snapshot_path() {
+        assert_eq!(
+            super::snapshot_path(
+                path::Path::new("/target/debug/build/foo-0123456789abcdef/out"),
+                "foo"
+            ),
+            path::Path::new("/target/debug/build/built-foo.snapshot")
+        );
+    }
+}
@@ -1,32 +1,403 @@
 use crate::util::ArrayDisplay;
 use crate::{fmt_option_str, write_str_variable, write_variable};
-use std::{collections, env, ffi, fmt, fs, io, process};
+use std::{borrow, collections, env, ffi, fmt, fs, io, path, process, thread, time};
 
 pub struct EnvironmentMap(collections::HashMap<String, String>);
 
-fn get_version_from_cmd(executable: &ffi::OsStr) -> io::Result<String> {
-    let output = process::Command::new(executable).arg("-V").output()?;
-    let mut v = String::from_utf8(output.stdout).unwrap();
-    v.pop(); // remove newline
-    Ok(v)
+/// The x86-64 microarchitecture levels and the target features they require
+/// on top of the previous level.
+static X86_64_LEVELS: [(&str, &[&str]); 3] = [
+    (
+        "x86-64-v2",
+        &[
+            "cmpxchg16b",
+            "lahfsahf",
+            "popcnt",
+            "sse3",
+            "sse4.1",
+            "sse4.2",
+            "ssse3",
+        ],
+    ),
+    (
+        "x86-64-v3",
+        &[
+            "avx", "avx2", "bmi1", "bmi2", "f16c", "fma", "lzcnt", "movbe", "xsave",
+        ],
+    ),
+    (
+        "x86-64-v4",
+        &["avx512bw", "avx512cd", "avx512dq", "avx512f", "avx512vl"],
+    ),
+];
+
+/// Determine the CPU dispatch level implied by the enabled target features.
+fn cpu_dispatch_level<'a>(
+    arch: &str,
+    target_features: impl IntoIterator<Item = &'a str>,
+) -> Option<&'static str> {
+    if arch != "x86_64" {
+        return None;
+    }
+    let target_features = target_features
+        .into_iter()
+        .collect::<collections::HashSet<_>>();
+    let mut level = "x86-64";
+    for (name, required) in &X86_64_LEVELS {
+        if !required.iter().all(|f| target_features.contains(f)) {
+            break;
+        }
+        level = name;
+    }
+    Some(level)
+}
+
+/// The variant of the generated `OptLevel`-enum and the numeric level corresponding
+/// to the value of `OPT_LEVEL`.
+fn parse_opt_level(opt_level: &str) -> (&'static str, Option<u8>) {
+    match opt_level {
+        "0" => ("O0", Some(0)),
+        "1" => ("O1", Some(1)),
+        "2" => ("O2", Some(2)),
+        "3" => ("O3", Some(3)),
+        "s" => ("Size", None),
+        "z" => ("MinSize", None),
+        _ => ("Other", None),
+    }
+}
+
+#[cfg(feature = "manifest")]
+use crate::manifest::DeclaredManifest;
+
+/// Without the `manifest`-feature, nothing is known about the manifest beyond what
+/// Cargo provides by means of the environment.
+#[cfg(not(feature = "manifest"))]
+#[derive(Debug, Default)]
+struct DeclaredManifest;
+
+#[cfg(not(feature = "manifest"))]
+impl DeclaredManifest {
+    fn load(_manifest_path: &path::Path) -> Self {
+        Self
+    }
+
+    fn declared_str(&self, _key: &str) -> Option<String> {
+        None
+    }
+
+    fn declared_array(&self, _key: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn profile_value(&self, _profile: &str, _key: &str) -> Option<String> {
+        None
+    }
+}
+
+/// The value of the codegen-option `key` in `flags`, given as `-C key=value`,
+/// `-Ckey=value` or `--codegen key=value`; like `rustc`, the last one takes precedence.
+/// An option given without a value is `true`.
+fn codegen_option<'a>(flags: &[&'a str], key: &str) -> Option<&'a str> {
+    let mut value = None;
+    let mut flags = flags.iter();
+    while let Some(flag) = flags.next() {
+        let option = match *flag {
+            "-C" | "--codegen" => flags.next().copied(),
+            flag => flag
+                .strip_prefix("-C")
+                .or_else(|| flag.strip_prefix("--codegen=")),
+        };
+        match option.map(|o| o.split_once('=').unwrap_or((o, "true"))) {
+            Some((k, v)) if k == key => value = Some(v),
+            _ => {}
+        }
+    }
+    value
+}
+
+/// The authors given by the colon-separated `CARGO_PKG_AUTHORS`. As authors may
+/// contain colons themselves, they are taken from the `manifest` if they correspond
+/// to `CARGO_PKG_AUTHORS`; otherwise, `CARGO_PKG_AUTHORS` is split on every colon.
+fn authors_list(pkg_authors: &str, manifest: &DeclaredManifest) -> Vec<String> {
+    let declared = manifest.declared_array("authors");
+    if !declared.is_empty() && declared.join(":") == pkg_authors {
+        return declared;
+    }
+    pkg_authors
+        .split(':')
+        .filter(|author| !author.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+/// The names Windows reserves for devices, which can only be used as part of a
+/// path if the path has a verbatim-prefix.
+static RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Removes the verbatim-prefix from a Windows-path like `\\?\C:\foo` or
+/// `\\?\UNC\server\share\foo`, as produced by e.g. `fs::canonicalize()`. The
+/// prefix is kept if the path depends on it, e.g. because it is too long or has
+/// components which are not valid otherwise. Other paths are returned unchanged.
+pub(crate) fn simplify_path(path: &str) -> borrow::Cow<'_, str> {
+    let simplified = if let Some(unc) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{unc}")
+    } else if let Some(local) = path
+        .strip_prefix(r"\\?\")
+        .filter(|p| p.as_bytes().get(1..3) == Some(b":\\"))
+    {
+        local.to_owned()
+    } else {
+        return borrow::Cow::Borrowed(path);
+    };
+    let valid_component = |c: &str| {
+        let stem = c.split('.').next().unwrap_or_default();
+        c != "."
+            && c != ".."
+            && !c.ends_with(['.', ' '])
+            && !RESERVED_NAMES
+                .iter()
+                .any(|r| r.eq_ignore_ascii_case(stem.trim_end()))
+    };
+    if simplified.len() < 260
+        && !simplified.contains('/')
+        && simplified
+            .trim_start_matches('\\')
+            .split('\\')
+            .skip(1)
+            .filter(|c| !c.is_empty())
+            .all(valid_component)
+    {
+        borrow::Cow::Owned(simplified)
+    } else {
+        borrow::Cow::Borrowed(path)
+    }
+}
+
+/// Runs `{executable} -V` and returns its output. If `timeout` is given, the process
+/// is killed if it does not finish in time, and re-run up to `retries` times.
+fn get_version_from_cmd(
+    executable: &ffi::OsStr,
+    timeout: Option<time::Duration>,
+    retries: u32,
+) -> io::Result<String> {
+    let name = executable.to_string_lossy();
+    let mut attempts = 0;
+    let output = loop {
+        let child = process::Command::new(executable)
+            .arg("-V")
+            .stdin(process::Stdio::null())
+            .stdout(process::Stdio::piped())
+            .stderr(process::Stdio::null())
+            .spawn()
+            .map_err(|e| io::Error::new(e.kind(), format!("failed to execute `{name}`: {e}")))?;
+        match wait_with_timeout(child, timeout)? {
+            Some(output) => break output,
+            None if attempts < retries => attempts += 1,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!(
+                        "`{name} -V` did not finish within {}s after {} attempt(s)",
+                        timeout.unwrap_or_default().as_secs(),
+                        attempts + 1
+                    ),
+                ))
+            }
+        }
+    };
+    parse_version_output(&output.stdout).ok_or_else(|| {
+        io::Error::other(format!(
+            "unexpected output of `{name} -V`: {:?}",
+            String::from_utf8_lossy(&output.stdout)
+        ))
+    })
+}
+
+/// Finds the version in the output of e.g. `rustc -V`, which is the first line
+/// containing a word like `1.2.3`. Wrappers may add other lines, CRLF-line-endings
+/// or output which is not valid UTF-8.
+fn parse_version_output(stdout: &[u8]) -> Option<String> {
+    let is_version = |word: &str| {
+        let mut parts = word.split('.');
+        parts.clone().count() >= 3
+            && parts
+                .by_ref()
+                .take(2)
+                .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+    };
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| line.split_whitespace().any(is_version))
+        .map(ToOwned::to_owned)
 }
 
+/// Waits for `child` to finish and collects its output, or kills it and returns
+/// `None` if it did not finish within `timeout`.
+fn wait_with_timeout(
+    mut child: process::Child,
+    timeout: Option<time::Duration>,
+) -> io::Result<Option<process::Output>> {
+    let Some(timeout) = timeout else {
+        return child.wait_with_output().map(Some);
+    };
+    // The child blocks once the pipe is full, so its output is read while waiting
+    let reader = child.stdout.take().map(|mut stdout| {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            io::Read::read_to_end(&mut stdout, &mut buf).map(|_| buf)
+        })
+    });
+    let deadline = time::Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if time::Instant::now() >= deadline {
+            // The reader is left to finish once the pipe is closed, which
+            // grandchildren may keep open
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(time::Duration::from_millis(10));
+    };
+    let stdout = match reader {
+        Some(reader) => reader
+            .join()
+            .map_err(|_| io::Error::other("failed to read the output"))??,
+        None => Vec::new(),
+    };
+    Ok(Some(process::Output {
+        status,
+        stdout,
+        stderr: Vec::new(),
+    }))
+}
+
+/// Runs `program` with `args` and returns the first non-empty line of its output,
+/// if the process could be executed successfully within `timeout`.
+fn first_line_of_cmd(
+    program: &str,
+    args: &[&str],
+    timeout: Option<time::Duration>,
+) -> Option<String> {
+    let child = process::Command::new(program)
+        .args(args)
+        .stdin(process::Stdio::null())
+        .stdout(process::Stdio::piped())
+        .stderr(process::Stdio::null())
+        .spawn()
+        .ok()?;
+    let output = wait_with_timeout(child, timeout).ok()??;
+    if !output.status.success() {
+        return None;
+    }
+    first_line(&output.stdout)
+}
+
+/// The first non-empty line of `stdout`, trimmed.
+fn first_line(stdout: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+/// The environment-variables, besides `CARGO_*`, which Cargo sets for build-scripts
+/// and `built` relies upon.
+static CARGO_VARS: [&str; 10] = [
+    "CARGO",
+    "RUSTC",
+    "RUSTC_LINKER",
+    "RUSTDOC",
+    "TARGET",
+    "HOST",
+    "PROFILE",
+    "OPT_LEVEL",
+    "NUM_JOBS",
+    "DEBUG",
+];
+
 impl EnvironmentMap {
+    /// The current environment; names and values which are not valid UTF-8 are
+    /// converted lossily.
     pub fn new() -> Self {
+        Self::from_vars(env::vars_os(), |_| Ok(())).unwrap_or_else(|_| unreachable!("infallible"))
+    }
+
+    /// The current environment, as `new()`. A warning is emitted for every relevant
+    /// name or value which is not valid UTF-8, or an error is returned if
+    /// `Options::set_strict_env()` is enabled.
+    pub fn collect(options: &crate::Options) -> io::Result<Self> {
+        Self::from_vars(env::vars_os(), |name| {
+            let relevant = name.starts_with("CARGO_")
+                || CARGO_VARS.contains(&name)
+                || options.captured_env_vars.iter().any(|n| n == name);
+            if !relevant {
+                return Ok(());
+            }
+            let msg = format!("environment variable `{name}` is not valid UTF-8");
+            if options.strict_env {
+                return Err(io::Error::other(msg));
+            }
+            crate::self_check::warn(format_args!("{msg}; it was converted lossily"));
+            Ok(())
+        })
+    }
+
+    /// Collects `vars`, converting names and values which are not valid UTF-8 lossily
+    /// after reporting the (converted) name to `invalid`.
+    fn from_vars(
+        vars: impl IntoIterator<Item = (ffi::OsString, ffi::OsString)>,
+        mut invalid: impl FnMut(&str) -> io::Result<()>,
+    ) -> io::Result<Self> {
         let mut envmap = collections::HashMap::new();
-        for (k, v) in env::vars_os() {
-            let k = k.into_string();
-            let v = v.into_string();
-            if let (Ok(k), Ok(v)) = (k, v) {
-                envmap.insert(k, v);
+        for (k, v) in vars {
+            let (k, v) = match (k.into_string(), v.into_string()) {
+                (Ok(k), Ok(v)) => (k, v),
+                (k, v) => {
+                    let k = k.unwrap_or_else(|k| k.to_string_lossy().into_owned());
+                    invalid(&k)?;
+                    (k, v.unwrap_or_else(|v| v.to_string_lossy().into_owned()))
+                }
+            };
+            envmap.insert(k, v);
+        }
+        for name in ["CARGO", "RUSTC", "RUSTC_LINKER", "RUSTDOC"] {
+            if let Some(value) = envmap.get_mut(name) {
+                *value = simplify_path(value).into_owned();
             }
         }
-        Self(envmap)
+        Ok(Self(envmap))
     }
 
-    pub fn write_ci(&self, mut w: &fs::File) -> io::Result<()> {
-        use io::Write;
+    /// The variables for whose name `f` returns a key, as `(key, value)`, sorted by key.
+    fn filter_map_keys<'a>(
+        &'a self,
+        mut f: impl FnMut(&'a str) -> Option<&'a str>,
+    ) -> Vec<(&'a str, &'a str)> {
+        let mut vars = self
+            .0
+            .iter()
+            .filter_map(|(name, value)| Some((f(name)?, value.as_str())))
+            .collect::<Vec<_>>();
+        vars.sort_unstable();
+        vars
+    }
 
+    /// The value of the environment-variable `name`, which Cargo is expected to set.
+    fn get(&self, name: &str) -> io::Result<&str> {
+        self.0.get(name).map(String::as_str).ok_or_else(|| {
+            io::Error::other(format!("missing expected environment variable `{name}`"))
+        })
+    }
+
+    pub fn write_ci(&self, mut w: impl crate::report::Backend) -> io::Result<()> {
         write_variable!(
             w,
             "CI_PLATFORM",
@@ -34,19 +405,227 @@ impl EnvironmentMap {
             fmt_option_str(self.detect_ci()),
             "The Continuous Integration platform detected during compilation."
         );
+        writeln!(
+            w,
+            "/// A Continuous Integration platform `built` can detect.\n\
+            #[allow(dead_code)]\n\
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\n\
+            #[non_exhaustive]\n\
+            pub enum CiPlatform {{"
+        )?;
+        for platform in CIPlatform::ALL {
+            writeln!(w, "    /// {platform}\n    {},", platform.variant_name())?;
+        }
+        writeln!(w, "}}")?;
+        write_variable!(
+            w,
+            "CI_PLATFORM_KIND",
+            "Option<CiPlatform>",
+            match self.detect_ci() {
+                Some(platform) => format!("Some(CiPlatform::{})", platform.variant_name()),
+                None => "None".to_owned(),
+            },
+            "The Continuous Integration platform detected during compilation, as a `CiPlatform`."
+        );
+        let [number, url, pull_request, pipeline] = self.ci_metadata();
+        for (name, value, doc) in [
+            (
+                "CI_BUILD_NUMBER",
+                number,
+                "The number of the CI-build, as given by the CI-platform; `None` if not \
+                known.",
+            ),
+            (
+                "CI_BUILD_URL",
+                url,
+                "The URL of the CI-build or -job; `None` if not known.",
+            ),
+            (
+                "CI_PULL_REQUEST",
+                pull_request,
+                "The number of the pull- or merge-request the CI-build was triggered by; \
+                `None` if not known or not triggered by one.",
+            ),
+            (
+                "CI_PIPELINE_ID",
+                pipeline,
+                "The id of the CI-pipeline or -workflow; `None` if not known.",
+            ),
+        ] {
+            write_variable!(
+                w,
+                name,
+                "Option<&str>",
+                fmt_option_str(value.map(|v| v.escape_default().to_string())),
+                doc
+            );
+        }
         Ok(())
     }
 
-    pub fn write_env(&self, mut w: &fs::File) -> io::Result<()> {
-        use io::Write;
+    /// The build-number, the build-URL, the number of the pull-request and the id of
+    /// the pipeline, as far as the detected CI-platform provides them.
+    fn ci_metadata(&self) -> [Option<String>; 4] {
+        let var = |name: &str| {
+            self.0
+                .get(name)
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty() && *v != "false")
+                .map(ToOwned::to_owned)
+        };
+        let vars = |names: [Option<&str>; 4]| names.map(|name| name.and_then(var));
+        let Some(platform) = self.detect_ci() else {
+            return Default::default();
+        };
+        match platform {
+            // Gitea and Forgejo provide GitHub's variables
+            CIPlatform::GitHubActions | CIPlatform::GiteaActions => {
+                let url = match (
+                    var("GITHUB_SERVER_URL"),
+                    var("GITHUB_REPOSITORY"),
+                    var("GITHUB_RUN_ID"),
+                ) {
+                    (Some(server), Some(repo), Some(run)) => {
+                        Some(format!("{server}/{repo}/actions/runs/{run}"))
+                    }
+                    _ => None,
+                };
+                // `refs/pull/<number>/merge` for pull-requests
+                let pull_request = var("GITHUB_REF").and_then(|r| {
+                    Some(r.strip_prefix("refs/pull/")?.split_once('/')?.0.to_owned())
+                });
+                [
+                    var("GITHUB_RUN_NUMBER"),
+                    url,
+                    pull_request,
+                    var("GITHUB_RUN_ID"),
+                ]
+            }
+            CIPlatform::GitLab => vars([
+                Some("CI_JOB_ID"),
+                Some("CI_JOB_URL"),
+                Some("CI_MERGE_REQUEST_IID"),
+                Some("CI_PIPELINE_ID"),
+            ]),
+            CIPlatform::Buildkite => vars([
+                Some("BUILDKITE_BUILD_NUMBER"),
+                Some("BUILDKITE_BUILD_URL"),
+                Some("BUILDKITE_PULL_REQUEST"),
+                Some("BUILDKITE_BUILD_ID"),
+            ]),
+            CIPlatform::Travis => vars([
+                Some("TRAVIS_BUILD_NUMBER"),
+                Some("TRAVIS_BUILD_WEB_URL"),
+                Some("TRAVIS_PULL_REQUEST"),
+                Some("TRAVIS_BUILD_ID"),
+            ]),
+            CIPlatform::Circle => vars([
+                Some("CIRCLE_BUILD_NUM"),
+                Some("CIRCLE_BUILD_URL"),
+                Some("CIRCLE_PR_NUMBER"),
+                Some("CIRCLE_WORKFLOW_ID"),
+            ]),
+            CIPlatform::AppVeyor => vars([
+                Some("APPVEYOR_BUILD_NUMBER"),
+                None,
+                Some("APPVEYOR_PULL_REQUEST_NUMBER"),
+                Some("APPVEYOR_BUILD_ID"),
+            ]),
+            CIPlatform::Codeship => vars([
+                Some("CI_BUILD_NUMBER"),
+                Some("CI_BUILD_URL"),
+                Some("CI_PR_NUMBER"),
+                None,
+            ]),
+            CIPlatform::Drone => vars([
+                Some("DRONE_BUILD_NUMBER"),
+                Some("DRONE_BUILD_LINK"),
+                Some("DRONE_PULL_REQUEST"),
+                None,
+            ]),
+            CIPlatform::Semaphore => vars([
+                Some("SEMAPHORE_WORKFLOW_NUMBER"),
+                None,
+                Some("SEMAPHORE_GIT_PR_NUMBER"),
+                Some("SEMAPHORE_PIPELINE_ID"),
+            ]),
+            CIPlatform::Jenkins | CIPlatform::Hudson => vars([
+                Some("BUILD_NUMBER"),
+                Some("BUILD_URL"),
+                Some("CHANGE_ID"),
+                None,
+            ]),
+            CIPlatform::Bamboo => vars([
+                Some("bamboo_buildNumber"),
+                Some("bamboo_buildResultsUrl"),
+                Some("bamboo_repository_pr_key"),
+                None,
+            ]),
+            CIPlatform::TFS | CIPlatform::AzurePipelines => vars([
+                Some("BUILD_BUILDNUMBER"),
+                None,
+                Some("SYSTEM_PULLREQUEST_PULLREQUESTNUMBER"),
+                Some("BUILD_BUILDID"),
+            ]),
+            CIPlatform::TeamCity => vars([Some("BUILD_NUMBER"), None, None, None]),
+            CIPlatform::Cirrus => vars([None, None, Some("CIRRUS_PR"), Some("CIRRUS_BUILD_ID")]),
+            CIPlatform::Woodpecker => vars([
+                Some("CI_PIPELINE_NUMBER"),
+                Some("CI_PIPELINE_URL"),
+                Some("CI_COMMIT_PULL_REQUEST"),
+                None,
+            ]),
+            CIPlatform::Sourcehut => vars([Some("JOB_ID"), Some("JOB_URL"), None, None]),
+            CIPlatform::Buddy => vars([
+                Some("BUDDY_EXECUTION_ID"),
+                Some("BUDDY_EXECUTION_URL"),
+                Some("BUDDY_EXECUTION_PULL_REQUEST_NO"),
+                Some("BUDDY_PIPELINE_ID"),
+            ]),
+            CIPlatform::Codefresh => vars([
+                None,
+                Some("CF_BUILD_URL"),
+                Some("CF_PULL_REQUEST_NUMBER"),
+                Some("CF_BUILD_ID"),
+            ]),
+            CIPlatform::Harness => vars([Some("HARNESS_BUILD_ID"), None, None, None]),
+            CIPlatform::CodeBuild => vars([
+                Some("CODEBUILD_BUILD_NUMBER"),
+                None,
+                None,
+                Some("CODEBUILD_BUILD_ID"),
+            ]),
+            CIPlatform::GoCD => vars([
+                Some("GO_PIPELINE_COUNTER"),
+                None,
+                None,
+                Some("GO_PIPELINE_LABEL"),
+            ]),
+            CIPlatform::BitBucket => vars([
+                Some("BITBUCKET_BUILD_NUMBER"),
+                None,
+                Some("BITBUCKET_PR_ID"),
+                Some("BITBUCKET_PIPELINE_UUID"),
+            ]),
+            CIPlatform::TaskCluster => vars([None, None, None, Some("TASK_ID")]),
+            CIPlatform::Magnum | CIPlatform::Generic => {
+                vars([Some("BUILD_NUMBER"), None, None, None])
+            }
+        }
+    }
+
+    pub fn write_env(
+        &self,
+        options: &crate::Options,
+        mut w: impl crate::report::Backend,
+    ) -> io::Result<()> {
         macro_rules! write_env_str {
             ($(($name:ident, $env_name:expr,$doc:expr)),*) => {$(
                 write_str_variable!(
                     w,
                     stringify!($name),
-                    self.0.get($env_name)
-                        .expect(stringify!(Missing expected environment variable $env_name)),
-                        $doc
+                    self.get($env_name)?,
+                    $doc
                 );
             )*}
         }
@@ -105,39 +684,405 @@ impl EnvironmentMap {
                 "The documentation generator that cargo resolved to use."
             )
         );
+        write_variable!(
+            w,
+            "LINKER",
+            "Option<&str>",
+            fmt_option_str(self.linker()?.map(|l| l.escape_default())),
+            "The linker cargo resolved to use, if one was configured."
+        );
+        write_str_variable!(
+            w,
+            "PROFILE_NAME",
+            self.profile_name(),
+            "The name of the profile used during compilation, e.g. `dev`, `release` or a \
+            custom profile; unlike `PROFILE`, which only distinguishes `release` and `debug`."
+        );
+        // `built.rs` is shared by all targets of the package, and cargo does not set
+        // these for the build script, so they are only known when `built.rs` is included
+        write_variable!(
+            w,
+            "BIN_NAME",
+            "Option<&str>",
+            "option_env!(\"CARGO_BIN_NAME\")",
+            "The name of the binary-target being compiled, given by `CARGO_BIN_NAME`; \
+            `None` if the target is not a binary."
+        );
+        write_variable!(
+            w,
+            "CRATE_NAME",
+            "&str",
+            "env!(\"CARGO_CRATE_NAME\")",
+            "The name of the crate being compiled, given by `CARGO_CRATE_NAME`."
+        );
+        // Derived from the constants above by the compiler, so they follow overrides
+        writeln!(
+            w,
+            "/// Parses the decimal number `s` at compile time, e.g. to derive numeric \
+            constants from string-constants; `None` if `s` is not a number which fits \
+            into a `u32`.\n\
+            #[allow(dead_code)]\n\
+            pub const fn parse_u32(s: &str) -> Option<u32> {{\n    \
+                let bytes = s.as_bytes();\n    \
+                if bytes.is_empty() {{\n        \
+                    return None;\n    \
+                }}\n    \
+                let mut value: u32 = 0;\n    \
+                let mut i = 0;\n    \
+                while i < bytes.len() {{\n        \
+                    let digit = bytes[i].wrapping_sub(b'0');\n        \
+                    if digit > 9 {{\n            \
+                        return None;\n        \
+                    }}\n        \
+                    value = match value.checked_mul(10) {{\n            \
+                        Some(value) => match value.checked_add(digit as u32) {{\n                \
+                            Some(value) => value,\n                \
+                            None => return None,\n            \
+                        }},\n            \
+                        None => return None,\n        \
+                    }};\n        \
+                    i += 1;\n    \
+                }}\n    \
+                Some(value)\n\
+            }}"
+        )?;
+        for part in ["MAJOR", "MINOR", "PATCH"] {
+            write_variable!(
+                w,
+                format_args!("PKG_VERSION_{part}_NUMERIC"),
+                "Option<u32>",
+                format_args!("parse_u32(PKG_VERSION_{part})"),
+                format_args!(
+                    "`PKG_VERSION_{part}` as a number, derived at compile time; `None` if it \
+                    is not a number which fits into a `u32`."
+                )
+            );
+        }
+        let version_num =
+            |name| -> io::Result<u64> { self.get(name)?.parse().map_err(io::Error::other) };
+        let build_metadata = self
+            .get("CARGO_PKG_VERSION")?
+            .split_once('+')
+            .map(|(_, build)| build)
+            .unwrap_or_default();
+        write_variable!(
+            w,
+            "PKG_SEMVER",
+            "(u64, u64, u64, &str, &str)",
+            format_args!(
+                "({}, {}, {}, \"{}\", \"{}\")",
+                version_num("CARGO_PKG_VERSION_MAJOR")?,
+                version_num("CARGO_PKG_VERSION_MINOR")?,
+                version_num("CARGO_PKG_VERSION_PATCH")?,
+                self.get("CARGO_PKG_VERSION_PRE")?.escape_default(),
+                build_metadata.escape_default()
+            ),
+            "The version as a tuple of major, minor, patch, pre-release and build-metadata."
+        );
+        let manifest = self
+            .0
+            .get("CARGO_MANIFEST_DIR")
+            .map(|dir| DeclaredManifest::load(&options.manifest_path(dir.as_ref())))
+            .unwrap_or_default();
+        let pkg_str = |env_name, key| match self.0.get(env_name) {
+            Some(value) => value.clone(),
+            None => manifest.declared_str(key).unwrap_or_default(),
+        };
+        let rust_version = pkg_str("CARGO_PKG_RUST_VERSION", "rust-version");
+        let authors = authors_list(self.get("CARGO_PKG_AUTHORS")?, &manifest);
+        write_variable!(
+            w,
+            "PKG_AUTHORS_LIST",
+            format_args!("[&str; {}]", authors.len()),
+            ArrayDisplay(&authors, |a, f| write!(f, "\"{}\"", a.escape_default())),
+            "The authors, as declared in Cargo.toml."
+        );
+        write_str_variable!(
+            w,
+            "PKG_RUST_VERSION",
+            rust_version,
+            "The minimum supported Rust version, as declared in Cargo.toml; empty if not declared."
+        );
+        write_str_variable!(
+            w,
+            "PKG_README",
+            pkg_str("CARGO_PKG_README", "readme"),
+            "The path to the README-file, as declared in Cargo.toml; empty if not declared."
+        );
+        write_str_variable!(
+            w,
+            "PKG_LICENSE_FILE",
+            pkg_str("CARGO_PKG_LICENSE_FILE", "license-file"),
+            "The path to the license-file, as declared in Cargo.toml; empty if not declared."
+        );
+        write_str_variable!(
+            w,
+            "PKG_DOCUMENTATION",
+            manifest.declared_str("documentation").unwrap_or_default(),
+            "The URL of the documentation, as declared in Cargo.toml; empty if not declared."
+        );
+        for (name, key, doc) in [
+            (
+                "PKG_KEYWORDS",
+                "keywords",
+                "The keywords, as declared in Cargo.toml.",
+            ),
+            (
+                "PKG_CATEGORIES",
+                "categories",
+                "The categories, as declared in Cargo.toml.",
+            ),
+        ] {
+            let values = manifest.declared_array(key);
+            write_variable!(
+                w,
+                name,
+                format_args!("[&str; {}]", values.len()),
+                ArrayDisplay(&values, |v, f| write!(f, "\"{}\"", v.escape_default())),
+                doc
+            );
+            write_str_variable!(
+                w,
+                format_args!("{name}_STR"),
+                values.join(", "),
+                format_args!("The {key} as a comma-separated string.")
+            );
+        }
+        let opt_level = self.get("OPT_LEVEL")?;
         write_str_variable!(
             w,
             "OPT_LEVEL",
-            env::var("OPT_LEVEL").unwrap(),
+            opt_level,
             "Value of OPT_LEVEL for the profile used during compilation."
         );
+        let (variant, numeric) = parse_opt_level(opt_level);
+        write_variable!(
+            w,
+            "OPT_LEVEL_NUMERIC",
+            "Option<u8>",
+            match numeric {
+                Some(level) => format!("Some({level})"),
+                None => "None".to_owned(),
+            },
+            "The numeric value of OPT_LEVEL; `None` if optimizing for size (`s` or `z`)."
+        );
+        writeln!(
+            w,
+            "/// The optimization-level of the profile used during compilation.\n\
+            #[allow(dead_code)]\n\
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\n\
+            pub enum OptLevel {{\n    \
+                /// No optimizations (`0`).\n    \
+                O0,\n    \
+                /// Basic optimizations (`1`).\n    \
+                O1,\n    \
+                /// Some optimizations (`2`).\n    \
+                O2,\n    \
+                /// All optimizations (`3`).\n    \
+                O3,\n    \
+                /// Optimize for binary size (`s`).\n    \
+                Size,\n    \
+                /// Optimize for binary size, also turning off loop vectorization (`z`).\n    \
+                MinSize,\n    \
+                /// A level unknown to `built`; see `OPT_LEVEL`.\n    \
+                Other,\n\
+            }}"
+        )?;
+        write_variable!(
+            w,
+            "OPT_LEVEL_KIND",
+            "OptLevel",
+            format_args!("OptLevel::{variant}"),
+            "Value of OPT_LEVEL for the profile used during compilation, as an `OptLevel`."
+        );
         write_variable!(
             w,
             "NUM_JOBS",
             "u32",
-            env::var("NUM_JOBS").unwrap(),
+            self.get("NUM_JOBS")?,
             "The parallelism that was specified during compilation."
         );
+        let debug = self.get("DEBUG")? == "true";
         write_variable!(
             w,
             "DEBUG",
             "bool",
-            env::var("DEBUG").unwrap() == "true",
+            debug,
             "Value of DEBUG for the profile used during compilation."
         );
+        let rustflags = self.rustflags();
+        let flags = rustflags.split_whitespace().collect::<Vec<_>>();
+        let profile = self.profile_name();
+        let setting = |key: &str| self.profile_setting(&manifest, &profile, key);
+        // Cargo's default is `full` if debuginfo is enabled at all
+        let debuginfo = codegen_option(&flags, "debuginfo")
+            .map(ToOwned::to_owned)
+            .or_else(|| setting("debug"))
+            .unwrap_or_else(|| if debug { "2" } else { "0" }.to_owned());
+        write_str_variable!(
+            w,
+            "DEBUGINFO",
+            debuginfo,
+            "The debuginfo-level of the profile used during compilation as it was \
+            configured, e.g. `0`, `line-tables-only` or `true`."
+        );
+        write_str_variable!(
+            w,
+            "RUSTFLAGS",
+            self.rustflags(),
+            "The extra flags passed to the compiler, e.g. by means of `RUSTFLAGS`, separated by spaces."
+        );
+
+        write_variable!(
+            w,
+            "LTO",
+            "Option<&str>",
+            fmt_option_str(setting("lto").map(|v| v.escape_default().to_string())),
+            "The `lto`-setting of the profile used during compilation; `None` if not \
+            configured."
+        );
+        write_variable!(
+            w,
+            "CODEGEN_UNITS",
+            "Option<u32>",
+            match setting("codegen-units").and_then(|v| v.parse::<u32>().ok()) {
+                Some(units) => format!("Some({units})"),
+                None => "None".to_owned(),
+            },
+            "The `codegen-units`-setting of the profile used during compilation; `None` if \
+            not configured."
+        );
+        let panic = match self.0.get("CARGO_CFG_PANIC") {
+            Some(panic) => Some(panic.clone()),
+            None => setting("panic"),
+        };
+        write_variable!(
+            w,
+            "PANIC_STRATEGY",
+            "Option<&str>",
+            fmt_option_str(panic.map(|v| v.escape_default().to_string())),
+            "The panic-strategy, given by `CARGO_CFG_PANIC` or the profile used during \
+            compilation; `None` if not configured."
+        );
+        write_variable!(
+            w,
+            "STRIP",
+            "Option<&str>",
+            fmt_option_str(setting("strip").map(|v| v.escape_default().to_string())),
+            "The `strip`-setting of the profile used during compilation; `None` if not \
+            configured."
+        );
         Ok(())
     }
 
-    pub fn write_features(&self, mut w: &fs::File) -> io::Result<()> {
-        use io::Write;
+    /// The name of the profile used during compilation, given by `CARGO_PROFILE` or
+    /// derived from `OUT_DIR`, which is
+    /// `<target-dir>/[<target>/]<profile-dir>/build/<package>-<hash>/out`; the
+    /// profile-directory of the `dev`-profile is named `debug`. Falls back to `PROFILE`.
+    fn profile_name(&self) -> String {
+        if let Some(profile) = self.0.get("CARGO_PROFILE").filter(|p| !p.is_empty()) {
+            return profile.clone();
+        }
+        let from_out_dir = self
+            .0
+            .get("OUT_DIR")
+            .and_then(|out_dir| path::Path::new(out_dir).ancestors().nth(3)?.file_name())
+            .and_then(ffi::OsStr::to_str);
+        match (from_out_dir, self.0.get("PROFILE").map(String::as_str)) {
+            (Some("debug"), _) | (None, Some("debug")) => "dev".to_owned(),
+            (Some(profile), _) | (None, Some(profile)) => profile.to_owned(),
+            (None, None) => "dev".to_owned(),
+        }
+    }
 
-        let mut features = Vec::new();
-        for name in self.0.keys() {
-            if let Some(feat) = name.strip_prefix("CARGO_FEATURE_") {
-                features.push(feat.to_owned());
-            }
+    /// The setting `key` of the profile `profile`, given by `-C key=...` in the extra
+    /// flags, by `CARGO_PROFILE_<profile>_<key>` or by the profile in the manifest, in
+    /// this order.
+    fn profile_setting(
+        &self,
+        manifest: &DeclaredManifest,
+        profile: &str,
+        key: &str,
+    ) -> Option<String> {
+        let rustflags = self.rustflags();
+        let flags = rustflags.split_whitespace().collect::<Vec<_>>();
+        if let Some(value) = codegen_option(&flags, key) {
+            return Some(value.to_owned());
         }
-        features.sort_unstable();
+        let var = format!("CARGO_PROFILE_{profile}_{key}")
+            .to_uppercase()
+            .replace('-', "_");
+        if let Some(value) = self.0.get(&var) {
+            return Some(value.clone());
+        }
+        manifest.profile_value(profile, key)
+    }
+
+    /// The linker given by `RUSTC_LINKER` or, if Cargo did not set it, by the
+    /// target-specific `CARGO_TARGET_<triple>_LINKER`.
+    fn linker(&self) -> io::Result<Option<&str>> {
+        if let Some(linker) = self.0.get("RUSTC_LINKER") {
+            return Ok(Some(linker));
+        }
+        let target = self.get("TARGET")?.to_uppercase().replace(['-', '.'], "_");
+        Ok(self
+            .0
+            .get(&format!("CARGO_TARGET_{target}_LINKER"))
+            .map(String::as_str))
+    }
+
+    /// The extra flags passed to the compiler, decoded from `CARGO_ENCODED_RUSTFLAGS`
+    /// or taken from `RUSTFLAGS` if Cargo did not set the former.
+    fn rustflags(&self) -> String {
+        match self.0.get("CARGO_ENCODED_RUSTFLAGS") {
+            Some(encoded) => encoded
+                .split('\x1f')
+                .filter(|flag| !flag.is_empty())
+                .collect::<Vec<_>>()
+                .join(" "),
+            None => self
+                .0
+                .get("RUSTFLAGS")
+                .map(|flags| flags.trim().to_owned())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn write_features(
+        &self,
+        #[cfg_attr(not(feature = "manifest"), allow(unused_variables))] options: &crate::Options,
+        mut w: impl crate::report::Backend,
+    ) -> io::Result<()> {
+        #[cfg_attr(not(feature = "manifest"), allow(unused_mut))]
+        let mut features = self
+            .filter_map_keys(|name| name.strip_prefix("CARGO_FEATURE_"))
+            .into_iter()
+            .map(|(feat, _)| feat.to_owned())
+            .collect::<Vec<_>>();
+
+        #[cfg(feature = "manifest")]
+        let foreign_features = if options.validate_features {
+            let manifest_dir = self.get("CARGO_MANIFEST_DIR")?;
+            let manifest =
+                crate::manifest::Manifest::load(&options.manifest_path(manifest_dir.as_ref()))?;
+            let declared = manifest
+                .features
+                .iter()
+                .map(|feat| feat.to_uppercase().replace('-', "_"))
+                .collect::<Vec<_>>();
+            let foreign_features;
+            (features, foreign_features) = features
+                .into_iter()
+                .partition(|feat| declared.contains(feat));
+            for feat in &foreign_features {
+                crate::self_check::warn(format_args!(
+                    "`CARGO_FEATURE_{feat}` does not refer to a declared feature"
+                ));
+            }
+            Some(foreign_features)
+        } else {
+            None
+        };
 
         write_variable!(
             w,
@@ -179,12 +1124,26 @@ impl EnvironmentMap {
             "The feature-string as above, from lowercase strings."
         );
 
+        #[cfg(feature = "manifest")]
+        if let Some(foreign_features) = foreign_features {
+            write_variable!(
+                w,
+                "FEATURES_FOREIGN",
+                format_args!("[&str; {}]", foreign_features.len()),
+                ArrayDisplay(&foreign_features, |t, f| write!(
+                    f,
+                    "\"{}\"",
+                    t.escape_default()
+                )),
+                "`CARGO_FEATURE_*`-variables set during compilation which do not refer to a \
+                feature declared by the manifest."
+            );
+        }
+
         Ok(())
     }
 
-    pub fn write_cfg(&self, mut w: &fs::File) -> io::Result<()> {
-        use io::Write;
-
+    pub fn write_cfg(&self, mut w: impl crate::report::Backend) -> io::Result<()> {
         write_str_variable!(
             w,
             "CFG_TARGET_ARCH",
@@ -230,17 +1189,124 @@ impl EnvironmentMap {
             "The pointer width, given by `CARGO_CFG_TARGET_POINTER_WIDTH`."
         );
 
+        write_variable!(
+            w,
+            "CPU_DISPATCH_LEVEL",
+            "Option<&str>",
+            fmt_option_str(cpu_dispatch_level(
+                &self.0["CARGO_CFG_TARGET_ARCH"],
+                self.0
+                    .get("CARGO_CFG_TARGET_FEATURE")
+                    .map(|s| s.split(','))
+                    .into_iter()
+                    .flatten()
+            )),
+            "The microarchitecture level (e.g. `x86-64-v3`) implied by the target features \
+            enabled via `-C target-cpu` or `-C target-feature`; `None` if the target is not `x86_64`."
+        );
+
+        let mut target_features = self
+            .0
+            .get("CARGO_CFG_TARGET_FEATURE")
+            .map(|s| s.split(',').filter(|f| !f.is_empty()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        target_features.sort_unstable();
+        write_variable!(
+            w,
+            "CFG_TARGET_FEATURES",
+            format_args!("[&str; {}]", target_features.len()),
+            ArrayDisplay(&target_features, |t, f| write!(
+                f,
+                "\"{}\"",
+                t.escape_default()
+            )),
+            "The target features, given by `CARGO_CFG_TARGET_FEATURE`."
+        );
+        write_str_variable!(
+            w,
+            "CFG_TARGET_FEATURES_STR",
+            target_features.join(", "),
+            "The target features as a comma-separated string."
+        );
+
+        let cfg_all = self
+            .filter_map_keys(|name| name.strip_prefix("CARGO_CFG_"))
+            .into_iter()
+            .map(|(cfg, value)| (cfg.to_lowercase(), value))
+            .collect::<Vec<_>>();
+        write_variable!(
+            w,
+            "CFG_ALL",
+            format_args!("[(&str, &str); {}]", cfg_all.len()),
+            ArrayDisplay(&cfg_all, |(cfg, value), f| write!(
+                f,
+                "(\"{}\", \"{}\")",
+                cfg.escape_default(),
+                value.escape_default()
+            )),
+            "All `cfg`-options given by `CARGO_CFG_*` and their values; multiple values \
+            of the same option are separated by commas."
+        );
+
         Ok(())
     }
 
-    pub fn write_compiler_version(&self, mut w: &fs::File) -> io::Result<()> {
-        use std::io::Write;
+    pub fn write_captured_env(
+        &self,
+        options: &crate::Options,
+        mut w: impl crate::report::Backend,
+    ) -> io::Result<()> {
+        if options.captured_env_vars.is_empty() {
+            return Ok(());
+        }
+        for name in &options.captured_env_vars {
+            crate::self_check::rerun_if_env_changed(name);
+        }
+        write_variable!(
+            w,
+            "CAPTURED_ENV",
+            format_args!(
+                "[(&str, Option<&str>); {}]",
+                options.captured_env_vars.len()
+            ),
+            ArrayDisplay(&options.captured_env_vars, |name, fmt| write!(
+                fmt,
+                "(\"{}\", {})",
+                name.escape_default(),
+                fmt_option_str(self.0.get(name).map(|v| v.escape_default()))
+            )),
+            "The environment-variables selected by `Options::set_captured_env_vars()` and \
+            their values during compilation; `None` if a variable was not set."
+        );
+        Ok(())
+    }
 
-        let rustc = &self.0["RUSTC"];
-        let rustdoc = &self.0["RUSTDOC"];
+    pub fn write_compiler_version(
+        &self,
+        options: &crate::Options,
+        mut w: impl crate::report::Backend,
+    ) -> io::Result<()> {
+        let rustc = self.get("RUSTC")?;
+        let rustdoc = self.get("RUSTDOC")?;
 
-        let rustc_version = get_version_from_cmd(rustc.as_ref())?;
-        let rustdoc_version = get_version_from_cmd(rustdoc.as_ref()).unwrap_or_default();
+        let rustc_version = get_version_from_cmd(
+            rustc.as_ref(),
+            options.subprocess_timeout,
+            options.subprocess_retries,
+        )?;
+        let rustdoc_version = get_version_from_cmd(
+            rustdoc.as_ref(),
+            options.subprocess_timeout,
+            options.subprocess_retries,
+        )
+        .unwrap_or_default();
+        let cargo = self.0.get("CARGO").map_or("cargo", String::as_str);
+        let cargo_version = get_version_from_cmd(
+            cargo.as_ref(),
+            options.subprocess_timeout,
+            options.subprocess_retries,
+        )
+        .unwrap_or_default();
 
         write_str_variable!(
             w,
@@ -257,6 +1323,73 @@ impl EnvironmentMap {
                 "The output of `{rustdoc} -V`; empty string if `{rustdoc} -V` failed to execute"
             )
         );
+
+        write_str_variable!(
+            w,
+            "CARGO_VERSION",
+            cargo_version,
+            format_args!(
+                "The output of `{cargo} -V`; empty string if `{cargo} -V` failed to execute"
+            )
+        );
+        Ok(())
+    }
+
+    /// Writes `BUILD_HOST`, `BUILD_USER` and `BUILD_HOST_OS`, describing the machine
+    /// and the user which compiled the crate.
+    pub fn write_build_host(
+        &self,
+        options: &crate::Options,
+        mut w: impl crate::report::Backend,
+    ) -> io::Result<()> {
+        let var = |name: &str| {
+            self.0
+                .get(name)
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+                .map(ToOwned::to_owned)
+        };
+        let timeout = options.subprocess_timeout;
+
+        let host = if cfg!(windows) {
+            var("COMPUTERNAME")
+        } else {
+            fs::read_to_string("/proc/sys/kernel/hostname")
+                .ok()
+                .and_then(|h| first_line(h.as_bytes()))
+                .or_else(|| var("HOSTNAME"))
+        }
+        .or_else(|| first_line_of_cmd("hostname", &[], timeout));
+        let user = ["USER", "LOGNAME", "USERNAME"].into_iter().find_map(var);
+        let host_os = if cfg!(windows) {
+            first_line_of_cmd("cmd", &["/C", "ver"], timeout)
+        } else {
+            first_line_of_cmd("uname", &["-srm"], timeout)
+        };
+
+        write_variable!(
+            w,
+            "BUILD_HOST",
+            "Option<&str>",
+            fmt_option_str(host.map(|h| h.escape_default().to_string())),
+            "The hostname of the machine which compiled the crate, if known."
+        );
+        write_variable!(
+            w,
+            "BUILD_USER",
+            "Option<&str>",
+            fmt_option_str(user.map(|u| u.escape_default().to_string())),
+            "The name of the user who compiled the crate, given by `USER`, `LOGNAME` or \
+            `USERNAME`."
+        );
+        write_variable!(
+            w,
+            "BUILD_HOST_OS",
+            "Option<&str>",
+            fmt_option_str(host_os.map(|o| o.escape_default().to_string())),
+            "The operating system of the machine which compiled the crate, as reported \
+            by `uname -srm` or `ver`."
+        );
         Ok(())
     }
 
@@ -278,6 +1411,24 @@ impl EnvironmentMap {
                 }
             )*};
         }
+        // Platforms which also set the variables of others they are compatible with
+        detect!(
+            ("GITEA_ACTIONS", "true", GiteaActions),
+            ("FORGEJO_ACTIONS", "true", GiteaActions),
+            ("CI", "woodpecker", Woodpecker)
+        );
+        // Azure Pipelines is the hosted successor of TFS, both set `TF_BUILD`
+        if self.0.contains_key("TF_BUILD")
+            && self
+                .0
+                .get("SYSTEM_TEAMFOUNDATIONCOLLECTIONURI")
+                .is_some_and(|uri| {
+                    uri.contains("dev.azure.com") || uri.contains(".visualstudio.com")
+                })
+        {
+            return Some(CIPlatform::AzurePipelines);
+        }
+
         // Variable names collected by watson/ci-info
         detect!(
             ("TRAVIS", Travis),
@@ -295,14 +1446,22 @@ impl EnvironmentMap {
             ("HUDSON_URL", Hudson),
             ("GO_PIPELINE_LABEL", GoCD),
             ("BITBUCKET_COMMIT", BitBucket),
-            ("GITHUB_ACTIONS", GitHubActions)
+            ("GITHUB_ACTIONS", GitHubActions),
+            ("CIRRUS_CI", Cirrus),
+            ("BUDDY_WORKSPACE_ID", Buddy),
+            ("CF_BUILD_ID", Codefresh),
+            ("HARNESS_BUILD_ID", Harness),
+            ("CODEBUILD_BUILD_ARN", CodeBuild)
         );
 
         if self.0.contains_key("TASK_ID") && self.0.contains_key("RUN_ID") {
             return Some(CIPlatform::TaskCluster);
         }
 
-        detect!(("CI_NAME", "codeship", Codeship));
+        detect!(
+            ("CI_NAME", "codeship", Codeship),
+            ("CI_NAME", "sourcehut", Sourcehut)
+        );
 
         detect!(
             "CI",                     // Could be Travis, Circle, GitLab, AppVeyor or CodeShip
@@ -314,6 +1473,10 @@ impl EnvironmentMap {
 }
 
 /// Various Continuous Integration platforms whose presence can be detected.
+///
+/// More platforms may be added without a major version bump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum CIPlatform {
     /// <https://travis-ci.org>
     Travis,
@@ -351,10 +1514,156 @@ pub enum CIPlatform {
     BitBucket,
     /// <https://github.com/features/actions>
     GitHubActions,
+    /// <https://azure.microsoft.com/products/devops/pipelines>
+    AzurePipelines,
+    /// <https://cirrus-ci.org>
+    Cirrus,
+    /// <https://woodpecker-ci.org>
+    Woodpecker,
+    /// <https://builds.sr.ht>
+    Sourcehut,
+    /// <https://docs.gitea.com/usage/actions/overview>, including Forgejo Actions
+    GiteaActions,
+    /// <https://buddy.works>
+    Buddy,
+    /// <https://codefresh.io>
+    Codefresh,
+    /// <https://www.harness.io>
+    Harness,
+    /// <https://aws.amazon.com/codebuild>
+    CodeBuild,
     /// Unspecific
     Generic,
 }
 
+impl CIPlatform {
+    /// Detect the Continuous Integration platform from the environment-variables
+    /// `vars`, e.g. as captured elsewhere, instead of the environment of the current
+    /// process; see `built::util::detect_ci()`.
+    ///
+    /// ```
+    /// let vars = [("GITLAB_CI", "true"), ("CI", "true")];
+    /// let platform = built::CIPlatform::detect_from_iter(
+    ///     vars.map(|(k, v)| (k.to_owned(), v.to_owned())),
+    /// );
+    /// assert_eq!(platform, Some(built::CIPlatform::GitLab));
+    /// ```
+    #[must_use]
+    pub fn detect_from_iter(vars: impl IntoIterator<Item = (String, String)>) -> Option<Self> {
+        EnvironmentMap(vars.into_iter().collect()).detect_ci()
+    }
+
+    /// All platforms, in the order they are declared; see `index()`.
+    const ALL: [Self; 28] = [
+        Self::Travis,
+        Self::Circle,
+        Self::GitLab,
+        Self::AppVeyor,
+        Self::Codeship,
+        Self::Drone,
+        Self::Magnum,
+        Self::Semaphore,
+        Self::Jenkins,
+        Self::Bamboo,
+        Self::TFS,
+        Self::TeamCity,
+        Self::Buildkite,
+        Self::Hudson,
+        Self::TaskCluster,
+        Self::GoCD,
+        Self::BitBucket,
+        Self::GitHubActions,
+        Self::AzurePipelines,
+        Self::Cirrus,
+        Self::Woodpecker,
+        Self::Sourcehut,
+        Self::GiteaActions,
+        Self::Buddy,
+        Self::Codefresh,
+        Self::Harness,
+        Self::CodeBuild,
+        Self::Generic,
+    ];
+
+    /// The position of the platform in `ALL`. As the `match` is exhaustive, a new
+    /// platform can't be added without giving it a position here; the assertion below
+    /// checks that `ALL` holds every platform at its position.
+    const fn index(self) -> usize {
+        match self {
+            Self::Travis => 0,
+            Self::Circle => 1,
+            Self::GitLab => 2,
+            Self::AppVeyor => 3,
+            Self::Codeship => 4,
+            Self::Drone => 5,
+            Self::Magnum => 6,
+            Self::Semaphore => 7,
+            Self::Jenkins => 8,
+            Self::Bamboo => 9,
+            Self::TFS => 10,
+            Self::TeamCity => 11,
+            Self::Buildkite => 12,
+            Self::Hudson => 13,
+            Self::TaskCluster => 14,
+            Self::GoCD => 15,
+            Self::BitBucket => 16,
+            Self::GitHubActions => 17,
+            Self::AzurePipelines => 18,
+            Self::Cirrus => 19,
+            Self::Woodpecker => 20,
+            Self::Sourcehut => 21,
+            Self::GiteaActions => 22,
+            Self::Buddy => 23,
+            Self::Codefresh => 24,
+            Self::Harness => 25,
+            Self::CodeBuild => 26,
+            Self::Generic => 27,
+        }
+    }
+
+    /// The name of the variant, as used for the `CiPlatform`-enum in `built.rs`.
+    const fn variant_name(self) -> &'static str {
+        match self {
+            Self::Travis => "Travis",
+            Self::Circle => "Circle",
+            Self::GitLab => "GitLab",
+            Self::AppVeyor => "AppVeyor",
+            Self::Codeship => "Codeship",
+            Self::Drone => "Drone",
+            Self::Magnum => "Magnum",
+            Self::Semaphore => "Semaphore",
+            Self::Jenkins => "Jenkins",
+            Self::Bamboo => "Bamboo",
+            Self::TFS => "TFS",
+            Self::TeamCity => "TeamCity",
+            Self::Buildkite => "Buildkite",
+            Self::Hudson => "Hudson",
+            Self::TaskCluster => "TaskCluster",
+            Self::GoCD => "GoCD",
+            Self::BitBucket => "BitBucket",
+            Self::GitHubActions => "GitHubActions",
+            Self::AzurePipelines => "AzurePipelines",
+            Self::Cirrus => "Cirrus",
+            Self::Woodpecker => "Woodpecker",
+            Self::Sourcehut => "Sourcehut",
+            Self::GiteaActions => "GiteaActions",
+            Self::Buddy => "Buddy",
+            Self::Codefresh => "Codefresh",
+            Self::Harness => "Harness",
+            Self::CodeBuild => "CodeBuild",
+            Self::Generic => "Generic",
+        }
+    }
+}
+
+const _: () = {
+    let mut i = 0;
+    while i < CIPlatform::ALL.len() {
+        assert!(CIPlatform::ALL[i].index() == i);
+        i += 1;
+    }
+};
+
 impl fmt::Display for CIPlatform {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(match *self {
@@ -376,7 +1685,512 @@ impl fmt::Display for CIPlatform {
             CIPlatform::GoCD => "GoCD",
             CIPlatform::BitBucket => "BitBucket",
             CIPlatform::GitHubActions => "GitHub Actions",
+            CIPlatform::AzurePipelines => "Azure Pipelines",
+            CIPlatform::Cirrus => "Cirrus CI",
+            CIPlatform::Woodpecker => "Woodpecker",
+            CIPlatform::Sourcehut => "Sourcehut",
+            CIPlatform::GiteaActions => "Gitea Actions",
+            CIPlatform::Buddy => "Buddy",
+            CIPlatform::Codefresh => "Codefresh",
+            CIPlatform::Harness => "Harness",
+            CIPlatform::CodeBuild => "AWS CodeBuild",
             CIPlatform::Generic => "Generic CI",
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn ci_metadata() {
+        let envmap = |vars: &[(&str, &str)]| {
+            super::EnvironmentMap(
+                vars.iter()
+                    .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                    .collect(),
+            )
+        };
+        assert_eq!(
+            envmap(&[
+                ("GITHUB_ACTIONS", "true"),
+                ("GITHUB_SERVER_URL", "https://github.com"),
+                ("GITHUB_REPOSITORY", "lukaslueg/built"),
+                ("GITHUB_RUN_ID", "1234"),
+                ("GITHUB_RUN_NUMBER", "56"),
+                ("GITHUB_REF", "refs/pull/78/merge"),
+            ])
+            .ci_metadata(),
+            [
+                Some("56".to_owned()),
+                Some("https://github.com/lukaslueg/built/actions/runs/1234".to_owned()),
+                Some("78".to_owned()),
+                Some("1234".to_owned()),
+            ]
+        );
+        assert_eq!(
+            envmap(&[
+                ("TRAVIS", "true"),
+                ("TRAVIS_BUILD_NUMBER", "9"),
+                ("TRAVIS_PULL_REQUEST", "false"),
+            ])
+            .ci_metadata(),
+            [Some("9".to_owned()), None, None, None]
+        );
+        assert_eq!(
+            envmap(&[("GITHUB_RUN_NUMBER", "56")]).ci_metadata(),
+            [None, None, None, None]
+        );
+    }
+
+    #[test]
+    fn detect_ci() {
+        let detect = |vars: &[(&str, &str)]| {
+            super::EnvironmentMap(
+                vars.iter()
+                    .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                    .collect(),
+            )
+            .detect_ci()
+            .map(|platform| platform.to_string())
+        };
+        assert_eq!(detect(&[]), None);
+        assert_eq!(
+            detect(&[("GITHUB_ACTIONS", "true")]).as_deref(),
+            Some("GitHub Actions")
+        );
+        for forge in ["GITEA_ACTIONS", "FORGEJO_ACTIONS"] {
+            assert_eq!(
+                detect(&[("GITHUB_ACTIONS", "true"), (forge, "true")]).as_deref(),
+                Some("Gitea Actions")
+            );
+        }
+        assert_eq!(
+            detect(&[("CI", "woodpecker"), ("DRONE", "true")]).as_deref(),
+            Some("Woodpecker")
+        );
+        assert_eq!(
+            detect(&[
+                ("TF_BUILD", "True"),
+                (
+                    "SYSTEM_TEAMFOUNDATIONCOLLECTIONURI",
+                    "https://dev.azure.com/foo/"
+                )
+            ])
+            .as_deref(),
+            Some("Azure Pipelines")
+        );
+        assert_eq!(
+            detect(&[
+                ("TF_BUILD", "True"),
+                (
+                    "SYSTEM_TEAMFOUNDATIONCOLLECTIONURI",
+                    "https://tfs.example.com/"
+                )
+            ])
+            .as_deref(),
+            Some("Team Foundation Server")
+        );
+        for (var, value, platform) in [
+            ("CIRRUS_CI", "true", "Cirrus CI"),
+            ("CI_NAME", "sourcehut", "Sourcehut"),
+            ("BUDDY_WORKSPACE_ID", "1", "Buddy"),
+            ("CF_BUILD_ID", "1", "Codefresh"),
+            ("HARNESS_BUILD_ID", "1", "Harness"),
+            (
+                "CODEBUILD_BUILD_ARN",
+                "arn:aws:codebuild:foo",
+                "AWS CodeBuild",
+            ),
+        ] {
+            assert_eq!(
+                detect(&[("CI", "true"), (var, value)]).as_deref(),
+                Some(platform)
+            );
+        }
+    }
+
+    #[test]
+    fn write_ci() {
+        let envmap = super::EnvironmentMap(
+            [("GITLAB_CI", "true")]
+                .into_iter()
+                .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                .collect(),
+        );
+        let mut built = Vec::new();
+        envmap.write_ci(&mut built).unwrap();
+        let built = String::from_utf8(built).unwrap();
+        assert!(built.contains("pub static CI_PLATFORM: Option<&str> = Some(\"GitLab\");"));
+        assert!(built.contains("    /// Gitea Actions\n    GiteaActions,\n"));
+        assert!(built.contains(
+            "pub static CI_PLATFORM_KIND: Option<CiPlatform> = Some(CiPlatform::GitLab);"
+        ));
+        for platform in super::CIPlatform::ALL {
+            assert!(built.contains(&format!("    {},\n", platform.variant_name())));
+        }
+    }
+
+    #[test]
+    fn filter_map_keys() {
+        let envmap = super::EnvironmentMap(
+            [
+                ("CARGO_CFG_TARGET_OS", "linux"),
+                ("CARGO_CFG_TARGET_FEATURE", "fxsr,sse,sse2"),
+                ("CARGO_CFG_UNIX", ""),
+                ("CARGO_FEATURE_FOO", "1"),
+            ]
+            .into_iter()
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect(),
+        );
+        assert_eq!(
+            envmap.filter_map_keys(|name| name.strip_prefix("CARGO_CFG_")),
+            [
+                ("TARGET_FEATURE", "fxsr,sse,sse2"),
+                ("TARGET_OS", "linux"),
+                ("UNIX", "")
+            ]
+        );
+    }
+
+    #[test]
+    fn rustflags() {
+        let envmap = |vars: &[(&str, &str)]| {
+            super::EnvironmentMap(
+                vars.iter()
+                    .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                    .collect(),
+            )
+        };
+        assert_eq!(
+            envmap(&[
+                (
+                    "CARGO_ENCODED_RUSTFLAGS",
+                    "-C\x1ftarget-cpu=native\x1f--cfg\x1ffoo"
+                ),
+                ("RUSTFLAGS", "-Dwarnings")
+            ])
+            .rustflags(),
+            "-C target-cpu=native --cfg foo"
+        );
+        assert_eq!(
+            envmap(&[("CARGO_ENCODED_RUSTFLAGS", ""), ("RUSTFLAGS", "-Dwarnings")]).rustflags(),
+            ""
+        );
+        assert_eq!(
+            envmap(&[("RUSTFLAGS", " -Dwarnings ")]).rustflags(),
+            "-Dwarnings"
+        );
+        assert_eq!(envmap(&[]).rustflags(), "");
+    }
+
+    #[test]
+    fn linker() {
+        let envmap = |vars: &[(&str, &str)]| {
+            super::EnvironmentMap(
+                vars.iter()
+                    .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                    .collect(),
+            )
+        };
+        let target = ("TARGET", "thumbv7em-none-eabihf");
+        let target_linker = (
+            "CARGO_TARGET_THUMBV7EM_NONE_EABIHF_LINKER",
+            "arm-none-eabi-gcc",
+        );
+        assert_eq!(
+            envmap(&[target, target_linker, ("RUSTC_LINKER", "rust-lld")])
+                .linker()
+                .unwrap(),
+            Some("rust-lld")
+        );
+        assert_eq!(
+            envmap(&[target, target_linker]).linker().unwrap(),
+            Some("arm-none-eabi-gcc")
+        );
+        assert_eq!(envmap(&[target]).linker().unwrap(), None);
+    }
+
+    #[test]
+    fn parse_version_output() {
+        use super::parse_version_output;
+
+        let version = "rustc 1.80.0 (051478957 2024-07-21)";
+        assert_eq!(
+            parse_version_output(b"rustc 1.80.0 (051478957 2024-07-21)\n").as_deref(),
+            Some(version)
+        );
+        assert_eq!(
+            parse_version_output(b"rustc 1.80.0 (051478957 2024-07-21)\r\n").as_deref(),
+            Some(version)
+        );
+        assert_eq!(
+            parse_version_output(b"rustc 1.80.0 (051478957 2024-07-21)").as_deref(),
+            Some(version)
+        );
+        assert_eq!(
+            parse_version_output(
+                b"info: syncing channel updates\r\n\xffnotice\n  rustc 1.82.0-nightly (abc 2024-08-01)\n"
+            )
+            .as_deref(),
+            Some("rustc 1.82.0-nightly (abc 2024-08-01)")
+        );
+        assert_eq!(parse_version_output(b""), None);
+        assert_eq!(parse_version_output(b"command not found\n"), None);
+        assert_eq!(parse_version_output(b"1.2 is not a version\n"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_vars() {
+        use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+
+        let vars = || {
+            [
+                (OsString::from("CARGO_PKG_NAME"), OsString::from("foo")),
+                (
+                    OsString::from("CARGO_PKG_DESCRIPTION"),
+                    OsString::from_vec(b"f\xffo".to_vec()),
+                ),
+            ]
+        };
+        let mut invalid = Vec::new();
+        let envmap = super::EnvironmentMap::from_vars(vars(), |name| {
+            invalid.push(name.to_owned());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(invalid, ["CARGO_PKG_DESCRIPTION"]);
+        assert_eq!(envmap.get("CARGO_PKG_NAME").unwrap(), "foo");
+        assert_eq!(envmap.get("CARGO_PKG_DESCRIPTION").unwrap(), "f\u{fffd}o");
+
+        assert!(
+            super::EnvironmentMap::from_vars(vars(), |_| Err(std::io::Error::other("strict")))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn simplify_path() {
+        use super::simplify_path;
+
+        assert_eq!(
+            simplify_path(r"\\?\C:\Users\rustc.exe"),
+            r"C:\Users\rustc.exe"
+        );
+        assert_eq!(
+            simplify_path(r"\\?\UNC\server\share\rustc.exe"),
+            r"\\server\share\rustc.exe"
+        );
+        assert_eq!(simplify_path("rustc"), "rustc");
+        assert_eq!(simplify_path("/usr/bin/rustc"), "/usr/bin/rustc");
+        assert_eq!(simplify_path(r"C:\Users\rustc.exe"), r"C:\Users\rustc.exe");
+        for verbatim in [
+            r"\\?\C:\Users\..\rustc.exe",
+            r"\\?\C:\Users\con.txt",
+            r"\\?\C:\Users\trailing. ",
+            r"\\?\C:\Users/rustc.exe",
+            r"\\?\Volume{00000000-0000-0000-0000-000000000000}\rustc.exe",
+        ] {
+            assert_eq!(simplify_path(verbatim), verbatim);
+        }
+        let long = format!(r"\\?\C:\{}", "a".repeat(300));
+        assert_eq!(simplify_path(&long), long);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn version_from_cmd_timeout() {
+        use std::{os::unix::fs::PermissionsExt, time};
+
+        let root = tempfile::tempdir().unwrap();
+        let executable = root.path().join("slow-rustc");
+        std::fs::write(&executable, "#!/bin/sh\nsleep 10\necho rustc 1.0.0\n").unwrap();
+        std::fs::set_permissions(&executable, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let started = time::Instant::now();
+        let err = super::get_version_from_cmd(
+            executable.as_os_str(),
+            Some(time::Duration::from_millis(100)),
+            1,
+        )
+        .unwrap_err();
+        assert!(started.elapsed() < time::Duration::from_secs(5));
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+        assert!(err.to_string().contains("slow-rustc -V"));
+        assert!(err.to_string().contains("2 attempt(s)"));
+
+        std::fs::write(&executable, "#!/bin/sh\necho rustc 1.0.0\n").unwrap();
+        assert_eq!(
+            super::get_version_from_cmd(
+                executable.as_os_str(),
+                Some(time::Duration::from_secs(10)),
+                0
+            )
+            .unwrap(),
+            "rustc 1.0.0"
+        );
+
+        // More output than fits into the pipe
+        std::fs::write(
+            &executable,
+            "#!/bin/sh\necho rustc 1.0.0\nhead -c 1000000 /dev/zero\n",
+        )
+        .unwrap();
+        assert_eq!(
+            super::get_version_from_cmd(
+                executable.as_os_str(),
+                Some(time::Duration::from_secs(10)),
+                0
+            )
+            .unwrap(),
+            "rustc 1.0.0"
+        );
+    }
+
+    #[test]
+    fn first_line() {
+        assert_eq!(super::first_line(b""), None);
+        assert_eq!(
+            super::first_line(b"\r\nMicrosoft Windows [Version 10.0.19045.3803]\r\n"),
+            Some("Microsoft Windows [Version 10.0.19045.3803]".to_owned())
+        );
+        assert_eq!(
+            super::first_line(b"Linux 6.8.0 x86_64\n"),
+            Some("Linux 6.8.0 x86_64".to_owned())
+        );
+    }
+
+    #[test]
+    fn parse_opt_level() {
+        assert_eq!(super::parse_opt_level("0"), ("O0", Some(0)));
+        assert_eq!(super::parse_opt_level("3"), ("O3", Some(3)));
+        assert_eq!(super::parse_opt_level("z"), ("MinSize", None));
+        assert_eq!(super::parse_opt_level("4"), ("Other", None));
+    }
+
+    /// The `DeclaredManifest` of a package which is not part of a workspace.
+    #[cfg(feature = "manifest")]
+    fn declared(manifest: &str) -> super::DeclaredManifest {
+        super::DeclaredManifest::parse(manifest, manifest)
+    }
+
+    #[test]
+    fn codegen_option() {
+        let flags = [
+            "-C",
+            "lto=thin",
+            "-Ccodegen-units=1",
+            "--codegen",
+            "strip",
+            "-Clto=fat",
+        ];
+        assert_eq!(super::codegen_option(&flags, "lto"), Some("fat"));
+        assert_eq!(super::codegen_option(&flags, "codegen-units"), Some("1"));
+        assert_eq!(super::codegen_option(&flags, "strip"), Some("true"));
+        assert_eq!(super::codegen_option(&flags, "panic"), None);
+    }
+
+    #[cfg(feature = "manifest")]
+    #[test]
+    fn profile_setting() {
+        let manifest = declared(
+            r#"
+[profile.release]
+lto = true
+codegen-units = 1 # for size
+
+[profile.dist]
+inherits = "release"
+strip = "symbols"
+"#,
+        );
+        let envmap = |vars: &[(&str, &str)]| {
+            super::EnvironmentMap(
+                vars.iter()
+                    .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                    .collect(),
+            )
+        };
+        let env = envmap(&[
+            (
+                "OUT_DIR",
+                "/work/target/x86_64-unknown-linux-gnu/dist/build/foo-0123/out",
+            ),
+            ("PROFILE", "release"),
+            ("CARGO_PROFILE_DIST_CODEGEN_UNITS", "4"),
+        ]);
+        assert_eq!(env.profile_name(), "dist");
+        let setting = |key| env.profile_setting(&manifest, "dist", key);
+        assert_eq!(setting("lto").as_deref(), Some("true"));
+        assert_eq!(setting("codegen-units").as_deref(), Some("4"));
+        assert_eq!(setting("strip").as_deref(), Some("symbols"));
+        assert_eq!(setting("panic"), None);
+
+        let env = envmap(&[
+            ("OUT_DIR", "/work/target/debug/build/foo-0123/out"),
+            ("CARGO_ENCODED_RUSTFLAGS", "-C\x1flto=off"),
+        ]);
+        assert_eq!(env.profile_name(), "dev");
+        assert_eq!(
+            env.profile_setting(&manifest, "release", "lto").as_deref(),
+            Some("off")
+        );
+        assert_eq!(envmap(&[("PROFILE", "release")]).profile_name(), "release");
+        assert_eq!(
+            envmap(&[
+                ("CARGO_PROFILE", "bench-lto"),
+                ("OUT_DIR", "/work/target/release/build/foo-0123/out")
+            ])
+            .profile_name(),
+            "bench-lto"
+        );
+    }
+
+    #[cfg(feature = "manifest")]
+    #[test]
+    fn authors_list() {
+        let manifest = declared("[package]\nauthors = [\"Joe\", \"Harry:Potter\"]\n");
+        assert_eq!(
+            super::authors_list("Joe:Harry:Potter", &manifest),
+            ["Joe", "Harry:Potter"]
+        );
+        assert_eq!(super::authors_list("Joe:Bob", &manifest), ["Joe", "Bob"]);
+        assert!(super::authors_list("", &declared("")).is_empty());
+    }
+
+    #[test]
+    fn cpu_dispatch_level() {
+        assert_eq!(super::cpu_dispatch_level("aarch64", ["neon"]), None);
+        assert_eq!(
+            super::cpu_dispatch_level("x86_64", ["fxsr", "sse", "sse2"]),
+            Some("x86-64")
+        );
+        let v2 = [
+            "cmpxchg16b",
+            "lahfsahf",
+            "popcnt",
+            "sse3",
+            "sse4.1",
+            "sse4.2",
+            "ssse3",
+        ];
+        assert_eq!(super::cpu_dispatch_level("x86_64", v2), Some("x86-64-v2"));
+        let v3 = [
+            "avx", "avx2", "bmi1", "bmi2", "f16c", "fma", "lzcnt", "movbe", "xsave",
+        ];
+        assert_eq!(
+            super::cpu_dispatch_level("x86_64", v2.into_iter().chain(v3)),
+            Some("x86-64-v3")
+        );
+        // v4-features without v3 don't make a v4
+        assert_eq!(
+            super::cpu_dispatch_level(
+                "x86_64",
+                v2.into_iter()
+                    .chain(["avx512bw", "avx512cd", "avx512dq", "avx512f", "avx512vl"])
+            ),
+            Some("x86-64-v2")
+        );
+    }
+}
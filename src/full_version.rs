@@ -0,0 +1,63 @@
+use crate::{util::DocStr, write_str_variable, write_variable};
+use std::io;
+
+/// The constants `FULL_VERSION` is assembled from, unless set otherwise.
+pub(crate) static DEFAULT_PARTS: [&str; 3] = ["PKG_VERSION", "GIT_COMMIT_HASH_SHORT", "PROFILE"];
+
+/// Renders `FULL_VERSION` and the `full_version!()`-macro, assembled from the values
+/// of the constants `parts` in the content of a `built.rs`, separated by spaces.
+/// Constants which are not present, `None` or empty are skipped.
+pub fn render(built: &str, parts: &[&str], mut w: impl crate::report::Backend) -> io::Result<()> {
+    let items = crate::json::items(built).collect::<Vec<_>>();
+    let full_version = parts
+        .iter()
+        .filter_map(|part| {
+            let item = items.iter().find(|item| item.name == *part)?;
+            crate::tags::tag_value(item.datatype, item.value)
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let names = parts
+        .iter()
+        .map(|part| format!("`{part}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let doc = format!("The values of {names}, separated by spaces.");
+
+    write_str_variable!(w, "FULL_VERSION", full_version, doc);
+    writeln!(
+        w,
+        "#[doc={}]\n#[allow(unused_macros)]\nmacro_rules! full_version {{\n    () => {{\n        \"{}\"\n    }};\n}}",
+        DocStr(
+            "`FULL_VERSION` as a string-literal, e.g. for use in `#[doc]`-attributes or `concat!()`."
+        ),
+        full_version.escape_default()
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn render() {
+        let built = r#"#[allow(dead_code)]
+pub static PKG_VERSION: &str = "1.2.3";
+#[allow(dead_code)]
+pub static GIT_COMMIT_HASH_SHORT: Option<&str> = None;
+#[allow(dead_code)]
+pub static PROFILE: &str = "debug";
+"#;
+        let mut full_version = Vec::new();
+        super::render(built, &super::DEFAULT_PARTS, &mut full_version).unwrap();
+        let full_version = String::from_utf8(full_version).unwrap();
+        assert!(full_version.contains(r#"pub static FULL_VERSION: &str = "1.2.3 debug";"#));
+        assert!(full_version.ends_with(
+            r#"macro_rules! full_version {
+    () => {
+        "1.2.3 debug"
+    };
+}
+"#
+        ));
+    }
+}
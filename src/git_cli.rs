@@ -0,0 +1,357 @@
+use std::{io, path, process};
+
+/// Runs `git` with the given arguments in `root`.
+///
+/// Returns `Ok(None)` if `git` exited unsuccessfully, the trimmed output otherwise.
+fn git(root: &path::Path, args: &[&str]) -> io::Result<Option<String>> {
+    let output = process::Command::new("git")
+        .current_dir(root)
+        .args(args)
+        .output()?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let stdout = String::from_utf8(output.stdout).map_err(io::Error::other)?;
+    Ok(Some(stdout.trim_end().to_owned()))
+}
+
+fn git_required(root: &path::Path, args: &[&str]) -> io::Result<String> {
+    git(root, args)?.ok_or_else(|| io::Error::other(format!("`git {}` failed", args.join(" "))))
+}
+
+fn is_repository(root: &path::Path) -> io::Result<bool> {
+    Ok(git(root, &["rev-parse", "--git-dir"])?.is_some())
+}
+
+/// Retrieves the git-tag or hash describing the exact version and a boolean
+/// that indicates if the repository currently has dirty/staged files, by
+/// means of the `git`-binary.
+///
+/// If a valid git-repo can't be discovered at or above the given path,
+/// `Ok(None)` is returned instead of an `Err`-value.
+///
+/// # Errors
+/// Errors are returned if `git` can't be executed or fails on an existing repository.
+pub fn get_repo_description(root: &path::Path) -> io::Result<Option<(String, bool)>> {
+    if !is_repository(root)? {
+        return Ok(None);
+    }
+    let tag = git_required(root, &["describe", "--tags", "--always"])?;
+    let dirty = !git_required(root, &["status", "--porcelain", "--untracked-files=no"])?.is_empty();
+    Ok(Some((tag, dirty)))
+}
+
+/// Retrieves the branch name and hash of HEAD by means of the `git`-binary.
+///
+/// The returned value is a tuple of head's reference-name, long-hash and short-hash. The
+/// branch name will be `None` if the head is detached, or it's not valid UTF-8.
+///
+/// If a valid git-repo can't be discovered at or above the given path,
+/// `Ok(None)` is returned instead of an `Err`-value.
+///
+/// # Errors
+/// Errors are returned if `git` can't be executed or fails on an existing repository.
+pub fn get_repo_head(root: &path::Path) -> io::Result<Option<(Option<String>, String, String)>> {
+    if !is_repository(root)? {
+        return Ok(None);
+    }
+    let commit = git_required(root, &["rev-parse", "HEAD"])?;
+    let commit_short = git_required(root, &["rev-parse", "--short", "HEAD"])?;
+    // `symbolic-ref` fails quietly if HEAD is detached
+    let branch = git(root, &["symbolic-ref", "-q", "HEAD"])?;
+    Ok(Some((branch, commit, commit_short)))
+}
+
+/// Retrieves whether the repository is a sparse checkout and whether it uses Git LFS,
+/// by means of the `git`-binary.
+///
+/// # Errors
+/// Errors are returned if `git` can't be executed or fails on an existing repository.
+pub fn get_repo_checkout(root: &path::Path) -> io::Result<Option<(bool, bool)>> {
+    if !is_repository(root)? {
+        return Ok(None);
+    }
+    let sparse =
+        git(root, &["config", "--bool", "core.sparseCheckout"])?.as_deref() == Some("true");
+    let git_dir = git_required(root, &["rev-parse", "--absolute-git-dir"])?;
+    let workdir = git(root, &["rev-parse", "--show-toplevel"])?;
+    Ok(Some((
+        sparse,
+        crate::git::uses_lfs(
+            path::Path::new(&git_dir),
+            workdir.as_deref().map(path::Path::new),
+        ),
+    )))
+}
+
+/// Retrieves the paths of the repository's submodules and the commits they are
+/// pinned to by HEAD, by means of the `git`-binary.
+///
+/// # Errors
+/// Errors are returned if `git` can't be executed or fails on an existing repository.
+pub fn get_repo_submodules(root: &path::Path) -> io::Result<Option<Vec<(String, String)>>> {
+    if !is_repository(root)? {
+        return Ok(None);
+    }
+    let tree = git_required(root, &["ls-tree", "-r", "--full-tree", "HEAD"])?;
+    Ok(Some(
+        tree.lines()
+            .filter_map(|line| {
+                // `<mode> <type> <object>\t<path>`, submodules are of type `commit`
+                let (info, path) = line.split_once('\t')?;
+                match info.split(' ').collect::<Vec<_>>()[..] {
+                    [_, "commit", commit] => Some((path.to_owned(), commit.to_owned())),
+                    _ => None,
+                }
+            })
+            .collect(),
+    ))
+}
+
+/// Retrieves the number of commits HEAD is ahead and behind of the upstream branch,
+/// by means of the `git`-binary.
+///
+/// # Errors
+/// Errors are returned if `git` can't be executed or its output is malformed.
+pub fn get_repo_ahead_behind(root: &path::Path) -> io::Result<Option<(u32, u32)>> {
+    if !is_repository(root)? {
+        return Ok(None);
+    }
+    // Fails if HEAD is detached or there is no upstream
+    let Some(counts) = git(
+        root,
+        &["rev-list", "--left-right", "--count", "HEAD...@{upstream}"],
+    )?
+    else {
+        return Ok(None);
+    };
+    let malformed = || io::Error::other(format!("malformed output `{counts}`"));
+    let (ahead, behind) = counts.split_once('\t').ok_or_else(malformed)?;
+    Ok(Some((
+        ahead.parse().map_err(|_| malformed())?,
+        behind.parse().map_err(|_| malformed())?,
+    )))
+}
+
+/// Retrieves whether HEAD's commit is signed and the key used to sign it, by means
+/// of the `git`-binary.
+///
+/// # Errors
+/// Errors are returned if `git` can't be executed or fails on an existing repository.
+pub fn get_repo_commit_signature(root: &path::Path) -> io::Result<Option<(bool, Option<String>)>> {
+    if !is_repository(root)? {
+        return Ok(None);
+    }
+    let commit = git_required(root, &["cat-file", "commit", "HEAD"])?;
+    Ok(Some(
+        match crate::git_signature::commit_signature(&commit) {
+            Some(signature) => (true, crate::git_signature::signer(&signature)),
+            None => (false, None),
+        },
+    ))
+}
+
+/// Retrieves whether the checkout is a linked worktree by means of the `git`-binary.
+///
+/// # Errors
+/// Errors are returned if `git` can't be executed or fails on an existing repository.
+pub fn get_repo_worktree(root: &path::Path) -> io::Result<Option<bool>> {
+    if !is_repository(root)? {
+        return Ok(None);
+    }
+    let (git_dir, common_dir) =
+        get_repo_git_dirs(root)?.ok_or_else(|| io::Error::other("git-directory not found"))?;
+    Ok(Some(git_dir.canonicalize()? != common_dir.canonicalize()?))
+}
+
+/// Retrieves the repository's git-directory and common git-directory, by means
+/// of the `git`-binary.
+///
+/// # Errors
+/// Errors are returned if `git` can't be executed or fails on an existing repository.
+pub fn get_repo_git_dirs(root: &path::Path) -> io::Result<Option<(path::PathBuf, path::PathBuf)>> {
+    if !is_repository(root)? {
+        return Ok(None);
+    }
+    let git_dir = git_required(root, &["rev-parse", "--absolute-git-dir"])?;
+    // Relative to `root` unless absolute
+    let common_dir = root.join(git_required(root, &["rev-parse", "--git-common-dir"])?);
+    Ok(Some((git_dir.into(), common_dir)))
+}
+
+/// Describes HEAD relative to the most recent tag matching the glob-`pattern`, by
+/// means of the `git`-binary. `Ok(None)` is returned if no tag matches.
+///
+/// # Errors
+/// Errors are returned if `git` can't be executed.
+pub fn get_repo_describe_match(root: &path::Path, pattern: &str) -> io::Result<Option<String>> {
+    if !is_repository(root)? {
+        return Ok(None);
+    }
+    git(root, &["describe", "--tags", "--match", pattern])
+}
+
+/// Retrieves the URL of the remote `name` by means of the `git`-binary.
+///
+/// # Errors
+/// Errors are returned if `git` can't be executed.
+pub fn get_repo_remote_url(root: &path::Path, name: &str) -> io::Result<Option<String>> {
+    if !is_repository(root)? {
+        return Ok(None);
+    }
+    git(root, &["remote", "get-url", name])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path, process};
+
+    fn git(root: &path::Path, args: &[&str]) {
+        let status = process::Command::new("git")
+            .current_dir(root)
+            .args(["-c", "user.name=foo", "-c", "user.email=bar"])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn parse_git_repo() {
+        let repo_root = tempfile::tempdir().unwrap();
+        assert!(super::get_repo_description(repo_root.as_ref())
+            .unwrap()
+            .is_none());
+
+        git(
+            repo_root.as_ref(),
+            &["init", "-q", "--initial-branch=master"],
+        );
+        let cruft_file = repo_root.path().join("cruftfile");
+        fs::write(&cruft_file, "Who? Me?").unwrap();
+        let project_root = repo_root.path().join("project_root");
+        fs::create_dir(&project_root).unwrap();
+        git(repo_root.as_ref(), &["add", "cruftfile"]);
+        git(repo_root.as_ref(), &["commit", "-q", "-m", "Testing"]);
+
+        let (tag, dirty) = super::get_repo_description(&project_root).unwrap().unwrap();
+        assert!(!tag.is_empty());
+        assert!(!dirty);
+        assert_eq!(
+            super::get_repo_commit_signature(&project_root).unwrap(),
+            Some((false, None))
+        );
+
+        git(
+            repo_root.as_ref(),
+            &["tag", "-a", "foobar", "-m", "Tagged foobar"],
+        );
+        fs::write(cruft_file, "now dirty").unwrap();
+        let (tag, dirty) = super::get_repo_description(&project_root).unwrap().unwrap();
+        assert_eq!(tag, "foobar");
+        assert!(dirty);
+        assert_eq!(
+            super::get_repo_describe_match(&project_root, "foo*").unwrap(),
+            Some("foobar".to_owned())
+        );
+        assert_eq!(
+            super::get_repo_describe_match(&project_root, "v*").unwrap(),
+            None
+        );
+
+        assert_eq!(super::get_repo_ahead_behind(&project_root).unwrap(), None);
+        git(repo_root.as_ref(), &["checkout", "-q", "-b", "baz"]);
+        git(
+            repo_root.as_ref(),
+            &["branch", "-q", "--set-upstream-to", "master"],
+        );
+        git(
+            repo_root.as_ref(),
+            &["commit", "-q", "--allow-empty", "-m", "Ahead"],
+        );
+        assert_eq!(
+            super::get_repo_ahead_behind(&project_root).unwrap(),
+            Some((1, 0))
+        );
+        let (branch, commit, commit_short) = super::get_repo_head(&project_root).unwrap().unwrap();
+        assert_eq!(branch.as_deref(), Some("refs/heads/baz"));
+        assert!(commit.starts_with(&commit_short));
+
+        git(repo_root.as_ref(), &["checkout", "-q", "--detach"]);
+        let (branch, _, _) = super::get_repo_head(&project_root).unwrap().unwrap();
+        assert!(branch.is_none());
+
+        assert_eq!(
+            super::get_repo_checkout(&project_root).unwrap(),
+            Some((false, false))
+        );
+        assert_eq!(
+            super::get_repo_worktree(&project_root).unwrap(),
+            Some(false)
+        );
+        let worktree_root = tempfile::tempdir().unwrap();
+        let worktree = worktree_root.path().join("wt");
+        git(
+            repo_root.as_ref(),
+            &["worktree", "add", "-q", worktree.to_str().unwrap()],
+        );
+        assert_eq!(super::get_repo_worktree(&worktree).unwrap(), Some(true));
+        let (git_dir, common_dir) = super::get_repo_git_dirs(&worktree).unwrap().unwrap();
+        let common_dir = common_dir.canonicalize().unwrap();
+        assert_eq!(
+            common_dir,
+            repo_root.path().join(".git").canonicalize().unwrap()
+        );
+        assert_eq!(
+            git_dir.canonicalize().unwrap(),
+            common_dir.join("worktrees/wt")
+        );
+        assert_eq!(
+            super::get_repo_remote_url(&project_root, "origin").unwrap(),
+            None
+        );
+        git(
+            repo_root.as_ref(),
+            &["remote", "add", "origin", "git@example.com:foo/bar.git"],
+        );
+        assert_eq!(
+            super::get_repo_remote_url(&project_root, "origin")
+                .unwrap()
+                .as_deref(),
+            Some("git@example.com:foo/bar.git")
+        );
+        assert_eq!(
+            super::get_repo_submodules(&project_root).unwrap(),
+            Some(vec![])
+        );
+        let pin = "8f232d6ef707e1956a43342693d2a31e72989554";
+        git(
+            repo_root.as_ref(),
+            &[
+                "update-index",
+                "--add",
+                "--cacheinfo",
+                &format!("160000,{pin},vendor/sdk"),
+            ],
+        );
+        git(repo_root.as_ref(), &["commit", "-q", "-m", "Add submodule"]);
+        assert_eq!(
+            super::get_repo_submodules(&project_root).unwrap(),
+            Some(vec![("vendor/sdk".to_owned(), pin.to_owned())])
+        );
+        fs::write(
+            repo_root.path().join(".gitattributes"),
+            "*.bin filter=lfs diff=lfs merge=lfs -text",
+        )
+        .unwrap();
+        git(
+            repo_root.as_ref(),
+            &["config", "core.sparseCheckout", "true"],
+        );
+        assert_eq!(
+            super::get_repo_checkout(&project_root).unwrap(),
+            Some((true, true))
+        );
+    }
+}
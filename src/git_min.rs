@@ -0,0 +1,233 @@
+use std::{fs, io, path};
+
+/// Symbolic references are followed no further than this.
+const MAX_SYMREF_DEPTH: usize = 5;
+
+/// Length of the abbreviated commit hash.
+const SHORT_HASH_LEN: usize = 7;
+
+/// The `.git`-directory holding `HEAD` and the (common) directory holding the refs.
+struct GitDir {
+    git_dir: path::PathBuf,
+    common_dir: path::PathBuf,
+}
+
+impl GitDir {
+    /// Discovers the git-directory at or above `root`.
+    fn discover(root: &path::Path) -> io::Result<Option<Self>> {
+        for dir in root.ancestors() {
+            let dot_git = dir.join(".git");
+            let git_dir = if dot_git.is_dir() {
+                dot_git
+            } else if dot_git.is_file() {
+                // Linked worktrees and submodules use a `gitdir: <path>`-file
+                let content = fs::read_to_string(&dot_git)?;
+                let target = content
+                    .trim_end()
+                    .strip_prefix("gitdir: ")
+                    .ok_or_else(|| io::Error::other("malformed .git-file"))?;
+                dir.join(target)
+            } else {
+                continue;
+            };
+            let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+                Ok(common_dir) => git_dir.join(common_dir.trim_end()),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => git_dir.clone(),
+                Err(e) => return Err(e),
+            };
+            return Ok(Some(Self {
+                git_dir,
+                common_dir,
+            }));
+        }
+        Ok(None)
+    }
+
+    /// The content of the reference `name`, either from a loose ref or `packed-refs`.
+    fn read_ref(&self, name: &str) -> io::Result<Option<String>> {
+        // Per-worktree refs like `HEAD` live in the worktree's own git-directory
+        for dir in [&self.git_dir, &self.common_dir] {
+            match fs::read_to_string(dir.join(name)) {
+                Ok(content) => return Ok(Some(content.trim_end().to_owned())),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        match fs::read_to_string(self.common_dir.join("packed-refs")) {
+            Ok(packed) => Ok(parse_packed_refs(&packed, name).map(ToOwned::to_owned)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Looks up the hash of `name` in the content of a `packed-refs`-file.
+fn parse_packed_refs<'a>(packed: &'a str, name: &str) -> Option<&'a str> {
+    packed
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.starts_with('^'))
+        .find_map(|line| {
+            let (hash, ref_name) = line.split_once(' ')?;
+            (ref_name.trim_end() == name).then_some(hash)
+        })
+}
+
+fn is_hash(s: &str) -> bool {
+    matches!(s.len(), 40 | 64) && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Retrieves the branch name and hash of HEAD by reading the git-directory directly.
+///
+/// The returned value is a tuple of head's reference-name, long-hash and short-hash. The
+/// branch name will be `None` if the head is detached.
+///
+/// If a git-directory can't be discovered at or above the given path,
+/// `Ok(None)` is returned instead of an `Err`-value.
+///
+/// # Errors
+/// Errors are returned if the git-directory can't be read, is malformed or
+/// `HEAD` does not point to a commit.
+pub fn get_repo_head(root: &path::Path) -> io::Result<Option<(Option<String>, String, String)>> {
+    let Some(git_dir) = GitDir::discover(root)? else {
+        return Ok(None);
+    };
+    let mut name = "HEAD".to_owned();
+    let mut branch = None;
+    for _ in 0..MAX_SYMREF_DEPTH {
+        let content = git_dir
+            .read_ref(&name)?
+            .ok_or_else(|| io::Error::other(format!("reference `{name}` not found")))?;
+        match content.strip_prefix("ref: ") {
+            Some(target) => {
+                name = target.to_owned();
+                branch = Some(name.clone());
+            }
+            None if is_hash(&content) => {
+                let commit_short = content[..SHORT_HASH_LEN].to_owned();
+                return Ok(Some((branch, content, commit_short)));
+            }
+            None => return Err(io::Error::other(format!("malformed reference `{name}`"))),
+        }
+    }
+    Err(io::Error::other("too many levels of symbolic references"))
+}
+
+/// Retrieves whether the checkout is a linked worktree by reading the git-directory directly.
+///
+/// # Errors
+/// Errors are returned if the git-directory can't be read or is malformed.
+pub fn get_repo_worktree(root: &path::Path) -> io::Result<Option<bool>> {
+    Ok(GitDir::discover(root)?.map(|git_dir| git_dir.git_dir != git_dir.common_dir))
+}
+
+/// Retrieves the repository's git-directory and common git-directory.
+///
+/// # Errors
+/// Errors are returned if the git-directory can't be read or is malformed.
+pub fn get_repo_git_dirs(root: &path::Path) -> io::Result<Option<(path::PathBuf, path::PathBuf)>> {
+    Ok(GitDir::discover(root)?.map(|git_dir| (git_dir.git_dir, git_dir.common_dir)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    static HASH: &str = "ca2af4f11bb8f4f6421c4cccf428bf4862573daf";
+
+    #[test]
+    fn packed_refs() {
+        let packed = format!(
+            "# pack-refs with: peeled fully-peeled sorted\n\
+            0000000000000000000000000000000000000000 refs/heads/other\n\
+            {HASH} refs/heads/master\n\
+            ^1111111111111111111111111111111111111111\n"
+        );
+        assert_eq!(
+            super::parse_packed_refs(&packed, "refs/heads/master"),
+            Some(HASH)
+        );
+        assert_eq!(super::parse_packed_refs(&packed, "refs/heads/foo"), None);
+    }
+
+    #[test]
+    fn read_repo_head() {
+        let repo_root = tempfile::tempdir().unwrap();
+        let project_root = repo_root.path().join("project_root");
+        fs::create_dir(&project_root).unwrap();
+        assert!(super::get_repo_head(&project_root).unwrap().is_none());
+
+        let git_dir = repo_root.path().join(".git");
+        fs::create_dir_all(git_dir.join("refs/heads")).unwrap();
+        fs::write(git_dir.join("HEAD"), "ref: refs/heads/master\n").unwrap();
+        // An unborn branch
+        assert!(super::get_repo_head(&project_root).is_err());
+
+        fs::write(
+            git_dir.join("packed-refs"),
+            format!("{HASH} refs/heads/master\n"),
+        )
+        .unwrap();
+        assert_eq!(
+            super::get_repo_head(&project_root).unwrap(),
+            Some((
+                Some("refs/heads/master".to_owned()),
+                HASH.to_owned(),
+                "ca2af4f".to_owned()
+            ))
+        );
+
+        // Loose refs take precedence over packed refs
+        let loose = "0123456789012345678901234567890123456789";
+        fs::write(git_dir.join("refs/heads/master"), format!("{loose}\n")).unwrap();
+        assert_eq!(
+            super::get_repo_head(&project_root).unwrap().unwrap().1,
+            loose
+        );
+
+        fs::write(git_dir.join("HEAD"), format!("{HASH}\n")).unwrap();
+        assert_eq!(
+            super::get_repo_head(&project_root).unwrap(),
+            Some((None, HASH.to_owned(), "ca2af4f".to_owned()))
+        );
+        assert_eq!(
+            super::get_repo_worktree(&project_root).unwrap(),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn linked_worktree() {
+        let repo_root = tempfile::tempdir().unwrap();
+        let git_dir = repo_root.path().join("main/.git");
+        let wt_git_dir = git_dir.join("worktrees/wt");
+        fs::create_dir_all(git_dir.join("refs/heads")).unwrap();
+        fs::create_dir_all(&wt_git_dir).unwrap();
+        fs::write(git_dir.join("HEAD"), "ref: refs/heads/master\n").unwrap();
+        fs::write(git_dir.join("refs/heads/master"), format!("{HASH}\n")).unwrap();
+        fs::write(git_dir.join("refs/heads/wt"), format!("{HASH}\n")).unwrap();
+        fs::write(wt_git_dir.join("HEAD"), "ref: refs/heads/wt\n").unwrap();
+        fs::write(wt_git_dir.join("commondir"), "../..\n").unwrap();
+
+        let worktree = repo_root.path().join("wt");
+        fs::create_dir(&worktree).unwrap();
+        fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", wt_git_dir.display()),
+        )
+        .unwrap();
+
+        assert_eq!(super::get_repo_worktree(&worktree).unwrap(), Some(true));
+        assert_eq!(
+            super::get_repo_git_dirs(&worktree).unwrap(),
+            Some((wt_git_dir.clone(), wt_git_dir.join("../..")))
+        );
+        assert_eq!(
+            super::get_repo_head(&worktree).unwrap(),
+            Some((
+                Some("refs/heads/wt".to_owned()),
+                HASH.to_owned(),
+                "ca2af4f".to_owned()
+            ))
+        );
+    }
+}
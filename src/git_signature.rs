@@ -0,0 +1,281 @@
+/// Decodes standard base64, ignoring whitespace and padding.
+fn base64_decode(s: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(s.len() * 3 / 4);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    for c in s.bytes() {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' | b'\r' | b'\n' | b' ' => continue,
+            _ => return None,
+        };
+        acc = (acc << 6) | u32::from(v);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// Encodes standard base64, with padding.
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | u32::from(*b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3f]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{b:02X}")).collect()
+}
+
+/// The base64-body of an ASCII-armored block, without headers and checksum.
+fn armor_body(armored: &str) -> String {
+    let mut lines = armored
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| !line.starts_with("-----BEGIN"))
+        .skip(1)
+        .peekable();
+    // PGP-armor may carry headers, separated from the body by an empty line
+    if lines.clone().any(str::is_empty) && lines.peek().is_some_and(|l| l.contains(": ")) {
+        lines
+            .by_ref()
+            .take_while(|line| !line.is_empty())
+            .for_each(drop);
+    }
+    lines
+        .take_while(|line| !line.starts_with('=') && !line.starts_with("-----"))
+        .collect()
+}
+
+/// A big-endian cursor over a byte-slice.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn uint(&mut self, n: usize) -> Option<usize> {
+        Some(
+            self.take(n)?
+                .iter()
+                .fold(0, |acc, b| (acc << 8) | usize::from(*b)),
+        )
+    }
+
+    /// A length as used by new-format packets and subpackets.
+    fn new_length(&mut self) -> Option<usize> {
+        match self.u8()? {
+            o @ 0..=191 => Some(usize::from(o)),
+            o @ 192..=254 => Some(((usize::from(o) - 192) << 8) + usize::from(self.u8()?) + 192),
+            255 => self.uint(4),
+        }
+    }
+
+    /// An SSH wire-format `string`.
+    fn ssh_string(&mut self) -> Option<&'a [u8]> {
+        let len = self.uint(4)?;
+        self.take(len)
+    }
+}
+
+/// The issuer's fingerprint or key id of an OpenPGP signature-packet.
+fn pgp_signer(data: &[u8]) -> Option<String> {
+    let mut r = Reader(data);
+    let tag = r.u8()?;
+    let len = if tag & 0x40 != 0 {
+        r.new_length()?
+    } else {
+        match tag & 0x03 {
+            0 => r.uint(1)?,
+            1 => r.uint(2)?,
+            2 => r.uint(4)?,
+            _ => r.0.len(),
+        }
+    };
+    let mut body = Reader(r.take(len)?);
+    match body.u8()? {
+        3 => {
+            // hashed length, type, creation time
+            body.take(1 + 1 + 4)?;
+            Some(hex(body.take(8)?))
+        }
+        version @ 4..=6 => {
+            // type, public key algorithm, hash algorithm
+            body.take(3)?;
+            let count_len = if version == 4 { 2 } else { 4 };
+            let mut key_id = None;
+            for _ in 0..2 {
+                let subpackets_len = body.uint(count_len)?;
+                let mut subpackets = Reader(body.take(subpackets_len)?);
+                while !subpackets.0.is_empty() {
+                    let len = subpackets.new_length()?;
+                    let mut subpacket = Reader(subpackets.take(len)?);
+                    match subpacket.u8()? & 0x7f {
+                        // Issuer fingerprint, preceded by the key version
+                        33 => return Some(hex(subpacket.0.get(1..)?)),
+                        // Issuer key id
+                        16 => key_id = Some(hex(subpacket.0)),
+                        _ => {}
+                    }
+                }
+            }
+            key_id
+        }
+        _ => None,
+    }
+}
+
+/// The public key of an SSH-signature, in OpenSSH's format.
+fn ssh_signer(data: &[u8]) -> Option<String> {
+    let mut r = Reader(data);
+    if r.take(6)? != b"SSHSIG" {
+        return None;
+    }
+    r.uint(4)?;
+    let public_key = r.ssh_string()?;
+    let key_type = Reader(public_key).ssh_string()?;
+    Some(format!(
+        "{} {}",
+        String::from_utf8_lossy(key_type),
+        base64_encode(public_key)
+    ))
+}
+
+/// Extracts the key that was used to create an ASCII-armored signature as found in a
+/// commit's `gpgsig`-header: The issuer's fingerprint (or key id) in case of OpenPGP and
+/// the public key in case of SSH. `None` is returned for other kinds of signatures.
+pub fn signer(signature: &str) -> Option<String> {
+    let data = base64_decode(&armor_body(signature))?;
+    if signature.contains("-----BEGIN PGP SIGNATURE-----") {
+        pgp_signer(&data)
+    } else if signature.contains("-----BEGIN SSH SIGNATURE-----") {
+        ssh_signer(&data)
+    } else {
+        None
+    }
+}
+
+/// Extracts the signature from the raw representation of a commit-object, as
+/// printed by `git cat-file commit`.
+#[cfg(feature = "git-cli")]
+pub fn commit_signature(raw_commit: &str) -> Option<String> {
+    let mut signature: Option<String> = None;
+    for line in raw_commit.lines() {
+        if line.is_empty() {
+            // End of headers
+            break;
+        }
+        match (&mut signature, line.strip_prefix(' ')) {
+            (Some(sig), Some(continuation)) => {
+                sig.push('\n');
+                sig.push_str(continuation);
+            }
+            (Some(_), None) => break,
+            (None, _) => {
+                signature = line
+                    .strip_prefix("gpgsig ")
+                    .or_else(|| line.strip_prefix("gpgsig-sha256 "))
+                    .map(ToOwned::to_owned);
+            }
+        }
+    }
+    signature
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    pub(crate) static PGP_SIGNATURE: &str = "-----BEGIN PGP SIGNATURE-----
+
+iHUEABYIAB0WIQSB14W5y53eRo2qOYTthmEExXLY2gUCatJzNwAKCRDthmEExXLY
+2r9SAQC8W2l9TMjvSF+AWMI7pxJPDe3sDcRkSTPIJHhqIG3mEwEAqVITR6yLlGIt
+PayCEIJpw/qO6FsOUF3AttGXuXVWTAg=
+=VUUc
+-----END PGP SIGNATURE-----";
+
+    pub(crate) static SSH_SIGNATURE: &str = "-----BEGIN SSH SIGNATURE-----
+U1NIU0lHAAAAAQAAADMAAAALc3NoLWVkMjU1MTkAAAAgsV8uFlgU/SNaP+RoxlKm+NhaUU
+hllt63pWcJPos3dFAAAAADZ2l0AAAAAAAAAAZzaGE1MTIAAABTAAAAC3NzaC1lZDI1NTE5
+AAAAQLX2t585362xBTmuDRuIEao8ECJ3h+YbMsFcnUn59GTbxcbl/EbNQR8HN6iwSzBYqr
+3+p7kLNX52O+SRvORQ+QA=
+-----END SSH SIGNATURE-----";
+
+    #[test]
+    fn base64() {
+        for data in [&b""[..], b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar"] {
+            let encoded = super::base64_encode(data);
+            assert_eq!(super::base64_decode(&encoded).unwrap(), data);
+        }
+        assert_eq!(super::base64_encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(super::base64_encode(b"fooba"), "Zm9vYmE=");
+    }
+
+    #[test]
+    fn pgp_signer() {
+        assert_eq!(
+            super::signer(PGP_SIGNATURE).as_deref(),
+            Some("81D785B9CB9DDE468DAA3984ED866104C572D8DA")
+        );
+    }
+
+    #[test]
+    fn ssh_signer() {
+        assert_eq!(
+            super::signer(SSH_SIGNATURE).as_deref(),
+            Some(
+                "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILFfLhZYFP0jWj/kaMZSpvjYWlFIZZbet6VnCT6LN3RQ"
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "git-cli")]
+    fn commit_signature() {
+        let raw_commit = format!(
+            "tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+            author foo <bar> 1716639359 +0000\n\
+            committer foo <bar> 1716639359 +0000\n\
+            gpgsig {}\n\
+            \n\
+            Testing\n",
+            SSH_SIGNATURE.replace('\n', "\n ")
+        );
+        assert_eq!(
+            super::commit_signature(&raw_commit).as_deref(),
+            Some(SSH_SIGNATURE)
+        );
+        assert_eq!(
+            super::commit_signature("tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\nTesting"),
+            None
+        );
+    }
+}
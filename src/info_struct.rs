@@ -0,0 +1,109 @@
+use crate::util::DocStr;
+use std::fmt::Write;
+
+/// Adds a `'static`-lifetime to all references in `datatype`, as required for
+/// the type of a struct's field. Arrays become slices, as `serde` only implements
+/// `Serialize` for arrays of up to 32 elements.
+fn static_type(datatype: &str) -> String {
+    let datatype = match datatype
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .and_then(|s| s.rsplit_once(';'))
+    {
+        Some((item, _)) => format!("&[{item}]"),
+        None => datatype.to_owned(),
+    };
+    let mut out = String::with_capacity(datatype.len());
+    let mut chars = datatype.chars().peekable();
+    while let Some(c) = chars.next() {
+        out.push(c);
+        if c == '&' && chars.peek() != Some(&'\'') {
+            out.push_str("'static ");
+        }
+    }
+    out
+}
+
+/// Renders a `BuildInfo`-struct with one field per constant declared in the content
+/// of a `built.rs`, and a `BUILD_INFO`-constant holding the constants' values. If
+/// `serde` is `true`, the struct derives `serde::Serialize`.
+pub fn render(built: &str, serde: bool) -> String {
+    let items = crate::json::items(built).collect::<Vec<_>>();
+    let mut fields = String::new();
+    let mut values = String::new();
+    for item in &items {
+        let field = item.name.to_lowercase();
+        if let Some(doc) = &item.doc {
+            let _ = writeln!(fields, "    #[doc={}]", DocStr(doc));
+        }
+        let _ = writeln!(fields, "    pub {field}: {},", static_type(item.datatype));
+        let reference = if item.datatype.starts_with('[') {
+            "&"
+        } else {
+            ""
+        };
+        let _ = writeln!(values, "    {field}: {reference}{},", item.value);
+    }
+    format!(
+        "/// All of the above, as a single value; see `BUILD_INFO`.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy{})]
+pub struct BuildInfo {{
+{fields}}}
+/// All of the above, as a single value.
+#[allow(dead_code)]
+pub const BUILD_INFO: BuildInfo = BuildInfo {{
+{values}}};
+",
+        if serde { ", serde::Serialize" } else { "" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn static_type() {
+        assert_eq!(super::static_type("&str"), "&'static str");
+        assert_eq!(
+            super::static_type("[(&str, &[&str]); 2]"),
+            "&'static [(&'static str, &'static [&'static str])]"
+        );
+        assert_eq!(super::static_type("Option<u32>"), "Option<u32>");
+        assert_eq!(super::static_type("&'static str"), "&'static str");
+    }
+
+    #[test]
+    fn render() {
+        let built = r##"#[doc=r#"The name of the package."#]
+#[allow(dead_code)]
+pub static PKG_NAME: &str = "foo";
+#[allow(dead_code)]
+pub static GIT_DIRTY: Option<bool> = Some(true);
+#[allow(dead_code)]
+pub static FEATURES: [&str; 1] = ["FOO"];
+"##;
+        assert!(
+            super::render(built, true).contains("#[derive(Debug, Clone, Copy, serde::Serialize)]")
+        );
+        assert_eq!(
+            super::render(built, false),
+            r##"/// All of the above, as a single value; see `BUILD_INFO`.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub struct BuildInfo {
+    #[doc=r#"The name of the package."#]
+    pub pkg_name: &'static str,
+    pub git_dirty: Option<bool>,
+    pub features: &'static [&'static str],
+}
+/// All of the above, as a single value.
+#[allow(dead_code)]
+pub const BUILD_INFO: BuildInfo = BuildInfo {
+    pkg_name: "foo",
+    git_dirty: Some(true),
+    features: &["FOO"],
+};
+"##
+        );
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(not(any(feature = "chrono", feature = "time")))]
+use crate::{write_str_variable, write_variable};
+#[cfg(not(any(feature = "chrono", feature = "time")))]
+use std::io;
+
+/// Parse a time-string as formatted by `built` into a `jiff::Timestamp`.
+///
+/// ```
+/// pub mod build_info {
+///     pub static BUILT_TIME_UTC: &'static str = "Tue, 14 Feb 2017 05:21:41 GMT";
+/// }
+///
+/// assert_eq!(built::util::strptime_jiff(&build_info::BUILT_TIME_UTC).as_second(), 1487049701);
+/// ```
+///
+/// # Panics
+/// If the string can't be parsed. This should never happen with input provided
+/// by `built`.
+#[must_use]
+pub fn strptime_jiff(s: &str) -> jiff::Timestamp {
+    jiff::fmt::rfc2822::parse(s).unwrap().timestamp()
+}
+
+/// Writes the same constants as `krono::write_time()`, by means of the `jiff`-crate.
+#[cfg(not(any(feature = "chrono", feature = "time")))]
+pub fn write_time(
+    policy: crate::SourceDateEpochPolicy,
+    mut w: impl crate::report::Backend,
+) -> io::Result<()> {
+    let now = crate::source_date_epoch::get(policy, |ts| jiff::Timestamp::from_second(ts).ok())?
+        .unwrap_or_else(|| {
+            jiff::Timestamp::from_second(jiff::Timestamp::now().as_second()).unwrap()
+        });
+    write_str_variable!(
+        w,
+        "BUILT_TIME_UTC",
+        jiff::fmt::rfc2822::to_string(&now.to_zoned(jiff::tz::TimeZone::UTC))
+            .map_err(io::Error::other)?,
+        "The build time in RFC2822, UTC."
+    );
+    write_str_variable!(
+        w,
+        "BUILT_TIME_RFC3339",
+        now.to_string(),
+        "The build time in RFC3339, UTC."
+    );
+    write_variable!(
+        w,
+        "BUILT_TIME_UNIX",
+        "u64",
+        u64::try_from(now.as_second()).map_err(io::Error::other)?,
+        "The build time as seconds since the Unix epoch."
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn strptime_jiff() {
+        let t = super::strptime_jiff("Sat, 25 May 2024 12:15:59 +0000");
+        assert_eq!(t.as_second(), 1716639359);
+        assert_eq!(t.to_string(), "2024-05-25T12:15:59Z");
+    }
+}
@@ -0,0 +1,646 @@
+use crate::util::JsonStr;
+use std::{borrow, fmt, fmt::Write, io, iter, str};
+
+/// The version of the JSON-schema produced by `schema()`. Bumped whenever `CONSTANTS`
+/// or the way they are mapped to JSON changes.
+pub static SCHEMA_VERSION: u32 = 1;
+
+/// The type of a constant, as declared in `built.rs`.
+#[derive(Debug, PartialEq)]
+enum Type {
+    Str,
+    Bool,
+    Integer,
+    Option(Box<Type>),
+    Array(Box<Type>),
+    Tuple(Vec<Type>),
+}
+
+impl Type {
+    fn parse(s: &str) -> io::Result<Self> {
+        let s = s.trim();
+        Ok(if s == "&str" {
+            Self::Str
+        } else if s == "bool" {
+            Self::Bool
+        } else if ["u8", "u16", "u32", "u64", "usize"].contains(&s) {
+            Self::Integer
+        } else if let Some(inner) = s.strip_prefix("Option<").and_then(|s| s.strip_suffix('>')) {
+            Self::Option(Box::new(Self::parse(inner)?))
+        } else if let Some(inner) = s.strip_prefix("&[").and_then(|s| s.strip_suffix(']')) {
+            Self::Array(Box::new(Self::parse(inner)?))
+        } else if let Some(inner) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            let (item, _) = inner
+                .rsplit_once(';')
+                .ok_or_else(|| io::Error::other(format!("unsupported type `{s}`")))?;
+            Self::Array(Box::new(Self::parse(item)?))
+        } else if let Some(inner) = s.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+            // Tuples of tuples or of arrays of tuples are never generated
+            Self::Tuple(
+                inner
+                    .split(',')
+                    .map(Self::parse)
+                    .collect::<Result<_, _>>()?,
+            )
+        } else {
+            return Err(io::Error::other(format!("unsupported type `{s}`")));
+        })
+    }
+
+    fn schema(&self) -> String {
+        match self {
+            Self::Str => r#"{"type": "string"}"#.to_owned(),
+            Self::Bool => r#"{"type": "boolean"}"#.to_owned(),
+            Self::Integer => r#"{"type": "integer", "minimum": 0}"#.to_owned(),
+            Self::Option(inner) => {
+                format!(r#"{{"anyOf": [{}, {{"type": "null"}}]}}"#, inner.schema())
+            }
+            Self::Array(inner) => format!(r#"{{"type": "array", "items": {}}}"#, inner.schema()),
+            Self::Tuple(items) => format!(
+                r#"{{"type": "array", "prefixItems": [{}], "items": false}}"#,
+                items
+                    .iter()
+                    .map(Self::schema)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// A constant as declared in `built.rs`.
+pub(crate) struct Item<'a> {
+    pub doc: Option<borrow::Cow<'a, str>>,
+    pub name: &'a str,
+    pub datatype: &'a str,
+    pub value: &'a str,
+}
+
+/// The content of a documentation-string as written by `util::DocStr`.
+fn parse_doc(literal: &str) -> Option<borrow::Cow<'_, str>> {
+    match literal.strip_prefix('r') {
+        Some(raw) => {
+            let hashes = raw.bytes().take_while(|b| *b == b'#').count();
+            raw.get(hashes..raw.len().checked_sub(hashes)?)?
+                .strip_prefix('"')?
+                .strip_suffix('"')
+                .map(borrow::Cow::Borrowed)
+        }
+        None => unquote(literal).map(borrow::Cow::Owned),
+    }
+}
+
+/// The constants whose values are evaluated by the compiler, i.e. of an enum declared in
+/// `built.rs`, derived from other constants by a `const fn` or depending on the target
+/// `built.rs` is included in. They can't be represented in other formats nor overridden;
+/// `items()` skips them.
+pub(crate) static COMPILE_TIME_CONSTANTS: [&str; 8] = [
+    "BIN_NAME",
+    "CRATE_NAME",
+    "CI_PLATFORM_KIND",
+    "OPT_LEVEL_KIND",
+    "PKG_VERSION_MAJOR_NUMERIC",
+    "PKG_VERSION_MINOR_NUMERIC",
+    "PKG_VERSION_PATCH_NUMERIC",
+    "GIT_COMMITS_SINCE_TAG",
+];
+
+/// The constants declared in the content of a `built.rs`, except `COMPILE_TIME_CONSTANTS`.
+pub(crate) fn items(built: &str) -> impl Iterator<Item = Item<'_>> {
+    let mut doc = None;
+    built.lines().filter_map(move |line| {
+        if let Some(d) = line
+            .strip_prefix("#[doc=")
+            .and_then(|l| l.strip_suffix(']'))
+            .and_then(parse_doc)
+        {
+            doc = Some(d);
+            return None;
+        }
+        let (name, rest) = line.strip_prefix("pub static ")?.split_once(": ")?;
+        let (datatype, value) = rest.strip_suffix(';')?.split_once(" = ")?;
+        if COMPILE_TIME_CONSTANTS.contains(&name) {
+            return None;
+        }
+        Some(Item {
+            doc: doc.take(),
+            name,
+            datatype,
+            value,
+        })
+    })
+}
+
+/// Converts a Rust-expression as generated by `built` into JSON.
+struct ValueParser<'a>(iter::Peekable<str::Chars<'a>>);
+
+impl ValueParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.0.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn expect(&mut self, expected: char) -> io::Result<()> {
+        self.skip_whitespace();
+        match self.0.next() {
+            Some(c) if c == expected => Ok(()),
+            c => Err(io::Error::other(format!(
+                "expected `{expected}`, found `{c:?}`"
+            ))),
+        }
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        let mut s = String::new();
+        loop {
+            match self.0.next() {
+                Some('"') => return Ok(s),
+                Some('\\') => match self.0.next() {
+                    Some('n') => s.push('\n'),
+                    Some('r') => s.push('\r'),
+                    Some('t') => s.push('\t'),
+                    Some('0') => s.push('\0'),
+                    Some('u') => {
+                        self.expect('{')?;
+                        let hex =
+                            iter::from_fn(|| self.0.next_if(|c| *c != '}')).collect::<String>();
+                        self.expect('}')?;
+                        s.extend(u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32));
+                    }
+                    Some(c) => s.push(c),
+                    None => break,
+                },
+                Some(c) => s.push(c),
+                None => break,
+            }
+        }
+        Err(io::Error::other("unterminated string"))
+    }
+
+    fn list(&mut self, close: char) -> io::Result<Vec<Value>> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.0.next_if_eq(&close).is_some() {
+                break;
+            }
+            if !items.is_empty() {
+                self.expect(',')?;
+                self.skip_whitespace();
+                // Trailing comma
+                if self.0.next_if_eq(&close).is_some() {
+                    break;
+                }
+            }
+            items.push(self.value()?);
+        }
+        Ok(items)
+    }
+
+    fn value(&mut self) -> io::Result<Value> {
+        self.skip_whitespace();
+        Ok(match self.0.next() {
+            Some('"') => Value::Str(self.string()?),
+            Some('[') => Value::List(self.list(']')?),
+            Some('&') => self.value()?,
+            Some('(') => Value::List(self.list(')')?),
+            Some(c) if c.is_ascii_alphanumeric() => {
+                let word = iter::once(c)
+                    .chain(iter::from_fn(|| {
+                        self.0.next_if(|c| c.is_ascii_alphanumeric() || *c == '_')
+                    }))
+                    .collect::<String>();
+                match word.as_str() {
+                    "None" => Value::Null,
+                    "true" => Value::Bool(true),
+                    "false" => Value::Bool(false),
+                    "Some" => {
+                        self.expect('(')?;
+                        let value = self.value()?;
+                        self.expect(')')?;
+                        value
+                    }
+                    w => Value::Integer(
+                        w.parse()
+                            .map_err(|_| io::Error::other(format!("unsupported value `{w}`")))?,
+                    ),
+                }
+            }
+            c => return Err(io::Error::other(format!("unexpected `{c:?}`"))),
+        })
+    }
+}
+
+/// A value as generated by `built`, with Rust's `Option`s, tuples and arrays mapped
+/// to what is available in most data-formats.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Integer(u64),
+    Str(String),
+    List(Vec<Value>),
+}
+
+impl Value {
+    /// Parses the Rust-expression `value` of the constant `name`.
+    pub fn parse(name: &str, value: &str) -> io::Result<Self> {
+        let mut parser = ValueParser(value.chars().peekable());
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.0.next().is_some() {
+            return Err(io::Error::other(format!("trailing characters in `{name}`")));
+        }
+        Ok(value)
+    }
+}
+
+/// Renders a value as JSON, which is also valid as a TOML- or YAML-flow-value.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => f.write_str("null"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Integer(i) => write!(f, "{i}"),
+            Self::Str(s) => write!(f, "{}", JsonStr(s)),
+            Self::List(items) => {
+                f.write_char('[')?;
+                for (idx, item) in items.iter().enumerate() {
+                    if idx != 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                f.write_char(']')
+            }
+        }
+    }
+}
+
+/// The content of the string-literal `value`, if it is one.
+pub(crate) fn unquote(value: &str) -> Option<String> {
+    let mut parser = ValueParser(value.strip_prefix('"')?.chars().peekable());
+    let s = parser.string().ok()?;
+    parser.0.next().is_none().then_some(s)
+}
+
+/// Every constant `built` can produce, except `COMPILE_TIME_CONSTANTS` and those named
+/// by the user, as `(name, type, description, always present)`. `schema()` is derived
+/// from this alone; `SCHEMA_VERSION` is to be bumped whenever it changes.
+static CONSTANTS: [(&str, &str, &str, bool); 138] = [
+    ("CI_PLATFORM", "Option<&str>", "The Continuous Integration platform detected during compilation.", true),
+    ("CI_BUILD_NUMBER", "Option<&str>", "The number of the CI-build, as given by the CI-platform; `None` if not known.", true),
+    ("CI_BUILD_URL", "Option<&str>", "The URL of the CI-build or -job; `None` if not known.", true),
+    ("CI_PULL_REQUEST", "Option<&str>", "The number of the pull- or merge-request the CI-build was triggered by; `None` if not known or not triggered by one.", true),
+    ("CI_PIPELINE_ID", "Option<&str>", "The id of the CI-pipeline or -workflow; `None` if not known.", true),
+    ("PKG_VERSION", "&str", "The full version.", true),
+    ("PKG_VERSION_MAJOR", "&str", "The major version.", true),
+    ("PKG_VERSION_MINOR", "&str", "The minor version.", true),
+    ("PKG_VERSION_PATCH", "&str", "The patch version.", true),
+    ("PKG_VERSION_PRE", "&str", "The pre-release version.", true),
+    ("PKG_AUTHORS", "&str", "A colon-separated list of authors.", true),
+    ("PKG_NAME", "&str", "The name of the package.", true),
+    ("PKG_DESCRIPTION", "&str", "The description.", true),
+    ("PKG_HOMEPAGE", "&str", "The homepage.", true),
+    ("PKG_LICENSE", "&str", "The license.", true),
+    ("PKG_REPOSITORY", "&str", "The source repository as advertised in Cargo.toml.", true),
+    ("TARGET", "&str", "The target triple that was being compiled for.", true),
+    ("HOST", "&str", "The host triple of the rust compiler.", true),
+    ("PROFILE", "&str", "`release` for release builds, `debug` for other builds.", true),
+    ("RUSTC", "&str", "The compiler that cargo resolved to use.", true),
+    ("RUSTDOC", "&str", "The documentation generator that cargo resolved to use.", true),
+    ("LINKER", "Option<&str>", "The linker cargo resolved to use, if one was configured.", true),
+    ("PROFILE_NAME", "&str", "The name of the profile used during compilation, e.g. `dev`, `release` or a custom profile; unlike `PROFILE`, which only distinguishes `release` and `debug`.", true),
+    ("PKG_SEMVER", "(u64, u64, u64, &str, &str)", "The version as a tuple of major, minor, patch, pre-release and build-metadata.", true),
+    ("PKG_AUTHORS_LIST", "&[&str]", "The authors, as declared in Cargo.toml.", true),
+    ("PKG_RUST_VERSION", "&str", "The minimum supported Rust version, as declared in Cargo.toml; empty if not declared.", true),
+    ("PKG_README", "&str", "The path to the README-file, as declared in Cargo.toml; empty if not declared.", true),
+    ("PKG_LICENSE_FILE", "&str", "The path to the license-file, as declared in Cargo.toml; empty if not declared.", true),
+    ("PKG_DOCUMENTATION", "&str", "The URL of the documentation, as declared in Cargo.toml; empty if not declared.", true),
+    ("PKG_KEYWORDS", "&[&str]", "The keywords, as declared in Cargo.toml.", true),
+    ("PKG_KEYWORDS_STR", "&str", "The keywords as a comma-separated string.", true),
+    ("PKG_CATEGORIES", "&[&str]", "The categories, as declared in Cargo.toml.", true),
+    ("PKG_CATEGORIES_STR", "&str", "The categories as a comma-separated string.", true),
+    ("OPT_LEVEL", "&str", "Value of OPT_LEVEL for the profile used during compilation.", true),
+    ("OPT_LEVEL_NUMERIC", "Option<u8>", "The numeric value of OPT_LEVEL; `None` if optimizing for size (`s` or `z`).", true),
+    ("NUM_JOBS", "u32", "The parallelism that was specified during compilation.", true),
+    ("DEBUG", "bool", "Value of DEBUG for the profile used during compilation.", true),
+    ("DEBUGINFO", "&str", "The debuginfo-level of the profile used during compilation as it was configured, e.g. `0`, `line-tables-only` or `true`.", true),
+    ("RUSTFLAGS", "&str", "The extra flags passed to the compiler, e.g. by means of `RUSTFLAGS`, separated by spaces.", true),
+    ("LTO", "Option<&str>", "The `lto`-setting of the profile used during compilation; `None` if not configured.", true),
+    ("CODEGEN_UNITS", "Option<u32>", "The `codegen-units`-setting of the profile used during compilation; `None` if not configured.", true),
+    ("PANIC_STRATEGY", "Option<&str>", "The panic-strategy, given by `CARGO_CFG_PANIC` or the profile used during compilation; `None` if not configured.", true),
+    ("STRIP", "Option<&str>", "The `strip`-setting of the profile used during compilation; `None` if not configured.", true),
+    ("FEATURES", "&[&str]", "The features that were enabled during compilation.", true),
+    ("FEATURES_STR", "&str", "The features as a comma-separated string.", true),
+    ("FEATURES_LOWERCASE", "&[&str]", "The features as above, as lowercase strings.", true),
+    ("FEATURES_LOWERCASE_STR", "&str", "The feature-string as above, from lowercase strings.", true),
+    ("RUSTC_VERSION", "&str", "The output of `rustc -V`.", true),
+    ("RUSTDOC_VERSION", "&str", "The output of `rustdoc -V`; empty string if `rustdoc -V` failed to execute.", true),
+    ("CARGO_VERSION", "&str", "The output of `cargo -V`; empty string if `cargo -V` failed to execute.", true),
+    ("CFG_TARGET_ARCH", "&str", "The target architecture, given by `CARGO_CFG_TARGET_ARCH`.", true),
+    ("CFG_ENDIAN", "&str", "The endianness, given by `CARGO_CFG_TARGET_ENDIAN`.", true),
+    ("CFG_ENV", "&str", "The toolchain-environment, given by `CARGO_CFG_TARGET_ENV`.", true),
+    ("CFG_FAMILY", "&str", "The OS-family, given by `CARGO_CFG_TARGET_FAMILY`.", true),
+    ("CFG_OS", "&str", "The operating system, given by `CARGO_CFG_TARGET_OS`.", true),
+    ("CFG_POINTER_WIDTH", "&str", "The pointer width, given by `CARGO_CFG_TARGET_POINTER_WIDTH`.", true),
+    ("CPU_DISPATCH_LEVEL", "Option<&str>", "The microarchitecture level (e.g. `x86-64-v3`) implied by the target features enabled via `-C target-cpu` or `-C target-feature`; `None` if the target is not `x86_64`.", true),
+    ("CFG_TARGET_FEATURES", "&[&str]", "The target features, given by `CARGO_CFG_TARGET_FEATURE`.", true),
+    ("CFG_TARGET_FEATURES_STR", "&str", "The target features as a comma-separated string.", true),
+    ("CFG_ALL", "&[(&str, &str)]", "All `cfg`-options given by `CARGO_CFG_*` and their values; multiple values of the same option are separated by commas.", true),
+    ("GIT_VERSION", "Option<&str>", "If the crate was compiled from within a git-repository, `GIT_VERSION` contains HEAD's tag. The short commit id is used if HEAD is not tagged.", false),
+    ("GIT_DIRTY", "Option<bool>", "If the repository had dirty/staged files.", false),
+    ("GIT_SHALLOW", "Option<bool>", "If the crate was compiled from within a git-repository, `GIT_SHALLOW` indicates whether the repository is a shallow clone, in which case `GIT_VERSION` may refer to the commit id instead of a tag.", false),
+    ("GIT_HEAD_REF", "Option<&str>", "If the crate was compiled from within a git-repository, `GIT_HEAD_REF` contains full name to the reference pointed to by HEAD (e.g.: `refs/heads/master`). If HEAD is detached or the branch name is not valid UTF-8 `None` will be stored.", false),
+    ("GIT_COMMIT_HASH", "Option<&str>", "If the crate was compiled from within a git-repository, `GIT_COMMIT_HASH` contains HEAD's full commit SHA-1 hash.", false),
+    ("GIT_COMMIT_HASH_SHORT", "Option<&str>", "If the crate was compiled from within a git-repository, `GIT_COMMIT_HASH_SHORT` contains HEAD's short commit SHA-1 hash.", false),
+    ("GIT_OPERATION_IN_PROGRESS", "Option<&str>", "If the crate was compiled from within a git-repository while a `merge`, `rebase`, `am`, `cherry-pick`, `revert` or `bisect` was in progress, `GIT_OPERATION_IN_PROGRESS` contains the name of the operation.", false),
+    ("GIT_SPARSE_CHECKOUT", "Option<bool>", "If the crate was compiled from within a git-repository, `GIT_SPARSE_CHECKOUT` indicates whether the repository is a sparse checkout.", false),
+    ("GIT_LFS_PRESENT", "Option<bool>", "If the crate was compiled from within a git-repository, `GIT_LFS_PRESENT` indicates whether the repository uses Git LFS.", false),
+    ("GIT_AHEAD", "Option<u32>", "If the crate was compiled from within a git-repository, `GIT_AHEAD` contains the number of commits HEAD is ahead of the upstream branch. `None` if HEAD is detached or there is no upstream branch.", false),
+    ("GIT_BEHIND", "Option<u32>", "If the crate was compiled from within a git-repository, `GIT_BEHIND` contains the number of commits HEAD is behind the upstream branch. `None` if HEAD is detached or there is no upstream branch.", false),
+    ("GIT_IS_WORKTREE", "Option<bool>", "If the crate was compiled from within a git-repository, `GIT_IS_WORKTREE` indicates whether the checkout is a linked worktree (see `git worktree`).", false),
+    ("GIT_COMMIT_SIGNED", "Option<bool>", "If the crate was compiled from within a git-repository, `GIT_COMMIT_SIGNED` indicates whether HEAD's commit carries a signature. The signature is not verified.", false),
+    ("GIT_COMMIT_SIGNER", "Option<&str>", "If HEAD's commit is signed, `GIT_COMMIT_SIGNER` contains the issuer's fingerprint (or key id) of an OpenPGP-signature or the public key of an SSH-signature.", false),
+    ("GIT_SUBMODULES", "&[(&str, &str)]", "The paths of the repository's submodules and the commits they are pinned to by HEAD.", false),
+    ("GIT_SUBMODULES_STR", "&str", "The submodules as a comma-separated string.", false),
+    ("GIT_REMOTE_URL", "Option<&str>", "If the crate was compiled from within a git-repository, `GIT_REMOTE_URL` contains the URL of the remote selected by `Options::set_git_remote()`, without any credentials.", false),
+    ("BUILT_FROM_FORK", "Option<bool>", "Whether `GIT_REMOTE_URL` differs from `PKG_REPOSITORY`. `None` if either of them is unknown.", false),
+    ("BUILT_VERSION", "&str", "The version of `built` which produced this file.", true),
+    ("BUILT_FEATURES", "&[&str]", "The features of `built` which were enabled when this file was produced.", true),
+    ("BUILT_COLLECTORS", "&[&str]", "The collectors which contributed to this file as `name=enabled`, and those which did not, due to features or options, as `name=disabled`.", true),
+    ("BUILT_BANNER", "&[u8]", "The package's name and version, HEAD's short commit hash and whether the repository was dirty, as a NUL-terminated string. It is kept by the compiler, but may still be removed from the binary by the linker or by stripping.", true),
+    ("BUILT_SCHEMA_VERSION", "u32", "The version of the JSON-schema describing the information in this file.", true),
+    ("BUILT_SIGNATURE", "Option<&str>", "The path to the signature of this file, if it was signed during compilation.", true),
+    ("BUILD_TAGS", "&[(&str, &str)]", "Key/value-pairs for attaching to telemetry, e.g. as OpenTelemetry resource-attributes. Keys are lowercase and dot-separated, like `pkg.version`, `git.commit` or `rustc.version`, and are stable across versions of `built`. Values which are unknown or empty are omitted.", true),
+    ("FULL_VERSION", "&str", "The values of the constants selected by `Options::set_full_version_parts()`, separated by spaces.", true),
+    ("BUILD_HOST", "Option<&str>", "The hostname of the machine which compiled the crate, if known.", false),
+    ("BUILD_USER", "Option<&str>", "The name of the user who compiled the crate, given by `USER`, `LOGNAME` or `USERNAME`.", false),
+    ("BUILD_HOST_OS", "Option<&str>", "The operating system of the machine which compiled the crate, as reported by `uname -srm` or `ver`.", false),
+    ("BUILT_TIME_UTC", "&str", "The build time in RFC2822, UTC.", false),
+    ("BUILT_TIME_RFC3339", "&str", "The build time in RFC3339, UTC.", false),
+    ("BUILT_TIME_UNIX", "u64", "The build time as seconds since the Unix epoch.", false),
+    ("FEATURES_FOREIGN", "&[&str]", "`CARGO_FEATURE_*`-variables set during compilation which do not refer to a feature declared by the manifest.", false),
+    ("CAPTURED_ENV", "&[(&str, Option<&str>)]", "The environment-variables selected by `Options::set_captured_env_vars()` and their values during compilation; `None` if a variable was not set.", false),
+    ("TOOLCHAIN_CHANNEL", "Option<&str>", "The channel pinned by `rust-toolchain.toml` or `rust-toolchain`, if any.", false),
+    ("TOOLCHAIN_COMPONENTS", "&[&str]", "The components required by `rust-toolchain.toml`.", false),
+    ("TOOLCHAIN_TARGETS", "&[&str]", "The targets required by `rust-toolchain.toml`.", false),
+    ("DEPENDENCIES", "&[(&str, &str)]", "An array of effective dependencies as documented by `Cargo.lock`.", false),
+    ("DEPENDENCIES_STR", "&str", "The effective dependencies as a comma-separated string.", false),
+    ("DEPENDENCIES_COUNT", "usize", "The number of effective dependencies.", false),
+    ("DEPENDENCIES_PRUNED_COUNT", "usize", "The number of dependencies omitted from `DEPENDENCIES` because they are deeper in the dependency-graph than selected by `Options::set_dependency_depth()`.", false),
+    ("DIRECT_DEPENDENCIES", "&[(&str, &str)]", "An array of direct dependencies as documented by `Cargo.lock`.", false),
+    ("DIRECT_DEPENDENCIES_STR", "&str", "The direct dependencies as a comma-separated string.", false),
+    ("DIRECT_DEPENDENCIES_COUNT", "usize", "The number of direct dependencies.", false),
+    ("INDIRECT_DEPENDENCIES", "&[(&str, &str)]", "An array of indirect dependencies as documented by `Cargo.lock`.", false),
+    ("INDIRECT_DEPENDENCIES_STR", "&str", "The indirect dependencies as a comma-separated string.", false),
+    ("INDIRECT_DEPENDENCIES_COUNT", "usize", "The number of indirect dependencies.", false),
+    ("INDIRECT_DEPENDENCIES_PRUNED_COUNT", "usize", "The number of dependencies omitted from `INDIRECT_DEPENDENCIES` because they are deeper in the dependency-graph than selected by `Options::set_dependency_depth()`.", false),
+    ("DEPENDENCIES_CHECKSUMS", "&[(&str, &str, &str)]", "The names, versions and SHA-256 checksums of all packages in `Cargo.lock` which carry a checksum, i.e. those from a registry.", false),
+    ("DEPENDENCY_SOURCES", "&[(&str, &str, &str)]", "The names, versions and sources of all packages in `Cargo.lock`. The source is `crates.io`, `path` for local packages, or the URL of the registry or git-repository, the latter including the revision.", false),
+    ("REGISTRIES_USED", "&[&str]", "The registries from which packages in `Cargo.lock` originate; `crates.io` or the URL of an alternative registry. See `DEPENDENCY_SOURCES` for the registry of each package.", false),
+    ("DUPLICATE_DEPENDENCIES", "&[(&str, &[&str])]", "The names of packages which appear with more than one version in `Cargo.lock`, and their versions.", false),
+    ("SBOM_JSON", "&str", "The software bill of materials, as a CycloneDX- or SPDX-JSON-document.", false),
+    ("VULNERABLE_DEPENDENCIES", "&[(&str, &str, &str)]", "The dependencies' names, versions and the ids of the RustSec-advisories affecting them, as found in the local advisory-db.", false),
+    ("PKG_EDITION", "Option<&str>", "The edition of the package, as declared in `Cargo.toml`.", false),
+    ("PKG_DECLARED_FEATURES", "&[&str]", "The features declared in `Cargo.toml`, including those implied by optional dependencies.", false),
+    ("PKG_METADATA", "&str", "The `[package.metadata]`-table of `Cargo.toml`, as TOML.", false),
+    ("LIB_TARGET", "Option<&str>", "The name of the package's library-target, if any.", false),
+    ("BIN_TARGETS", "&[&str]", "The names of the package's binary-targets.", false),
+    ("PROFILE_OVERRIDES", "&[(&str, &str, &str, &str)]", "The settings of the `[profile.<name>.package.<spec>]`-tables in the workspace's `Cargo.toml`, as `(profile, package, setting, value)`, the value as TOML.", false),
+    ("WORKSPACE_ROOT", "Option<&str>", "The directory of the workspace the package was built in, if any.", false),
+    ("WORKSPACE_MEMBERS", "&[(&str, &str)]", "The names and versions of the members of the workspace the package was built in.", false),
+    ("RUNTIME_DEPENDENCIES", "&[(&str, &str)]", "The dependencies required at runtime, as resolved by `cargo metadata`.", false),
+    ("RUNTIME_DEPENDENCIES_STR", "&str", "The dependencies as above, as a comma-separated string.", false),
+    ("BUILD_DEPENDENCIES", "&[(&str, &str)]", "The dependencies only required to build the package, e.g. by build-scripts and procedural macros, as resolved by `cargo metadata`.", false),
+    ("BUILD_DEPENDENCIES_STR", "&str", "The dependencies as above, as a comma-separated string.", false),
+    ("DEV_DEPENDENCIES", "&[(&str, &str)]", "The dependencies only required for tests, examples and benchmarks, as resolved by `cargo metadata`.", false),
+    ("DEV_DEPENDENCIES_STR", "&str", "The dependencies as above, as a comma-separated string.", false),
+    ("DEPENDENCY_LICENSES", "&[(&str, &str, &str)]", "The dependencies' names, versions and SPDX license-expressions, as reported by `cargo metadata`. Packages which do not declare a license-expression are reported as `NOASSERTION`.", false),
+    ("LICENSE_SUMMARY_STR", "&str", "The distinct license-expressions of all dependencies and the number of dependencies using them, as a comma-separated string.", false),
+    ("BUILT_COLLECTION_STATS", "&[(&str, &str)]", "The status of each collector while producing this file (`ok`, `failed`, `skipped` or `overridden`) and the time it took, e.g. `ok 1.2ms`.", false),
+    ("ALL", "&[(&str, &str)]", "The names and values of all of the above constants. String-values are recorded as they are, all other values as Rust-expressions.", false),
+    ("DEPENDENCIES_PACKED", "&str", "The names and versions of the effective dependencies, back to back. Use `dependencies()` to iterate over them.", false),
+    ("DEPENDENCIES_OFFSETS", "&[u32]", "The byte-offsets of the names and versions in `DEPENDENCIES_PACKED`.", false),
+    ("DIRECT_DEPENDENCIES_PACKED", "&str", "The names and versions of the direct dependencies, back to back. Use `direct_dependencies()` to iterate over them.", false),
+    ("DIRECT_DEPENDENCIES_OFFSETS", "&[u32]", "The byte-offsets of the names and versions in `DIRECT_DEPENDENCIES_PACKED`.", false),
+    ("INDIRECT_DEPENDENCIES_PACKED", "&str", "The names and versions of the indirect dependencies, back to back. Use `indirect_dependencies()` to iterate over them.", false),
+    ("INDIRECT_DEPENDENCIES_OFFSETS", "&[u32]", "The byte-offsets of the names and versions in `INDIRECT_DEPENDENCIES_PACKED`.", false),
+];
+
+/// The JSON-schema describing the document rendered by `report::render()`. The schema
+/// is the same for every build: Only the constants which are always present are
+/// required, and the constants named by `Options::set_git_describe()` or prefixed by
+/// `Options::add_git_checkout()` are allowed as additional properties.
+pub fn schema() -> io::Result<String> {
+    let mut properties = String::new();
+    let mut required = Vec::new();
+    for (idx, (name, datatype, doc, always)) in CONSTANTS.iter().enumerate() {
+        let mut schema = if *name == "BUILT_SCHEMA_VERSION" {
+            format!(r#"{{"const": {SCHEMA_VERSION}}}"#)
+        } else {
+            Type::parse(datatype)?.schema()
+        };
+        schema.pop();
+        let _ = write!(schema, r#", "description": {}}}"#, JsonStr(doc));
+        let _ = write!(
+            properties,
+            "{}\n    {}: {schema}",
+            if idx == 0 { "" } else { "," },
+            JsonStr(name)
+        );
+        if *always {
+            required.push(JsonStr(name).to_string());
+        }
+    }
+    Ok(format!(
+        r#"{{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "title": {},
+  "type": "object",
+  "properties": {{{properties}
+  }},
+  "required": [{}],
+  "additionalProperties": true
+}}
+"#,
+        JsonStr(&format!("built {SCHEMA_VERSION}")),
+        required.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    static BUILT: &str = r##"//
+// EVERYTHING BELOW THIS POINT WAS AUTO-GENERATED DURING COMPILATION. DO NOT MODIFY.
+//
+#[doc=r#"The "name" of the package."#]
+#[allow(dead_code)]
+pub static PKG_NAME: &str = "foo\"bar\u{e9}\\";
+#[doc=r#"The parallelism."#]
+#[allow(dead_code)]
+pub static NUM_JOBS: u32 = 8;
+#[allow(dead_code)]
+pub static GIT_DIRTY: Option<bool> = Some(true);
+#[allow(dead_code)]
+pub static GIT_VERSION: Option<&str> = None;
+#[allow(dead_code)]
+pub static PKG_SEMVER: (u64, u64, u64, &str, &str) = (1, 2, 3, "rc1", "");
+#[allow(dead_code)]
+pub static DEPENDENCIES: [(&str, &str); 2] = [("a", "0.1.0"), ("b", "0.2.0")];
+#[allow(dead_code)]
+pub static FEATURES: [&str; 0] = [];
+#[allow(dead_code)]
+pub static DUPLICATE_DEPENDENCIES: [(&str, &[&str]); 1] = [("a", &["0.1.0", "0.2.0"])];
+#[allow(dead_code)]
+pub static BUILT_SCHEMA_VERSION: u32 = 1;
+//
+// EVERYTHING ABOVE THIS POINT WAS AUTO-GENERATED DURING COMPILATION. DO NOT MODIFY.
+//
+"##;
+
+    #[test]
+    fn parse_type() {
+        use super::Type;
+
+        assert_eq!(
+            Type::parse("[(&str, &str, &str); 3]").unwrap(),
+            Type::Array(Box::new(Type::Tuple(vec![Type::Str, Type::Str, Type::Str])))
+        );
+        assert_eq!(
+            Type::parse("[(&str, &[&str]); 1]").unwrap(),
+            Type::Array(Box::new(Type::Tuple(vec![
+                Type::Str,
+                Type::Array(Box::new(Type::Str))
+            ])))
+        );
+        assert_eq!(
+            Type::parse("Option<u32>").unwrap(),
+            Type::Option(Box::new(Type::Integer))
+        );
+        assert!(Type::parse("f64").is_err());
+    }
+
+    #[test]
+    fn parse_value() {
+        use super::Value;
+
+        assert_eq!(
+            Value::parse("FOO", r#"[("a", &["0.1.0"]), ("b", &[],)]"#).unwrap(),
+            Value::List(vec![
+                Value::List(vec![
+                    Value::Str("a".to_owned()),
+                    Value::List(vec![Value::Str("0.1.0".to_owned())])
+                ]),
+                Value::List(vec![Value::Str("b".to_owned()), Value::List(vec![])]),
+            ])
+        );
+        assert_eq!(
+            Value::parse("FOO", "Some(true)").unwrap(),
+            Value::Bool(true)
+        );
+        assert_eq!(Value::parse("FOO", "None").unwrap(), Value::Null);
+        assert_eq!(
+            Value::parse("FOO", r#"(1, 2, "é\u{e9}")"#)
+                .unwrap()
+                .to_string(),
+            r#"[1, 2, "éé"]"#
+        );
+        assert!(Value::parse("FOO", "Foo").is_err());
+        assert!(Value::parse("FOO", "1 2").is_err());
+    }
+
+    #[test]
+    fn hostile_docs() -> std::io::Result<()> {
+        use crate::util::DocStr;
+
+        assert_eq!(DocStr("foo").to_string(), r##"r#"foo"#"##);
+        assert_eq!(
+            DocStr(r###"a "#b"## "c"###).to_string(),
+            r####"r###"a "#b"## "c"###"####
+        );
+        assert_eq!(DocStr("a\n\"#b").to_string(), r##""a\n\"#b""##);
+
+        let docs = [
+            "plain",
+            r#"ends with "#,
+            r###"contains "# and "##"###,
+            "spans\nmultiple \"#lines\"#\r\n",
+            "tab\tand \u{1b}escape",
+        ];
+        let mut built = Vec::new();
+        for (idx, doc) in docs.iter().enumerate() {
+            crate::write_variable!(built, format_args!("FOO{idx}"), "u32", idx, doc);
+        }
+        let built = String::from_utf8(built).unwrap();
+        let parsed = super::items(&built)
+            .map(|item| item.doc.unwrap().into_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(parsed, docs);
+        Ok(())
+    }
+
+    #[test]
+    fn items() {
+        let built = "#[allow(dead_code)]\npub static OPT_LEVEL: &str = \"0\";\n\
+            #[allow(dead_code)]\npub static OPT_LEVEL_KIND: OptLevel = OptLevel::O0;\n";
+        assert_eq!(
+            super::items(built)
+                .map(|item| item.name)
+                .collect::<Vec<_>>(),
+            ["OPT_LEVEL"]
+        );
+    }
+
+    #[test]
+    fn unquote() {
+        assert_eq!(super::unquote(r#""foo\"bar""#).as_deref(), Some("foo\"bar"));
+        assert_eq!(super::unquote(r#""foo" "bar""#), None);
+        assert_eq!(super::unquote("Some(\"foo\")"), None);
+    }
+
+    #[test]
+    fn schema() {
+        let schema = super::schema().unwrap();
+        assert!(schema.contains(
+            r#"    "PKG_NAME": {"type": "string", "description": "The name of the package."},"#
+        ));
+        assert!(schema.contains(
+            r#"    "GIT_DIRTY": {"anyOf": [{"type": "boolean"}, {"type": "null"}], "description": "#
+        ));
+        assert!(schema.contains(
+            r#"    "DEPENDENCIES": {"type": "array", "items": {"type": "array", "prefixItems": [{"type": "string"}, {"type": "string"}], "items": false}, "description": "#
+        ));
+        assert!(schema.contains(r#"    "BUILT_SCHEMA_VERSION": {"const": 1, "description": "#));
+        let required = schema
+            .lines()
+            .find(|line| line.contains(r#""required""#))
+            .unwrap();
+        assert!(required.contains(r#""PKG_NAME""#));
+        assert!(!required.contains(r#""GIT_DIRTY""#));
+    }
+
+    #[test]
+    fn constants() {
+        use super::Type;
+
+        let mut types = std::collections::HashMap::new();
+        for (name, datatype, doc, _) in &super::CONSTANTS {
+            let datatype = Type::parse(datatype).unwrap();
+            assert!(
+                types.insert(*name, datatype).is_none(),
+                "`{name}` is listed twice"
+            );
+            assert!(!doc.is_empty() && !doc.contains('\n'));
+            assert!(!super::COMPILE_TIME_CONSTANTS.contains(name));
+        }
+        // Every constant in a `built.rs` is described by the schema
+        for item in super::items(BUILT) {
+            assert_eq!(
+                types.get(item.name),
+                Some(&Type::parse(item.datatype).unwrap()),
+                "{}",
+                item.name
+            );
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{write_str_variable, write_variable};
-use std::{fs, io};
+use std::io;
 
 /// Parse a time-string as formatted by `built`.
 ///
@@ -23,37 +23,30 @@ pub fn strptime(s: &str) -> chrono::DateTime<chrono::offset::Utc> {
         .with_timezone(&chrono::offset::Utc)
 }
 
-fn get_source_date_epoch_from_env() -> Option<chrono::DateTime<chrono::offset::Utc>> {
-    match std::env::var("SOURCE_DATE_EPOCH") {
-        Ok(val) => {
-            let ts = match val.parse::<i64>() {
-                Ok(ts) => ts,
-                Err(_) => {
-                    eprintln!("SOURCE_DATE_EPOCH defined, but not a i64");
-                    return None;
-                }
-            };
-            match chrono::DateTime::from_timestamp(ts, 0) {
-                Some(now) => Some(now),
-                None => {
-                    eprintln!("SOURCE_DATE_EPOCH can't be represented as a UTC-time");
-                    None
-                }
-            }
-        }
-        Err(_) => None,
-    }
-}
-
-pub fn write_time(mut w: &fs::File) -> io::Result<()> {
-    use io::Write;
-
-    let now = get_source_date_epoch_from_env().unwrap_or_else(chrono::offset::Utc::now);
+pub fn write_time(
+    policy: crate::SourceDateEpochPolicy,
+    mut w: impl crate::report::Backend,
+) -> io::Result<()> {
+    let now = crate::source_date_epoch::get(policy, |ts| chrono::DateTime::from_timestamp(ts, 0))?
+        .unwrap_or_else(chrono::offset::Utc::now);
     write_str_variable!(
         w,
         "BUILT_TIME_UTC",
         now.to_rfc2822(),
         "The build time in RFC2822, UTC."
     );
+    write_str_variable!(
+        w,
+        "BUILT_TIME_RFC3339",
+        now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        "The build time in RFC3339, UTC."
+    );
+    write_variable!(
+        w,
+        "BUILT_TIME_UNIX",
+        "u64",
+        u64::try_from(now.timestamp()).map_err(io::Error::other)?,
+        "The build time as seconds since the Unix epoch."
+    );
     Ok(())
 }
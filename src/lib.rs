@@ -117,6 +117,80 @@
 //! }
 //! ```
 //!
+//! The generated files can be signed after they have been written, see
+//! `Options::set_signer()`. The path to the signature of `built.rs` is recorded in the
+//! file itself:
+//!
+//! ```
+//! /// The path to the signature of this file, if it was signed during compilation.
+//! pub static BUILT_SIGNATURE: Option<&str> = None;
+//! ```
+//!
+//! The same information can be written as `const`-items by means of
+//! `Options::set_build_info_output()`, e.g. for consumption by other build-scripts,
+//! or as a JSON-document by means of `Options::set_json_output()`. A JSON-schema
+//! describing the document is available through `Options::set_json_schema_output()`:
+//!
+//! ```
+//! /// The version of the JSON-schema describing the information in this file.
+//! pub static BUILT_SCHEMA_VERSION: u32 = 1;
+//! ```
+//!
+//! `Options::add_output()` writes the information in other formats, e.g. as
+//! TOML, YAML or shell-assignments for consumption by deployment-tooling. Constants
+//! which are only evaluated by the compiler, e.g. `BIN_NAME` or
+//! `PKG_VERSION_MAJOR_NUMERIC`, are not included.
+//!
+//! `Options::set_overview_output()` writes an overview of the constants as Markdown,
+//! which can serve as the documentation of the module `built.rs` is included in.
+//!
+//! Most of the information can also be written as a C-header, see
+//! `Options::set_c_header_output()`:
+//!
+//! ```c
+//! /* The full version. */
+//! #define BUILT_PKG_VERSION "0.1.0"
+//! ```
+//!
+//! A `BuildInfo`-struct holding all of the information as a single value can be
+//! generated as well, see `Options::set_build_info_struct()`.
+//!
+//! `Options::set_accessors()` adds a `const fn` for every string-constant, which
+//! returns `None` instead of an empty string:
+//!
+//! ```
+//! /// `RUSTDOC_VERSION`, or `None` if it is empty.
+//! pub const fn rustdoc_version() -> Option<&'static str> {
+//!     Some("rustdoc 1.43.1 (8d69840ab 2020-05-04)")
+//! }
+//! ```
+//!
+//! `Options::set_emit_all()` adds the names and values of all constants as a
+//! single array:
+//!
+//! ```
+//! /// The names and values of all of the above constants. String-values are recorded as they are, all other values as Rust-expressions.
+//! pub static ALL: [(&str, &str); 3] = [("PKG_NAME", "example_project"), ("NUM_JOBS", "8"), ("GIT_DIRTY", "Some(false)")];
+//! ```
+//!
+//! The value of every constant can be overridden by setting the environment-variable
+//! `BUILT_OVERRIDE_{package name}_{constant}` during compilation, e.g.
+//! `BUILT_OVERRIDE_my_crate_GIT_VERSION='Some("v1.0.0")'`; dashes in the package's name
+//! are replaced by underscores. String-values are given as they are, all other values as
+//! Rust-expressions; arrays may differ in length from the original value. If any override
+//! is set or `built` emits any other `cargo:rerun-if-*`, e.g. due to
+//! `Options::set_git_rerun_if_changed()`, changing any of the variables re-runs the
+//! build-script. The assignments which reproduce the generated information can be
+//! written by means of `Options::set_override_replay_output()`; which overrides were
+//! applied and which were not can be reported by means of
+//! `Options::set_override_report_output()`.
+//!
+//! The values can be checked against an expected baseline, e.g. to catch a release
+//! which was built on a developer's machine instead of on CI; see `Options::add_baseline()`.
+//!
+//! A build-script can use `self_check()` to inspect the values and warnings `built`
+//! would produce, without writing any files.
+//!
 //! ---
 //!
 //! ## Feature flags
@@ -129,6 +203,19 @@
 //! ```
 //! /// The Continuous Integration platform detected during compilation.
 //! pub static CI_PLATFORM: Option<&str> = None;
+//! /// A Continuous Integration platform `built` can detect.
+//! #[non_exhaustive]
+//! pub enum CiPlatform { Travis, Circle, GitLab, GitHubActions, Generic }
+//! /// The Continuous Integration platform detected during compilation, as a `CiPlatform`.
+//! pub static CI_PLATFORM_KIND: Option<CiPlatform> = None;
+//! /// The number of the CI-build, as given by the CI-platform; `None` if not known.
+//! pub static CI_BUILD_NUMBER: Option<&str> = None;
+//! /// The URL of the CI-build or -job; `None` if not known.
+//! pub static CI_BUILD_URL: Option<&str> = None;
+//! /// The number of the pull- or merge-request the CI-build was triggered by; `None` if not known or not triggered by one.
+//! pub static CI_PULL_REQUEST: Option<&str> = None;
+//! /// The id of the CI-pipeline or -workflow; `None` if not known.
+//! pub static CI_PIPELINE_ID: Option<&str> = None;
 //!
 //! /// The full version.
 //! pub static PKG_VERSION: &str = "0.1.0";
@@ -140,9 +227,40 @@
 //! pub static PKG_VERSION_PATCH: &str = "0";
 //! /// "The pre-release version.
 //! pub static PKG_VERSION_PRE: &str = "";
+//! /// Parses the decimal number `s` at compile time, e.g. to derive numeric constants from string-constants; `None` if `s` is not a number which fits into a `u32`.
+//! pub const fn parse_u32(s: &str) -> Option<u32> {
+//!     // ...
+//! #   None
+//! }
+//! /// `PKG_VERSION_MAJOR` as a number, derived at compile time; `None` if it is not a number which fits into a `u32`.
+//! pub static PKG_VERSION_MAJOR_NUMERIC: Option<u32> = parse_u32(PKG_VERSION_MAJOR);
+//! /// `PKG_VERSION_MINOR` as a number, derived at compile time; `None` if it is not a number which fits into a `u32`.
+//! pub static PKG_VERSION_MINOR_NUMERIC: Option<u32> = parse_u32(PKG_VERSION_MINOR);
+//! /// `PKG_VERSION_PATCH` as a number, derived at compile time; `None` if it is not a number which fits into a `u32`.
+//! pub static PKG_VERSION_PATCH_NUMERIC: Option<u32> = parse_u32(PKG_VERSION_PATCH);
+//! /// The version as a tuple of major, minor, patch, pre-release and build-metadata.
+//! pub static PKG_SEMVER: (u64, u64, u64, &str, &str) = (0, 1, 0, "", "");
+//! /// The minimum supported Rust version, as declared in Cargo.toml; empty if not declared.
+//! pub static PKG_RUST_VERSION: &str = "1.70";
+//! /// The path to the README-file, as declared in Cargo.toml; empty if not declared.
+//! pub static PKG_README: &str = "README.md";
+//! /// The path to the license-file, as declared in Cargo.toml; empty if not declared.
+//! pub static PKG_LICENSE_FILE: &str = "";
+//! /// The URL of the documentation, as declared in Cargo.toml; empty if not declared.
+//! pub static PKG_DOCUMENTATION: &str = "https://docs.rs/example_project";
+//! /// The keywords, as declared in Cargo.toml.
+//! pub static PKG_KEYWORDS: [&str; 2] = ["example", "project"];
+//! /// The keywords as a comma-separated string.
+//! pub static PKG_KEYWORDS_STR: &str = "example, project";
+//! /// The categories, as declared in Cargo.toml.
+//! pub static PKG_CATEGORIES: [&str; 1] = ["development-tools"];
+//! /// The categories as a comma-separated string.
+//! pub static PKG_CATEGORIES_STR: &str = "development-tools";
 //!
 //! /// "A colon-separated list of authors.
 //! pub static PKG_AUTHORS: &str = "Lukas Lueg <lukas.lueg@gmail.com>";
+//! /// The authors, as declared in Cargo.toml.
+//! pub static PKG_AUTHORS_LIST: [&str; 1] = ["Lukas Lueg <lukas.lueg@gmail.com>"];
 //!
 //! /// The name of the package.
 //! pub static PKG_NAME: &str = "example_project";
@@ -166,17 +284,45 @@
 //! pub static RUSTC: &str = "rustc";
 //! /// The documentation-generator that cargo resolved to use.
 //! pub static RUSTDOC: &str = "rustdoc";
+//! /// The linker cargo resolved to use, if one was configured.
+//! pub static LINKER: Option<&str> = None;
+//! /// The name of the profile used during compilation, e.g. `dev`, `release` or a custom profile; unlike `PROFILE`, which only distinguishes `release` and `debug`.
+//! pub static PROFILE_NAME: &str = "dev";
+//! /// The name of the binary-target being compiled, given by `CARGO_BIN_NAME`; `None` if the target is not a binary.
+//! pub static BIN_NAME: Option<&str> = Some("example_project");
+//! /// The name of the crate being compiled, given by `CARGO_CRATE_NAME`.
+//! pub static CRATE_NAME: &str = "example_project";
 //! /// The output of `rustc -V`
 //! pub static RUSTC_VERSION: &str = "rustc 1.43.1 (8d69840ab 2020-05-04)";
 //! /// The output of `rustdoc -V`
 //! pub static RUSTDOC_VERSION: &str = "rustdoc 1.43.1 (8d69840ab 2020-05-04)";
+//! /// The output of `cargo -V`
+//! pub static CARGO_VERSION: &str = "cargo 1.43.0 (2cbe9048e 2020-05-03)";
 //!
 //! /// Value of OPT_LEVEL for the profile used during compilation.
 //! pub static OPT_LEVEL: &str = "0";
+//! /// The numeric value of OPT_LEVEL; `None` if optimizing for size (`s` or `z`).
+//! pub static OPT_LEVEL_NUMERIC: Option<u8> = Some(0);
+//! /// The optimization-level of the profile used during compilation.
+//! pub enum OptLevel { O0, O1, O2, O3, Size, MinSize, Other }
+//! /// Value of OPT_LEVEL for the profile used during compilation, as an `OptLevel`.
+//! pub static OPT_LEVEL_KIND: OptLevel = OptLevel::O0;
 //! /// The parallelism that was specified during compilation.
 //! pub static NUM_JOBS: u32 = 8;
 //! /// "Value of DEBUG for the profile used during compilation.
 //! pub static DEBUG: bool = true;
+//! /// The debuginfo-level of the profile used during compilation as it was configured, e.g. `0`, `line-tables-only` or `true`.
+//! pub static DEBUGINFO: &str = "2";
+//! /// The extra flags passed to the compiler, e.g. by means of `RUSTFLAGS`, separated by spaces.
+//! pub static RUSTFLAGS: &str = "-C target-cpu=native";
+//! /// The `lto`-setting of the profile used during compilation; `None` if not configured.
+//! pub static LTO: Option<&str> = Some("thin");
+//! /// The `codegen-units`-setting of the profile used during compilation; `None` if not configured.
+//! pub static CODEGEN_UNITS: Option<u32> = Some(1);
+//! /// The panic-strategy, given by `CARGO_CFG_PANIC` or the profile used during compilation; `None` if not configured.
+//! pub static PANIC_STRATEGY: Option<&str> = Some("unwind");
+//! /// The `strip`-setting of the profile used during compilation; `None` if not configured.
+//! pub static STRIP: Option<&str> = None;
 //!
 //! /// The features that were enabled during compilation.
 //! pub static FEATURES: [&str; 0] = [];
@@ -187,6 +333,10 @@
 //! /// The feature-string as above, from lowercase strings.
 //! pub static FEATURES_LOWERCASE_STR: &str = "";
 //!
+//! // Only if `Options::set_validate_features()` is enabled
+//! /// `CARGO_FEATURE_*`-variables set during compilation which do not refer to a feature declared by the manifest.
+//! pub static FEATURES_FOREIGN: [&str; 0] = [];
+//!
 //! /// The target architecture, given by `CARGO_CFG_TARGET_ARCH`.
 //! pub static CFG_TARGET_ARCH: &str = "x86_64";
 //! /// The endianness, given by `CARGO_CFG_TARGET_ENDIAN`.
@@ -199,7 +349,81 @@
 //! pub static CFG_OS: &str = "linux";
 //! /// The pointer width, given by `CARGO_CFG_TARGET_POINTER_WIDTH`.
 //! pub static CFG_POINTER_WIDTH: &str = "64";
+//! /// The microarchitecture level (e.g. `x86-64-v3`) implied by the target features
+//! /// enabled via `-C target-cpu` or `-C target-feature`; `None` if the target is not `x86_64`.
+//! pub static CPU_DISPATCH_LEVEL: Option<&str> = Some("x86-64");
+//! /// The target features, given by `CARGO_CFG_TARGET_FEATURE`.
+//! pub static CFG_TARGET_FEATURES: [&str; 3] = ["fxsr", "sse", "sse2"];
+//! /// The target features as a comma-separated string.
+//! pub static CFG_TARGET_FEATURES_STR: &str = "fxsr, sse, sse2";
+//! /// All `cfg`-options given by `CARGO_CFG_*` and their values; multiple values of the same option are separated by commas.
+//! pub static CFG_ALL: [(&str, &str); 3] = [("target_arch", "x86_64"), ("target_feature", "fxsr,sse,sse2"), ("unix", "")];
+//!
+//! /// The environment-variables selected by `Options::set_captured_env_vars()` and their values during compilation; `None` if a variable was not set.
+//! pub static CAPTURED_ENV: [(&str, Option<&str>); 2] = [("DEPLOY_ENV", Some("staging")), ("BUILD_URL", None)];
+//!
+//! // Only if `Options::set_toolchain_file()` is enabled, which requires the `manifest`-feature
+//! /// The channel pinned by `rust-toolchain.toml` or `rust-toolchain`, if any.
+//! pub static TOOLCHAIN_CHANNEL: Option<&str> = Some("1.85.0");
+//! /// The components required by `rust-toolchain.toml`.
+//! pub static TOOLCHAIN_COMPONENTS: [&str; 2] = ["rustfmt", "clippy"];
+//! /// The targets required by `rust-toolchain.toml`.
+//! pub static TOOLCHAIN_TARGETS: [&str; 0] = [];
+//!
+//! /// The hostname of the machine which compiled the crate, if known.
+//! pub static BUILD_HOST: Option<&str> = Some("buildbox-7");
+//! /// The name of the user who compiled the crate, given by `USER`, `LOGNAME` or `USERNAME`.
+//! pub static BUILD_USER: Option<&str> = Some("ci");
+//! /// The operating system of the machine which compiled the crate, as reported by `uname -srm` or `ver`.
+//! pub static BUILD_HOST_OS: Option<&str> = Some("Linux 6.8.0-45-generic x86_64");
+//!
+//! #[used]
+//! /// The package's name and version, HEAD's short commit hash and whether the repository was dirty, as a NUL-terminated string. It is kept by the compiler, but may still be removed from the binary by the linker or by stripping.
+//! pub static BUILT_BANNER: [u8; 22] = [101, 120, 97, 109, 112, 108, 101, 95, 112, 114, 111, 106, 101, 99, 116, 32, 48, 46, 49, 46, 48, 0];
+//!
+//! /// The version of `built` which produced this file.
+//! pub static BUILT_VERSION: &str = "0.7.5";
+//! /// The features of `built` which were enabled when this file was produced.
+//! pub static BUILT_FEATURES: [&str; 1] = ["git2"];
+//! /// The collectors which contributed to this file as `name=enabled`, and those which did not, due to features or options, as `name=disabled`.
+//! pub static BUILT_COLLECTORS: [&str; 13] = ["environment=enabled", "toolchain=disabled", "build-host=disabled", "git=enabled", "git-checkout=disabled", "dependencies=disabled", "sbom=disabled", "audit=disabled", "manifest=disabled", "workspace=disabled", "licenses=disabled", "time=disabled", "signature=disabled"];
+//!
+//! // Only if `Options::set_collection_stats()` is enabled
+//! /// The status of each collector while producing this file (`ok`, `failed`, `skipped` or `overridden`) and the time it took, e.g. `ok 1.2ms`.
+//! pub static BUILT_COLLECTION_STATS: [(&str, &str); 12] = [("environment", "ok 1.3ms"), ("toolchain", "skipped"), ("build-host", "skipped"), ("git", "failed 2.4ms"), ("git-checkout", "skipped"), ("dependencies", "skipped"), ("sbom", "skipped"), ("audit", "skipped"), ("manifest", "skipped"), ("workspace", "skipped"), ("licenses", "skipped"), ("time", "skipped")];
+//! ```
+//!
+//! `BUILD_TAGS` holds the most relevant of the above as key/value-pairs with stable,
+//! lowercase and dot-separated keys, e.g. for attaching them to telemetry:
+//!
 //! ```
+//! /// Key/value-pairs for attaching to telemetry, e.g. as OpenTelemetry resource-attributes. Keys are lowercase and dot-separated, like `pkg.version`, `git.commit` or `rustc.version`, and are stable across versions of `built`. Values which are unknown or empty are omitted.
+//! pub static BUILD_TAGS: [(&str, &str); 3] = [("pkg.name", "example_project"), ("pkg.version", "0.1.0"), ("git.commit", "ca2af4f11bb8f4f6421c4cccf428bf4862573daf")];
+//! ```
+//!
+//! The keys are `pkg.name`, `pkg.version`, `pkg.repository`, `git.version`,
+//! `git.commit`, `git.ref`, `git.dirty`, `rustc.version`, `cargo.version`,
+//! `build.target`, `build.host`, `build.profile`, `build.opt_level`, `build.panic`,
+//! `build.features`, `build.time`, `ci.platform` and `built.version`. Keys are
+//! never renamed or repurposed; new keys may be added. If `built` is included as a
+//! runtime-dependency, `built::Banner` renders them as a startup-banner,
+//! `built::cache_key()` derives a stable key for caching artifacts from them and
+//! `built::check_compatibility()` compares them with those of a plugin.
+//!
+//! `FULL_VERSION` combines some of the above into a single string; the parts can be
+//! selected by means of `Options::set_full_version_parts()`. The same string is
+//! available as a string-literal from the `full_version!()`-macro, e.g. for use in
+//! `#[doc]`-attributes, if the module `built.rs` is included into is `#[macro_use]`:
+//!
+//! ```
+//! /// The values of `PKG_VERSION`, `GIT_COMMIT_HASH_SHORT`, `PROFILE`, separated by spaces.
+//! pub static FULL_VERSION: &str = "0.1.0 ca2af4f debug";
+//! ```
+//!
+//! `BUILT_BANNER` can be found in the compiled binary, e.g. by means of
+//! `strings binary | grep example_project`. While `#[used]` keeps it from being
+//! optimized away by the compiler, the linker may still discard it, e.g. due to
+//! `--gc-sections` if nothing refers to it, as does stripping the binary.
 //!
 //! ### `cargo-lock`
 //! Parses `Cargo.lock`and generates representations of  dependencies and their versions.
@@ -218,14 +442,41 @@
 //! actually use them (e.g. `dev-dependencies` with `cargo test` never
 //! having been executed).
 //!
-//! Note that if the `dependency-tree`-feature is not active, the list of dependencies
-//! contains the root-package(s) as well.
+//! Note that by default, the list of dependencies does not contain the root-package(s),
+//! regardless of the `dependency-tree`-feature; use
+//! `Options::set_include_root_packages()` to include them. Use
+//! `Options::set_dependency_include()` and `Options::set_dependency_exclude()` to
+//! trim which dependencies get serialized.
+//!
+//! For large dependency-graphs, `Options::set_packed_dependencies()` serializes the
+//! lists of dependencies as a single string and a table of offsets, accessed by means
+//! of functions like `dependencies()`.
+//!
+//! ```
+//! /// The names and versions of the effective dependencies, back to back. Use `dependencies()` to iterate over them.
+//! pub static DEPENDENCIES_PACKED: &str = "autocfg1.0.0bitflags1.2.1";
+//! /// The byte-offsets of the names and versions in `DEPENDENCIES_PACKED`.
+//! pub static DEPENDENCIES_OFFSETS: [u32; 5] = [0, 7, 12, 20, 25];
+//! ```
+//!
+//! A software bill of materials can be derived from `Cargo.lock` as well, see
+//! `Options::set_sbom_output()` and `Options::set_embed_sbom()`.
 //!
 //! ```
 //! /// An array of effective dependencies as documented by `Cargo.lock`.
-//! pub static DEPENDENCIES: [(&str, &str); 37] = [("autocfg", "1.0.0"), ("bitflags", "1.2.1"), ("built", "0.4.1"), ("cargo-lock", "4.0.1"), ("cc", "1.0.54"), ("cfg-if", "0.1.10"), ("chrono", "0.4.11"), ("example_project", "0.1.0"), ("git2", "0.13.6"), ("idna", "0.2.0"), ("jobserver", "0.1.21"), ("libc", "0.2.71"), ("libgit2-sys", "0.12.6+1.0.0"), ("libz-sys", "1.0.25"), ("log", "0.4.8"), ("matches", "0.1.8"), ("num-integer", "0.1.42"), ("num-traits", "0.2.11"), ("percent-encoding", "2.1.0"), ("pkg-config", "0.3.17"), ("proc-macro2", "1.0.17"), ("quote", "1.0.6"), ("semver", "1.0.0"), ("serde", "1.0.110"), ("serde_derive", "1.0.110"), ("smallvec", "1.4.0"), ("syn", "1.0.25"), ("time", "0.1.43"), ("toml", "0.5.6"), ("unicode-bidi", "0.3.4"), ("unicode-normalization", "0.1.12"), ("unicode-xid", "0.2.0"), ("url", "2.1.1"), ("vcpkg", "0.2.8"), ("winapi", "0.3.8"), ("winapi-i686-pc-windows-gnu", "0.4.0"), ("winapi-x86_64-pc-windows-gnu", "0.4.0")];
+//! pub static DEPENDENCIES: [(&str, &str); 36] = [("autocfg", "1.0.0"), ("bitflags", "1.2.1"), ("built", "0.4.1"), ("cargo-lock", "4.0.1"), ("cc", "1.0.54"), ("cfg-if", "0.1.10"), ("chrono", "0.4.11"), ("git2", "0.13.6"), ("idna", "0.2.0"), ("jobserver", "0.1.21"), ("libc", "0.2.71"), ("libgit2-sys", "0.12.6+1.0.0"), ("libz-sys", "1.0.25"), ("log", "0.4.8"), ("matches", "0.1.8"), ("num-integer", "0.1.42"), ("num-traits", "0.2.11"), ("percent-encoding", "2.1.0"), ("pkg-config", "0.3.17"), ("proc-macro2", "1.0.17"), ("quote", "1.0.6"), ("semver", "1.0.0"), ("serde", "1.0.110"), ("serde_derive", "1.0.110"), ("smallvec", "1.4.0"), ("syn", "1.0.25"), ("time", "0.1.43"), ("toml", "0.5.6"), ("unicode-bidi", "0.3.4"), ("unicode-normalization", "0.1.12"), ("unicode-xid", "0.2.0"), ("url", "2.1.1"), ("vcpkg", "0.2.8"), ("winapi", "0.3.8"), ("winapi-i686-pc-windows-gnu", "0.4.0"), ("winapi-x86_64-pc-windows-gnu", "0.4.0")];
 //! /// The effective dependencies as a comma-separated string.
-//! pub static DEPENDENCIES_STR: &str = "autocfg 1.0.0, bitflags 1.2.1, built 0.4.1, cargo-lock 4.0.1, cc 1.0.54, cfg-if 0.1.10, chrono 0.4.11, example_project 0.1.0, git2 0.13.6, idna 0.2.0, jobserver 0.1.21, libc 0.2.71, libgit2-sys 0.12.6+1.0.0, libz-sys 1.0.25, log 0.4.8, matches 0.1.8, num-integer 0.1.42, num-traits 0.2.11, percent-encoding 2.1.0, pkg-config 0.3.17, proc-macro2 1.0.17, quote 1.0.6, semver 1.0.0, serde 1.0.110, serde_derive 1.0.110, smallvec 1.4.0, syn 1.0.25, time 0.1.43, toml 0.5.6, unicode-bidi 0.3.4, unicode-normalization 0.1.12, unicode-xid 0.2.0, url 2.1.1, vcpkg 0.2.8, winapi 0.3.8, winapi-i686-pc-windows-gnu 0.4.0, winapi-x86_64-pc-windows-gnu 0.4.0";
+//! pub static DEPENDENCIES_STR: &str = "autocfg 1.0.0, bitflags 1.2.1, built 0.4.1, cargo-lock 4.0.1, cc 1.0.54, cfg-if 0.1.10, chrono 0.4.11, git2 0.13.6, idna 0.2.0, jobserver 0.1.21, libc 0.2.71, libgit2-sys 0.12.6+1.0.0, libz-sys 1.0.25, log 0.4.8, matches 0.1.8, num-integer 0.1.42, num-traits 0.2.11, percent-encoding 2.1.0, pkg-config 0.3.17, proc-macro2 1.0.17, quote 1.0.6, semver 1.0.0, serde 1.0.110, serde_derive 1.0.110, smallvec 1.4.0, syn 1.0.25, time 0.1.43, toml 0.5.6, unicode-bidi 0.3.4, unicode-normalization 0.1.12, unicode-xid 0.2.0, url 2.1.1, vcpkg 0.2.8, winapi 0.3.8, winapi-i686-pc-windows-gnu 0.4.0, winapi-x86_64-pc-windows-gnu 0.4.0";
+//! /// The number of effective dependencies.
+//! pub static DEPENDENCIES_COUNT: usize = 36;
+//! /// The names, versions and SHA-256 checksums of all packages in `Cargo.lock` which carry a checksum, i.e. those from a registry.
+//! pub static DEPENDENCIES_CHECKSUMS: [(&str, &str, &str); 2] = [("autocfg", "1.0.0", "f8aac770f1885fd7e387acedd76065302551364496e46b3dd00860b2f8359b9d"), ("bitflags", "1.2.1", "cf1de2fe8c75bc145a2f577add951f8134889b4795d47466a54a5c846d691693")];
+//! /// The names, versions and sources of all packages in `Cargo.lock`. The source is `crates.io`, `path` for local packages, or the URL of the registry or git-repository, the latter including the revision.
+//! pub static DEPENDENCY_SOURCES: [(&str, &str, &str); 2] = [("autocfg", "1.0.0", "crates.io"), ("example_project", "0.1.0", "path")];
+//! /// The registries from which packages in `Cargo.lock` originate; `crates.io` or the URL of an alternative registry. See `DEPENDENCY_SOURCES` for the registry of each package.
+//! pub static REGISTRIES_USED: [&str; 1] = ["crates.io"];
+//! /// The names of packages which appear with more than one version in `Cargo.lock`, and their versions.
+//! pub static DUPLICATE_DEPENDENCIES: [(&str, &[&str]); 1] = [("bitflags", &["1.2.1", "2.4.0"])];
 //! ```
 //!
 //! ### `dependency-tree` (implies `cargo-lock`)
@@ -234,17 +485,97 @@
 //!
 //! "Direct" dependencies are those which the root-package(s) depends on.
 //! "Indirect" dependencies are those which are not direct dependencies.
+//! If a workspace has multiple root-packages, use `Options::set_dependency_roots()`
+//! to classify dependencies relative to the package being built. Use
+//! `Options::set_dependency_depth()` to only serialize dependencies up to a
+//! certain depth of the dependency-graph.
+//!
+//! Build-scripts can reuse the resolution by means of `built::Dependencies`.
 //!
 //! ```
+//! /// The number of dependencies omitted from `DEPENDENCIES` because they are deeper in the dependency-graph than selected by `Options::set_dependency_depth()`.
+//! pub static DEPENDENCIES_PRUNED_COUNT: usize = 0;
+//!
 //! /// An array of direct dependencies as documented by `Cargo.lock`.
 //! pub static DIRECT_DEPENDENCIES: [(&str, &str); 1] = [("built", "0.6.1")];
 //! /// The direct dependencies as a comma-separated string.
 //! pub static DIRECT_DEPENDENCIES_STR: &str = r"built 0.6.1";
+//! /// The number of direct dependencies.
+//! pub static DIRECT_DEPENDENCIES_COUNT: usize = 1;
 //!
 //! /// An array of indirect dependencies as documented by `Cargo.lock`.
 //! pub static INDIRECT_DEPENDENCIES: [(&str, &str); 64] = [("android-tzdata", "0.1.1"), ("android_system_properties", "0.1.5"), ("autocfg", "1.1.0"), ("bitflags", "2.4.0"), ("bumpalo", "3.13.0"), ("cargo-lock", "9.0.0"), ("cc", "1.0.83"), ("cfg-if", "1.0.0"), ("chrono", "0.4.29"), ("core-foundation-sys", "0.8.4"), ("equivalent", "1.0.1"), ("example_project", "0.1.0"), ("fixedbitset", "0.4.2"), ("form_urlencoded", "1.2.0"), ("git2", "0.18.0"), ("hashbrown", "0.14.0"), ("iana-time-zone", "0.1.57"), ("iana-time-zone-haiku", "0.1.2"), ("idna", "0.4.0"), ("indexmap", "2.0.0"), ("jobserver", "0.1.26"), ("js-sys", "0.3.64"), ("libc", "0.2.147"), ("libgit2-sys", "0.16.1+1.7.1"), ("libz-sys", "1.1.12"), ("log", "0.4.20"), ("memchr", "2.6.3"), ("num-traits", "0.2.16"), ("once_cell", "1.18.0"), ("percent-encoding", "2.3.0"), ("petgraph", "0.6.4"), ("pkg-config", "0.3.27"), ("proc-macro2", "1.0.66"), ("quote", "1.0.33"), ("semver", "1.0.18"), ("serde", "1.0.188"), ("serde_derive", "1.0.188"), ("serde_spanned", "0.6.3"), ("syn", "2.0.31"), ("tinyvec", "1.6.0"), ("tinyvec_macros", "0.1.1"), ("toml", "0.7.6"), ("toml_datetime", "0.6.3"), ("toml_edit", "0.19.14"), ("unicode-bidi", "0.3.13"), ("unicode-ident", "1.0.11"), ("unicode-normalization", "0.1.22"), ("url", "2.4.1"), ("vcpkg", "0.2.15"), ("wasm-bindgen", "0.2.87"), ("wasm-bindgen-backend", "0.2.87"), ("wasm-bindgen-macro", "0.2.87"), ("wasm-bindgen-macro-support", "0.2.87"), ("wasm-bindgen-shared", "0.2.87"), ("windows", "0.48.0"), ("windows-targets", "0.48.5"), ("windows_aarch64_gnullvm", "0.48.5"), ("windows_aarch64_msvc", "0.48.5"), ("windows_i686_gnu", "0.48.5"), ("windows_i686_msvc", "0.48.5"), ("windows_x86_64_gnu", "0.48.5"), ("windows_x86_64_gnullvm", "0.48.5"), ("windows_x86_64_msvc", "0.48.5"), ("winnow", "0.5.15")];
 //! /// The indirect dependencies as a comma-separated string.
 //! pub static INDIRECT_DEPENDENCIES_STR: &str = r"android-tzdata 0.1.1, android_system_properties 0.1.5, autocfg 1.1.0, bitflags 2.4.0, bumpalo 3.13.0, cargo-lock 9.0.0, cc 1.0.83, cfg-if 1.0.0, chrono 0.4.29, core-foundation-sys 0.8.4, equivalent 1.0.1, example_project 0.1.0, fixedbitset 0.4.2, form_urlencoded 1.2.0, git2 0.18.0, hashbrown 0.14.0, iana-time-zone 0.1.57, iana-time-zone-haiku 0.1.2, idna 0.4.0, indexmap 2.0.0, jobserver 0.1.26, js-sys 0.3.64, libc 0.2.147, libgit2-sys 0.16.1+1.7.1, libz-sys 1.1.12, log 0.4.20, memchr 2.6.3, num-traits 0.2.16, once_cell 1.18.0, percent-encoding 2.3.0, petgraph 0.6.4, pkg-config 0.3.27, proc-macro2 1.0.66, quote 1.0.33, semver 1.0.18, serde 1.0.188, serde_derive 1.0.188, serde_spanned 0.6.3, syn 2.0.31, tinyvec 1.6.0, tinyvec_macros 0.1.1, toml 0.7.6, toml_datetime 0.6.3, toml_edit 0.19.14, unicode-bidi 0.3.13, unicode-ident 1.0.11, unicode-normalization 0.1.22, url 2.4.1, vcpkg 0.2.15, wasm-bindgen 0.2.87, wasm-bindgen-backend 0.2.87, wasm-bindgen-macro 0.2.87, wasm-bindgen-macro-support 0.2.87, wasm-bindgen-shared 0.2.87, windows 0.48.0, windows-targets 0.48.5, windows_aarch64_gnullvm 0.48.5, windows_aarch64_msvc 0.48.5, windows_i686_gnu 0.48.5, windows_i686_msvc 0.48.5, windows_x86_64_gnu 0.48.5, windows_x86_64_gnullvm 0.48.5, windows_x86_64_msvc 0.48.5, winnow 0.5.15";
+//! /// The number of indirect dependencies.
+//! pub static INDIRECT_DEPENDENCIES_COUNT: usize = 64;
+//! /// The number of dependencies omitted from `INDIRECT_DEPENDENCIES` because they are deeper in the dependency-graph than selected by `Options::set_dependency_depth()`.
+//! pub static INDIRECT_DEPENDENCIES_PRUNED_COUNT: usize = 0;
+//! ```
+//!
+//! ### `audit` (implies `cargo-lock`)
+//! If a local checkout of the [RustSec advisory-db](https://github.com/rustsec/advisory-db)
+//! is provided by means of `Options::set_advisory_db()`, the dependencies in
+//! `Cargo.lock` are cross-referenced with its advisories. Informational and withdrawn
+//! advisories are ignored. No network-access is ever performed.
+//!
+//! ```
+//! /// The dependencies' names, versions and the ids of the RustSec-advisories affecting them, as found in the local advisory-db.
+//! pub static VULNERABLE_DEPENDENCIES: [(&str, &str, &str); 1] = [("time", "0.1.45", "RUSTSEC-2020-0071")];
+//! ```
+//!
+//! ### `cargo-metadata`
+//! Run `cargo metadata` to collect the license-expressions of all dependencies,
+//! e.g. to show third-party license information in an "About"-dialog. Unlike
+//! `Cargo.lock`, `cargo metadata` distinguishes normal, build- and dev-dependencies,
+//! resolved for the features enabled during compilation.
+//!
+//! ```
+//! /// The dependencies required at runtime, as resolved by `cargo metadata`.
+//! pub static RUNTIME_DEPENDENCIES: [(&str, &str); 2] = [("cfg-if", "1.0.0"), ("libc", "0.2.171")];
+//! /// The dependencies as above, as a comma-separated string.
+//! pub static RUNTIME_DEPENDENCIES_STR: &str = "cfg-if 1.0.0, libc 0.2.171";
+//! /// The dependencies only required to build the package, e.g. by build-scripts and procedural macros, as resolved by `cargo metadata`.
+//! pub static BUILD_DEPENDENCIES: [(&str, &str); 1] = [("built", "0.7.5")];
+//! /// The dependencies as above, as a comma-separated string.
+//! pub static BUILD_DEPENDENCIES_STR: &str = "built 0.7.5";
+//! /// The dependencies only required for tests, examples and benchmarks, as resolved by `cargo metadata`.
+//! pub static DEV_DEPENDENCIES: [(&str, &str); 1] = [("memchr", "2.7.4")];
+//! /// The dependencies as above, as a comma-separated string.
+//! pub static DEV_DEPENDENCIES_STR: &str = "memchr 2.7.4";
+//! /// The dependencies' names, versions and SPDX license-expressions, as reported by `cargo metadata`. Packages which do not declare a license-expression are reported as `NOASSERTION`.
+//! pub static DEPENDENCY_LICENSES: [(&str, &str, &str); 3] = [("cfg-if", "1.0.0", "MIT OR Apache-2.0"), ("libc", "0.2.171", "MIT OR Apache-2.0"), ("memchr", "2.7.4", "Unlicense OR MIT")];
+//! /// The distinct license-expressions of all dependencies and the number of dependencies using them, as a comma-separated string.
+//! pub static LICENSE_SUMMARY_STR: &str = "MIT OR Apache-2.0 (2), Unlicense OR MIT (1)";
+//! ```
+//!
+//! ### `manifest`
+//! Parse the package's `Cargo.toml` for information not available through
+//! the environment.
+//!
+//! The manifest is also used to complete the constants above: `PKG_DOCUMENTATION`,
+//! `PKG_KEYWORDS` and `PKG_CATEGORIES`, values inherited from the workspace, authors
+//! containing colons in `PKG_AUTHORS_LIST`, and `LTO`, `CODEGEN_UNITS`,
+//! `PANIC_STRATEGY` and `STRIP` as configured by the profile. Without this feature,
+//! these are only what Cargo provides through the environment, i.e. empty or `None`.
+//!
+//! ```
+//! /// The edition of the package, as declared in `Cargo.toml`.
+//! pub static PKG_EDITION: Option<&str> = Some("2021");
+//! /// The features declared in `Cargo.toml`, including those implied by optional dependencies.
+//! pub static PKG_DECLARED_FEATURES: [&str; 2] = ["default", "serde"];
+//! /// The `[package.metadata]`-table of `Cargo.toml`, as TOML.
+//! pub static PKG_METADATA: &str = "[docs.rs]\nall-features = true\n";
+//! /// The name of the package's library-target, if any.
+//! pub static LIB_TARGET: Option<&str> = Some("example_project");
+//! /// The names of the package's binary-targets.
+//! pub static BIN_TARGETS: [&str; 1] = ["example_project"];
+//! /// The directory of the workspace the package was built in, if any.
+//! pub static WORKSPACE_ROOT: Option<&str> = Some("/home/user/example_workspace");
+//! /// The names and versions of the members of the workspace the package was built in.
+//! pub static WORKSPACE_MEMBERS: [(&str, &str); 2] = [("example_lib", "0.3.0"), ("example_project", "0.1.0")];
+//! /// The settings of the `[profile.<name>.package.<spec>]`-tables in the workspace's `Cargo.toml`, as `(profile, package, setting, value)`, the value as TOML.
+//! pub static PROFILE_OVERRIDES: [(&str, &str, &str, &str); 1] = [("dev", "sha2", "opt-level", "3")];
 //! ```
 //!
 //! ### `git2`
@@ -253,16 +584,38 @@
 //!
 //! Notice that `GIT_HEAD_REF` is `None` if `HEAD` is detached or not valid UTF-8.
 //!
-//! Continuous Integration platforms like `Travis` and `AppVeyor` will
-//! do shallow clones, causing `libgit2` to be unable to get a meaningful
-//! result. `GIT_VERSION` and `GIT_DIRTY` will therefore always be `None` if
-//! a CI-platform is detected.
+//! Files outside of a sparse checkout and files handled by the Git LFS-filter
+//! whose content matches the LFS-pointer do not count towards `GIT_DIRTY`.
+//!
+//! Continuous Integration platforms like `Travis` and `AppVeyor` may
+//! do shallow clones, which is recorded as `GIT_SHALLOW`. The repository is
+//! described regardless; only if that actually fails, `GIT_VERSION` and `GIT_DIRTY`
+//! are `None`. Use `Options::set_git_on_ci()` to skip the repository altogether or
+//! to fail the build instead if a CI-platform is detected.
+//!
+//! Repositories with multiple tag-namespaces can record HEAD's description relative
+//! to each of them by means of `Options::set_git_describe()`. The revision of another
+//! checkout, e.g. of a vendored dependency, can be recorded by means of
+//! `Options::add_git_checkout()`.
 //! ```
 //! /// If the crate was compiled from within a git-repository,
 //! /// `GIT_VERSION` contains HEAD's tag. The short commit id is used
 //! /// if HEAD is not tagged.
 //! pub static GIT_VERSION: Option<&str> = Some("0.4.1-10-gca2af4f");
 //!
+//! /// The number of commits since the most recent tag in `version` as formatted by
+//! /// `git describe`, e.g. `v1.2.0-5-g0f3a1b2`, at compile time; `None` if `version`
+//! /// does not end in `-<count>-g<commit id>`.
+//! pub const fn describe_distance(version: &str) -> Option<u32> {
+//!     // ...
+//! #   Some(10)
+//! }
+//!
+//! /// The number of commits since the most recent tag, derived from `GIT_VERSION` at
+//! /// compile time; `None` if HEAD is tagged, no tag was found or if not compiled from
+//! /// within a git-repository.
+//! pub static GIT_COMMITS_SINCE_TAG: Option<u32> = match GIT_VERSION { Some(version) => describe_distance(version), None => None };
+//!
 //! /// If the repository had dirty/staged files.
 //! pub static GIT_DIRTY: Option<bool> = Some(true);
 //!
@@ -279,33 +632,184 @@
 //! /// If the crate was compiled from within a git-repository,
 //! /// `GIT_COMMIT_HASH_SHORT` contains HEAD's short commit SHA-1 hash.
 //! pub static GIT_COMMIT_HASH_SHORT: Option<&str> = Some("ca2af4f");
+//!
+//! /// If the crate was compiled from within a git-repository while a `merge`, `rebase`, `am`, `cherry-pick`, `revert` or `bisect` was in progress, `GIT_OPERATION_IN_PROGRESS` contains the name of the operation.
+//! pub static GIT_OPERATION_IN_PROGRESS: Option<&str> = None;
+//!
+//! /// If the crate was compiled from within a git-repository, `GIT_SHALLOW` indicates whether the repository is a shallow clone, in which case `GIT_VERSION` may refer to the commit id instead of a tag.
+//! pub static GIT_SHALLOW: Option<bool> = Some(false);
+//!
+//! /// If the crate was compiled from within a git-repository, `GIT_AHEAD` contains
+//! /// the number of commits HEAD is ahead of the upstream branch. `None` if HEAD is
+//! /// detached or there is no upstream branch.
+//! pub static GIT_AHEAD: Option<u32> = Some(0);
+//!
+//! /// If the crate was compiled from within a git-repository, `GIT_BEHIND` contains
+//! /// the number of commits HEAD is behind the upstream branch. `None` if HEAD is
+//! /// detached or there is no upstream branch.
+//! pub static GIT_BEHIND: Option<u32> = Some(14);
+//!
+//! /// If the crate was compiled from within a git-repository,
+//! /// `GIT_SPARSE_CHECKOUT` indicates whether the repository is a sparse checkout.
+//! pub static GIT_SPARSE_CHECKOUT: Option<bool> = Some(false);
+//!
+//! /// If the crate was compiled from within a git-repository,
+//! /// `GIT_LFS_PRESENT` indicates whether the repository uses Git LFS.
+//! pub static GIT_LFS_PRESENT: Option<bool> = Some(false);
+//!
+//! /// If the crate was compiled from within a git-repository,
+//! /// `GIT_IS_WORKTREE` indicates whether the checkout is a linked worktree (see `git worktree`).
+//! pub static GIT_IS_WORKTREE: Option<bool> = Some(false);
+//!
+//! /// If the crate was compiled from within a git-repository,
+//! /// `GIT_COMMIT_SIGNED` indicates whether HEAD's commit carries a signature.
+//! /// The signature is not verified.
+//! pub static GIT_COMMIT_SIGNED: Option<bool> = Some(true);
+//!
+//! /// If HEAD's commit is signed, `GIT_COMMIT_SIGNER` contains the issuer's
+//! /// fingerprint (or key id) of an OpenPGP-signature or the public key of an
+//! /// SSH-signature.
+//! pub static GIT_COMMIT_SIGNER: Option<&str> = Some("81D785B9CB9DDE468DAA3984ED866104C572D8DA");
+//!
+//! /// The paths of the repository's submodules and the commits they are pinned to by HEAD.
+//! pub static GIT_SUBMODULES: [(&str, &str); 1] = [("vendor/sdk", "8f232d6ef707e1956a43342693d2a31e72989554")];
+//! /// The submodules as a comma-separated string.
+//! pub static GIT_SUBMODULES_STR: &str = "vendor/sdk 8f232d6ef707e1956a43342693d2a31e72989554";
+//!
+//! /// If the crate was compiled from within a git-repository,
+//! /// `GIT_REMOTE_URL` contains the URL of the remote `origin`, without any credentials.
+//! pub static GIT_REMOTE_URL: Option<&str> = Some("https://github.com/lukaslueg/built");
+//!
+//! /// Whether `GIT_REMOTE_URL` differs from `PKG_REPOSITORY`.
+//! /// `None` if either of them is unknown.
+//! pub static BUILT_FROM_FORK: Option<bool> = Some(false);
+//! ```
+//!
+//! As the signature is not verified, `GIT_COMMIT_SIGNER` should be checked against
+//! the keys one trusts:
+//!
+//! ```rust
+//! # mod built_info {
+//! #    pub static GIT_COMMIT_SIGNED: Option<bool> = Some(true);
+//! #    pub static GIT_COMMIT_SIGNER: Option<&str> = Some("81D785B9CB9DDE468DAA3984ED866104C572D8DA");
+//! # }
+//! #
+//! # static TRUSTED_KEYS: [&str; 1] = ["81D785B9CB9DDE468DAA3984ED866104C572D8DA"];
+//! /// Whether this binary was built from a commit signed by a trusted key
+//! fn built_from_trusted_commit() -> bool {
+//!     built_info::GIT_COMMIT_SIGNED == Some(true)
+//!         && built_info::GIT_COMMIT_SIGNER.is_some_and(|signer| TRUSTED_KEYS.contains(&signer))
+//! }
+//! # assert!(built_from_trusted_commit());
 //! ```
 //!
-//! ### `chrono`
+//! ### `git-cli`
+//! Provides the same information as the `git2`-feature by invoking the `git`-binary
+//! instead of `libgit2`, avoiding the dependency on a C-library. If both features
+//! are enabled, `libgit2` is used by default and the `git`-binary is used as a fallback
+//! in case `libgit2` errors out; use `Options::set_git_backend()` to choose explicitly.
 //!
-//! The build-time is recorded as `BUILT_TIME_UTC`. If `built` is included as a runtime-dependency,
-//! it can parse the string-representation into a `time:Tm` with the help of
-//! `built::util::strptime()`.
+//! ### `git-minimal`
+//! Reads `HEAD`, loose refs and `packed-refs` directly from the git-directory, without
+//! any further dependencies. Only `GIT_COMMIT_HASH`, `GIT_COMMIT_HASH_SHORT`,
+//! `GIT_HEAD_REF`, `GIT_IS_WORKTREE`, `GIT_OPERATION_IN_PROGRESS` and `GIT_SHALLOW`
+//! are available; all other `GIT_*`-values are `None`.
+//!
+//! ### `opentelemetry`
+//! If `built` is included as a runtime-dependency, `built::otel_resource()` turns
+//! `BUILD_TAGS` into an OpenTelemetry-`Resource`, recording e.g. `service.name`,
+//! `service.version` and `vcs.ref.head.revision` as per the semantic conventions.
+//!
+//! ```rust,ignore
+//! let resource = built::otel_resource(&built_info::BUILD_TAGS);
+//! ```
+//!
+//! ### `chrono`, `time` or `jiff`
+//!
+//! The build-time is recorded as `BUILT_TIME_UTC`, `BUILT_TIME_RFC3339` and
+//! `BUILT_TIME_UNIX`. If `built` is included as a runtime-dependency, it can parse the
+//! string-representation into a `time:Tm` with the help of `built::util::strptime()`.
+//! The `time`-feature provides the same constants without depending on `chrono`;
+//! `built::util::parse_rfc2822()` parses them into a `time::OffsetDateTime`. Likewise,
+//! the `jiff`-feature records them by means of `jiff`, which is re-exported as
+//! `built::jiff`; `built::util::strptime_jiff()` parses them into a `jiff::Timestamp`.
+//! If several of these features are enabled, `chrono` takes precedence over `time`,
+//! which takes precedence over `jiff`.
 //!
 //! `built` honors the environment variable `SOURCE_DATE_EPOCH`. If the variable is defined and
 //! parses to a valid UTC timestamp, that build-time is used instead of the current local time.
-//! The variable is silently ignored if defined but but does not parse to a valid UTC timestamp.
+//! If the variable is defined but does not parse to a valid UTC timestamp, a warning is
+//! emitted and the current time is used; use `Options::set_source_date_epoch()` to fail
+//! the build instead. A timestamp before the Unix epoch always fails the build.
+//! `Options::set_deterministic()` requires `SOURCE_DATE_EPOCH` and additionally pins or
+//! omits all other values which depend on the machine compiling the crate.
 //!
 //! ```
 //! /// The built-time in RFC2822, UTC
 //! pub static BUILT_TIME_UTC: &str = "Wed, 27 May 2020 18:12:39 +0000";
+//! /// The build time in RFC3339, UTC.
+//! pub static BUILT_TIME_RFC3339: &str = "2020-05-27T18:12:39Z";
+//! /// The build time as seconds since the Unix epoch.
+//! pub static BUILT_TIME_UNIX: u64 = 1590603159;
 //! ```
 
+mod accessors;
+#[cfg(feature = "audit")]
+mod audit;
+mod banner;
+mod baseline;
+mod c_header;
+mod cache_key;
+mod compat;
 #[cfg(feature = "cargo-lock")]
 mod dependencies;
+mod deterministic;
+mod drift;
 mod environment;
-#[cfg(feature = "git2")]
+mod full_version;
+#[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
 mod git;
+#[cfg(feature = "git-cli")]
+mod git_cli;
+#[cfg(feature = "git-minimal")]
+mod git_min;
+#[cfg(any(feature = "git2", feature = "git-cli"))]
+mod git_signature;
+mod info_struct;
+#[cfg(feature = "jiff")]
+mod jiff_crate;
+mod json;
 #[cfg(feature = "chrono")]
 mod krono;
+#[cfg(feature = "cargo-metadata")]
+mod licenses;
+#[cfg(feature = "manifest")]
+mod manifest;
+#[cfg(feature = "cargo-metadata")]
+mod metadata;
+#[cfg(feature = "opentelemetry")]
+mod otel;
+mod overrides;
+mod overview;
+mod report;
+mod self_check;
+mod signing;
+#[cfg(any(
+    feature = "chrono",
+    feature = "time",
+    feature = "jiff",
+    feature = "cargo-lock"
+))]
+mod source_date_epoch;
+mod stats;
+mod tags;
+#[cfg(feature = "time")]
+mod time_crate;
+#[cfg(feature = "manifest")]
+mod toolchain;
 pub mod util;
 
-use std::{env, fmt, fs, io, io::Write, path};
+use std::{env, fmt, fs, io, io::Write, path, time};
 
 #[cfg(feature = "semver")]
 pub use semver;
@@ -313,7 +817,36 @@ pub use semver;
 #[cfg(feature = "chrono")]
 pub use chrono;
 
+#[cfg(feature = "jiff")]
+pub use jiff;
+
+#[cfg(feature = "opentelemetry")]
+pub use opentelemetry_sdk;
+
+pub use banner::{Banner, BannerStyle};
+pub use baseline::BaselineDeviation;
+pub use cache_key::cache_key;
+pub use compat::{check_compatibility, Incompatibility};
+#[cfg(feature = "cargo-lock")]
+pub use dependencies::SbomFormat;
+#[cfg(feature = "dependency-tree")]
+pub use dependencies::{Dependencies, DependencyRoots};
 pub use environment::CIPlatform;
+#[cfg(feature = "opentelemetry")]
+pub use otel::otel_resource;
+pub use report::Format;
+pub use self_check::{self_check, SelfCheck};
+pub use signing::Signer;
+#[cfg(any(
+    feature = "chrono",
+    feature = "time",
+    feature = "jiff",
+    feature = "cargo-lock"
+))]
+pub use source_date_epoch::SourceDateEpochPolicy;
+
+#[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
+pub use git::{GitBackend, GitOnCi};
 
 #[doc = include_str!("../README.md")]
 #[allow(dead_code)]
@@ -321,10 +854,14 @@ type _READMETEST = ();
 
 macro_rules! write_variable {
     ($writer:expr, $name:expr, $datatype:expr, $value:expr, $doc:expr) => {
-        writeln!(
-            $writer,
-            "#[doc=r#\"{}\"#]\n#[allow(dead_code)]\npub static {}: {} = {};",
-            $doc, $name, $datatype, $value
+        $crate::report::Backend::constant(
+            &mut $writer,
+            $crate::report::Constant {
+                name: $name.to_string(),
+                datatype: $datatype.to_string(),
+                value: $value.to_string(),
+                doc: $doc.to_string(),
+            },
         )?;
     };
 }
@@ -350,6 +887,759 @@ pub(crate) fn fmt_option_str<S: fmt::Display>(o: Option<S>) -> String {
     }
 }
 
+/// Controls the behavior of `write_built_file_with_options()`.
+///
+/// `write_built_file()` and `write_built_file_with_opts()` use the default options.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    #[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
+    git_backend: GitBackend,
+    #[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
+    git_remote: Option<String>,
+    #[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
+    git_on_ci: GitOnCi,
+    #[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
+    git_rerun_if_changed: bool,
+    #[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
+    git_describe: Vec<(String, String)>,
+    #[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
+    git_checkouts: Vec<(String, path::PathBuf)>,
+    #[cfg(feature = "cargo-lock")]
+    include_root_packages: bool,
+    #[cfg(feature = "cargo-lock")]
+    dependency_include: Vec<String>,
+    #[cfg(feature = "cargo-lock")]
+    dependency_exclude: Vec<String>,
+    #[cfg(feature = "cargo-lock")]
+    packed_dependencies: bool,
+    #[cfg(feature = "dependency-tree")]
+    dependency_roots: DependencyRoots,
+    #[cfg(feature = "dependency-tree")]
+    dependency_depth: Option<usize>,
+    #[cfg(feature = "cargo-lock")]
+    sbom_output: Option<(path::PathBuf, SbomFormat)>,
+    #[cfg(feature = "cargo-lock")]
+    embed_sbom: bool,
+    #[cfg(feature = "audit")]
+    advisory_db: Option<path::PathBuf>,
+    manifest_path: Option<path::PathBuf>,
+    build_info_output: Option<path::PathBuf>,
+    build_info_struct: bool,
+    accessors: bool,
+    serde: bool,
+    emit_all: bool,
+    json_output: Option<path::PathBuf>,
+    json_schema_output: Option<path::PathBuf>,
+    c_header_output: Option<path::PathBuf>,
+    overview_output: Option<path::PathBuf>,
+    override_replay_output: Option<path::PathBuf>,
+    override_report_output: Option<path::PathBuf>,
+    outputs: Vec<(Format, path::PathBuf)>,
+    drift_warnings: bool,
+    subprocess_timeout: Option<time::Duration>,
+    subprocess_retries: u32,
+    #[cfg(feature = "manifest")]
+    validate_features: bool,
+    captured_env_vars: Vec<String>,
+    full_version_parts: Option<Vec<String>>,
+    #[cfg(feature = "manifest")]
+    toolchain_file: bool,
+    build_host_info: bool,
+    collection_stats: bool,
+    deterministic: bool,
+    baseline: Vec<(String, Vec<String>)>,
+    baseline_deviation: BaselineDeviation,
+    #[cfg(any(
+        feature = "chrono",
+        feature = "time",
+        feature = "jiff",
+        feature = "cargo-lock"
+    ))]
+    source_date_epoch: SourceDateEpochPolicy,
+    strict_env: bool,
+    #[cfg(feature = "manifest")]
+    manifest_rerun_if_changed: bool,
+    signer: Option<Signer>,
+}
+
+impl Options {
+    /// Select the implementation used to query the git-repository.
+    ///
+    /// Defaults to `GitBackend::Git2` if the `git2`-feature is enabled.
+    #[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
+    pub fn set_git_backend(&mut self, backend: GitBackend) -> &mut Self {
+        self.git_backend = backend;
+        self
+    }
+
+    /// Select the git-remote whose URL is recorded as `GIT_REMOTE_URL`.
+    ///
+    /// Defaults to `origin`.
+    #[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
+    pub fn set_git_remote(&mut self, name: impl Into<String>) -> &mut Self {
+        self.git_remote = Some(name.into());
+        self
+    }
+
+    /// Control how the git-repository is handled if a CI-platform is detected.
+    ///
+    /// Defaults to `GitOnCi::Attempt`.
+    #[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
+    pub fn set_git_on_ci(&mut self, on_ci: GitOnCi) -> &mut Self {
+        self.git_on_ci = on_ci;
+        self
+    }
+
+    /// Emit `cargo:rerun-if-changed` for git's `HEAD`, the branch it points to and
+    /// the index, so the build-script re-runs on commit and checkout.
+    ///
+    /// Notice that once any `rerun-if-changed` is emitted, Cargo no longer re-runs
+    /// the build-script if any other file in the package changes; also emit
+    /// `cargo:rerun-if-changed=build.rs` or similar. Defaults to `false`.
+    #[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
+    pub fn set_git_rerun_if_changed(&mut self, rerun: bool) -> &mut Self {
+        self.git_rerun_if_changed = rerun;
+        self
+    }
+
+    /// Additionally describe HEAD relative to the most recent tag matching a
+    /// glob-pattern, e.g. `("GIT_VERSION_INFRA", "infra-*")`, recording the result
+    /// under the given name. This allows repositories with multiple tag-namespaces to
+    /// record each of them; `GIT_VERSION` considers all tags.
+    ///
+    /// Not available with `GitBackend::Minimal`. Defaults to no additional descriptions.
+    #[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
+    pub fn set_git_describe<I, N, P>(&mut self, constants: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (N, P)>,
+        N: Into<String>,
+        P: Into<String>,
+    {
+        self.git_describe = constants
+            .into_iter()
+            .map(|(name, pattern)| (name.into(), pattern.into()))
+            .collect();
+        self
+    }
+
+    /// Additionally record the git-repository at `path`, e.g. a vendored fork of a
+    /// dependency, writing the same `GIT_*`-constants as for the crate itself, with
+    /// their names prefixed by `prefix`; e.g. the prefix `VENDORED_FOO_` yields
+    /// `VENDORED_FOO_GIT_COMMIT_HASH`. Can be called multiple times to record multiple
+    /// repositories.
+    ///
+    /// A relative `path` is relative to the crate's manifest-directory. Defaults to no
+    /// additional repositories.
+    #[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
+    pub fn add_git_checkout(
+        &mut self,
+        prefix: impl Into<String>,
+        path: impl Into<path::PathBuf>,
+    ) -> &mut Self {
+        self.git_checkouts.push((prefix.into(), path.into()));
+        self
+    }
+
+    /// Whether the root-package(s) are included in `DEPENDENCIES`, regardless of
+    /// the `dependency-tree`-feature. The root-packages are the packages no other
+    /// package in `Cargo.lock` depends upon, e.g. the crate itself and other
+    /// workspace-members. Defaults to `false`.
+    #[cfg(feature = "cargo-lock")]
+    pub fn set_include_root_packages(&mut self, include: bool) -> &mut Self {
+        self.include_root_packages = include;
+        self
+    }
+
+    /// Only serialize the dependencies whose name matches any of the glob-`patterns`
+    /// into `DEPENDENCIES` and its variants. A `*` matches any number of characters,
+    /// a `?` matches exactly one character.
+    ///
+    /// Defaults to including all dependencies.
+    #[cfg(feature = "cargo-lock")]
+    pub fn set_dependency_include<I>(&mut self, patterns: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.dependency_include = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Do not serialize the dependencies whose name matches any of the glob-`patterns`
+    /// into `DEPENDENCIES` and its variants, e.g. `["windows*"]`. Takes precedence over
+    /// `set_dependency_include()`.
+    ///
+    /// Defaults to excluding no dependencies.
+    #[cfg(feature = "cargo-lock")]
+    pub fn set_dependency_exclude<I>(&mut self, patterns: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.dependency_exclude = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Serialize `DEPENDENCIES`, `DIRECT_DEPENDENCIES` and `INDIRECT_DEPENDENCIES` as
+    /// a single string of all names and versions plus a table of offsets into it,
+    /// instead of an array of `(&str, &str)`. Each list is then accessed by means of a
+    /// function like `dependencies()`, returning an iterator over `(&str, &str)`. For
+    /// large dependency-graphs, this avoids two relocations per dependency and
+    /// reduces the size of the binary.
+    ///
+    /// Defaults to `false`.
+    #[cfg(feature = "cargo-lock")]
+    pub fn set_packed_dependencies(&mut self, enabled: bool) -> &mut Self {
+        self.packed_dependencies = enabled;
+        self
+    }
+
+    /// Select the packages relative to which dependencies are classified as direct
+    /// or indirect, in case a workspace has multiple root-packages.
+    ///
+    /// Defaults to `DependencyRoots::All`.
+    #[cfg(feature = "dependency-tree")]
+    pub fn set_dependency_roots(&mut self, roots: DependencyRoots) -> &mut Self {
+        self.dependency_roots = roots;
+        self
+    }
+
+    /// Only serialize the dependencies which are at most `depth` steps away from the
+    /// root-package(s) into `DEPENDENCIES` and `INDIRECT_DEPENDENCIES`, e.g. `Some(1)` for
+    /// only direct dependencies. This keeps the generated arrays small for large
+    /// dependency-graphs. The number of omitted dependencies is recorded as
+    /// `DEPENDENCIES_PRUNED_COUNT` and `INDIRECT_DEPENDENCIES_PRUNED_COUNT`.
+    ///
+    /// Defaults to `None`, serializing all dependencies.
+    #[cfg(feature = "dependency-tree")]
+    pub fn set_dependency_depth(&mut self, depth: Option<usize>) -> &mut Self {
+        self.dependency_depth = depth;
+        self
+    }
+
+    /// Set the path to the package's manifest, from whose directory the discovery of
+    /// the git-repository and `Cargo.lock` starts. This takes precedence over the
+    /// `manifest_location` given to `write_built_file_with_options()`.
+    ///
+    /// `write_built_file()` uses `CARGO_MANIFEST_PATH`, if set.
+    pub fn set_manifest_path(&mut self, path: impl Into<path::PathBuf>) -> &mut Self {
+        self.manifest_path = Some(path.into());
+        self
+    }
+
+    /// The path to the manifest; `Cargo.toml` in `manifest_location` unless set explicitly.
+    pub(crate) fn manifest_path(&self, manifest_location: &path::Path) -> path::PathBuf {
+        self.manifest_path
+            .clone()
+            .unwrap_or_else(|| manifest_location.join("Cargo.toml"))
+    }
+
+    /// Cross-check the features derived from `CARGO_FEATURE_*` against the features
+    /// declared by the package's manifest.
+    ///
+    /// Unknown entries, e.g. injected into the environment by other means, are moved
+    /// from `FEATURES` into `FEATURES_FOREIGN` and a warning is emitted;
+    /// `FEATURES_FOREIGN` is only emitted if validation is enabled.
+    /// Defaults to `false`.
+    #[cfg(feature = "manifest")]
+    pub fn set_validate_features(&mut self, validate: bool) -> &mut Self {
+        self.validate_features = validate;
+        self
+    }
+
+    /// Record the values of the environment-variables `names` during compilation as
+    /// `CAPTURED_ENV`, e.g. `["DEPLOY_ENV", "BUILD_URL"]`. A variable which is not set
+    /// is recorded as `None`.
+    ///
+    /// Emits `cargo:rerun-if-env-changed` for every variable. Defaults to no variables.
+    pub fn set_captured_env_vars<I>(&mut self, names: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.captured_env_vars = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Select the constants whose values make up `FULL_VERSION`, e.g.
+    /// `["PKG_VERSION", "GIT_VERSION", "TARGET"]`. Constants which are not present,
+    /// `None` or empty are skipped.
+    ///
+    /// Defaults to `["PKG_VERSION", "GIT_COMMIT_HASH_SHORT", "PROFILE"]`.
+    pub fn set_full_version_parts<I>(&mut self, parts: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.full_version_parts = Some(parts.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Record the toolchain pinned by the `rust-toolchain.toml` or `rust-toolchain`
+    /// closest to the manifest as `TOOLCHAIN_CHANNEL`, `TOOLCHAIN_COMPONENTS` and
+    /// `TOOLCHAIN_TARGETS`, e.g. to verify that a binary was built using the pinned
+    /// toolchain by comparing with `RUSTC_VERSION`. Skipped if no `manifest_location`
+    /// is given to `write_built_file_with_options()`. Defaults to `false`.
+    ///
+    /// Fails if the toolchain-file can't be parsed.
+    #[cfg(feature = "manifest")]
+    pub fn set_toolchain_file(&mut self, enabled: bool) -> &mut Self {
+        self.toolchain_file = enabled;
+        self
+    }
+
+    /// Record the hostname, the user's name and the operating system of the machine
+    /// compiling the crate as `BUILD_HOST`, `BUILD_USER` and `BUILD_HOST_OS`, e.g. to
+    /// track down problems which only occur with binaries built on a particular machine.
+    ///
+    /// This makes builds irreproducible across machines and may leak personal
+    /// information into the binary. Defaults to `false`.
+    pub fn set_build_host_info(&mut self, enabled: bool) -> &mut Self {
+        self.build_host_info = enabled;
+        self
+    }
+
+    /// Record the status of each collector and the time it took as
+    /// `BUILT_COLLECTION_STATS`, e.g. to find out why a binary lacks information about
+    /// the git-repository.
+    ///
+    /// As the timings differ from build to build, this makes builds irreproducible.
+    /// Defaults to `false`.
+    pub fn set_collection_stats(&mut self, enabled: bool) -> &mut Self {
+        self.collection_stats = enabled;
+        self
+    }
+
+    /// Make `built.rs` reproducible, so rebuilding the package from the same sources
+    /// yields an identical file. `SOURCE_DATE_EPOCH` is required and must be valid,
+    /// values like `NUM_JOBS`, `HOST`, the paths of `RUSTC` and `RUSTDOC` or the
+    /// build-host's name are pinned or omitted, and generating `built.rs` fails if a
+    /// requested value can't be made deterministic, e.g. `BUILT_COLLECTION_STATS`,
+    /// `CAPTURED_ENV` or `RUSTFLAGS` containing absolute paths.
+    ///
+    /// Defaults to `false`.
+    pub fn set_deterministic(&mut self, enabled: bool) -> &mut Self {
+        self.deterministic = enabled;
+        self
+    }
+
+    /// Expect the value of the constant `name` to match any of the glob-`patterns`,
+    /// e.g. `("TARGET", ["x86_64-*-linux-gnu"])` or `("CI_PLATFORM", ["GitHub Actions"])`,
+    /// to catch builds in unexpected environments early. String-values are matched as
+    /// they are, `None` as an empty string and all other values as Rust-expressions,
+    /// without `Some(...)`. Can be called multiple times to set up multiple expectations.
+    ///
+    /// See `set_baseline_deviation()` for how deviations are handled.
+    pub fn add_baseline<I>(&mut self, name: impl Into<String>, patterns: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.baseline
+            .push((name.into(), patterns.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    /// Control how values deviating from the baseline set by `add_baseline()` are
+    /// handled.
+    ///
+    /// Defaults to `BaselineDeviation::Warn`.
+    pub fn set_baseline_deviation(&mut self, deviation: BaselineDeviation) -> &mut Self {
+        self.baseline_deviation = deviation;
+        self
+    }
+
+    /// Control how a `SOURCE_DATE_EPOCH` which is defined but does not parse to a
+    /// valid UTC-timestamp is handled, both for the build time and the creation time
+    /// of an SPDX-SBOM. With `SourceDateEpochPolicy::Strict`, the build-script fails
+    /// instead of recording the current time, which would defeat a reproducible build.
+    ///
+    /// Defaults to `SourceDateEpochPolicy::Lenient`.
+    #[cfg(any(
+        feature = "chrono",
+        feature = "time",
+        feature = "jiff",
+        feature = "cargo-lock"
+    ))]
+    pub fn set_source_date_epoch(&mut self, policy: SourceDateEpochPolicy) -> &mut Self {
+        self.source_date_epoch = policy;
+        self
+    }
+
+    /// The `SourceDateEpochPolicy` in effect; deterministic output always requires a
+    /// valid `SOURCE_DATE_EPOCH`.
+    #[cfg(any(
+        feature = "chrono",
+        feature = "time",
+        feature = "jiff",
+        feature = "cargo-lock"
+    ))]
+    pub(crate) fn source_date_epoch_policy(&self) -> SourceDateEpochPolicy {
+        if self.deterministic {
+            SourceDateEpochPolicy::Strict
+        } else {
+            self.source_date_epoch
+        }
+    }
+
+    /// Fail if the name or value of an environment-variable `built` relies upon is not
+    /// valid UTF-8. Otherwise, such variables are converted lossily and a warning is
+    /// emitted. Defaults to `false`.
+    pub fn set_strict_env(&mut self, strict: bool) -> &mut Self {
+        self.strict_env = strict;
+        self
+    }
+
+    /// Emit `cargo:rerun-if-changed` for the package's `Cargo.toml`.
+    ///
+    /// Notice that once any `rerun-if-changed` is emitted, Cargo no longer re-runs
+    /// the build-script if any other file in the package changes. Defaults to `false`.
+    #[cfg(feature = "manifest")]
+    pub fn set_manifest_rerun_if_changed(&mut self, rerun: bool) -> &mut Self {
+        self.manifest_rerun_if_changed = rerun;
+        self
+    }
+
+    /// Write a software bill of materials, derived from `Cargo.lock`, to `path`.
+    ///
+    /// A relative `path` is relative to the directory `built.rs` is written to,
+    /// usually `OUT_DIR`.
+    #[cfg(feature = "cargo-lock")]
+    pub fn set_sbom_output(
+        &mut self,
+        path: impl Into<path::PathBuf>,
+        format: SbomFormat,
+    ) -> &mut Self {
+        self.sbom_output = Some((path.into(), format));
+        self
+    }
+
+    /// Embed the software bill of materials as `SBOM_JSON`, in the format selected by
+    /// `set_sbom_output()` or CycloneDX. Defaults to `false`.
+    #[cfg(feature = "cargo-lock")]
+    pub fn set_embed_sbom(&mut self, embed: bool) -> &mut Self {
+        self.embed_sbom = embed;
+        self
+    }
+
+    /// Cross-reference `Cargo.lock` with the RustSec advisory-db checked out at `path`,
+    /// recording affected dependencies as `VULNERABLE_DEPENDENCIES`.
+    ///
+    /// `built` never fetches or updates the advisory-db itself, keeping it up to
+    /// date is left to the caller.
+    #[cfg(feature = "audit")]
+    pub fn set_advisory_db(&mut self, path: impl Into<path::PathBuf>) -> &mut Self {
+        self.advisory_db = Some(path.into());
+        self
+    }
+
+    /// Additionally write the generated information to `path` as `const`-items, without
+    /// any attributes, so it can be `include!`d by other build-scripts or used in
+    /// `const`-contexts.
+    ///
+    /// A relative `path` is relative to the directory `built.rs` is written to,
+    /// usually `OUT_DIR`.
+    pub fn set_build_info_output(&mut self, path: impl Into<path::PathBuf>) -> &mut Self {
+        self.build_info_output = Some(path.into());
+        self
+    }
+
+    /// Additionally generate a `BuildInfo`-struct with one field per constant, e.g.
+    /// `pkg_version` for `PKG_VERSION`, and a `BUILD_INFO`-constant holding all values.
+    /// This allows passing the build-time information around as a single value.
+    ///
+    /// Arrays are represented as slices. The individual constants are generated
+    /// regardless. Defaults to `false`.
+    pub fn set_build_info_struct(&mut self, enabled: bool) -> &mut Self {
+        self.build_info_struct = enabled;
+        self
+    }
+
+    /// Additionally generate a `const fn` for every string-constant, e.g.
+    /// `rustdoc_version()` for `RUSTDOC_VERSION`, returning `None` if the constant is
+    /// empty or `None`. This spares checking for empty strings, e.g. if `rustdoc`
+    /// could not be run, or for `GIT_*`-values which may be unavailable on CI.
+    ///
+    /// Defaults to `false`.
+    pub fn set_accessors(&mut self, enabled: bool) -> &mut Self {
+        self.accessors = enabled;
+        self
+    }
+
+    /// Derive `serde::Serialize` on the `BuildInfo`-struct generated by means of
+    /// `set_build_info_struct()`, e.g. to return it from a web-service.
+    ///
+    /// The crate including `built.rs` needs to depend on `serde` with the
+    /// `derive`-feature enabled. Defaults to `false`.
+    pub fn set_serde(&mut self, enabled: bool) -> &mut Self {
+        self.serde = enabled;
+        self
+    }
+
+    /// Additionally generate `ALL`, holding the names and values of all other constants,
+    /// e.g. to dump all of the information without naming each constant. String-values
+    /// are recorded as they are, all other values as they are written in `built.rs`.
+    ///
+    /// Defaults to `false`.
+    pub fn set_emit_all(&mut self, enabled: bool) -> &mut Self {
+        self.emit_all = enabled;
+        self
+    }
+
+    /// Additionally write the generated information to `path` as a JSON-object, keyed
+    /// by the names of the constants.
+    ///
+    /// A relative `path` is relative to the directory `built.rs` is written to,
+    /// usually `OUT_DIR`.
+    pub fn set_json_output(&mut self, path: impl Into<path::PathBuf>) -> &mut Self {
+        self.json_output = Some(path.into());
+        self
+    }
+
+    /// Write a JSON-schema describing the document written by `set_json_output()` to
+    /// `path`. The schema's version is recorded as `BUILT_SCHEMA_VERSION`; it lists
+    /// every constant `built` can produce and does not depend on features or options,
+    /// so documents from any build validate against the schema of the same version.
+    ///
+    /// A relative `path` is relative to the directory `built.rs` is written to,
+    /// usually `OUT_DIR`.
+    pub fn set_json_schema_output(&mut self, path: impl Into<path::PathBuf>) -> &mut Self {
+        self.json_schema_output = Some(path.into());
+        self
+    }
+
+    /// Additionally write the generated information to `path` as a C-header, e.g. for
+    /// consumption by C or C++ code linking against the crate. Every constant which can
+    /// be represented in C is defined as a macro prefixed by `BUILT_`, e.g.
+    /// `#define BUILT_PKG_VERSION "1.2.3"`; constants which are `None` are not defined.
+    ///
+    /// A relative `path` is relative to the directory `built.rs` is written to,
+    /// usually `OUT_DIR`.
+    pub fn set_c_header_output(&mut self, path: impl Into<path::PathBuf>) -> &mut Self {
+        self.c_header_output = Some(path.into());
+        self
+    }
+
+    /// Additionally write an overview of the generated constants to `path` as Markdown:
+    /// their type, description, the collector and feature they originate from, and
+    /// whether they were overridden. As `built.rs` is included into a module, it can't
+    /// document the module itself; the overview can:
+    ///
+    /// ```ignore
+    /// pub mod built_info {
+    ///     #![doc = include_str!(concat!(env!("OUT_DIR"), "/built.md"))]
+    ///     include!(concat!(env!("OUT_DIR"), "/built.rs"));
+    /// }
+    /// ```
+    ///
+    /// A relative `path` is relative to the directory `built.rs` is written to,
+    /// usually `OUT_DIR`.
+    pub fn set_overview_output(&mut self, path: impl Into<path::PathBuf>) -> &mut Self {
+        self.overview_output = Some(path.into());
+        self
+    }
+
+    /// Write the `BUILT_OVERRIDE_*`-assignments which reproduce the generated
+    /// information to `path`, as a POSIX-shell script. Sourcing the script before
+    /// building on another machine reproduces `built.rs`, e.g. to verify it.
+    ///
+    /// A relative `path` is relative to the directory `built.rs` is written to,
+    /// usually `OUT_DIR`.
+    pub fn set_override_replay_output(&mut self, path: impl Into<path::PathBuf>) -> &mut Self {
+        self.override_replay_output = Some(path.into());
+        self
+    }
+
+    /// Write a JSON-document to `path` which lists the constants that were overridden,
+    /// the `BUILT_OVERRIDE_*`-variables of this package that did not override any
+    /// constant (e.g. due to a typo), and the collectors which were disabled, so their
+    /// constants have default values.
+    ///
+    /// A relative `path` is relative to the directory `built.rs` is written to,
+    /// usually `OUT_DIR`.
+    pub fn set_override_report_output(&mut self, path: impl Into<path::PathBuf>) -> &mut Self {
+        self.override_report_output = Some(path.into());
+        self
+    }
+
+    /// Additionally write the generated information to `path` in `format`, e.g. as
+    /// `built.toml` or `built.yaml` for consumption by deployment-tooling. Can be
+    /// called multiple times to write multiple files; all of them are rendered from
+    /// the same `built.rs`, so the information is only collected once.
+    ///
+    /// A relative `path` is relative to the directory `built.rs` is written to,
+    /// usually `OUT_DIR`.
+    pub fn add_output(&mut self, format: Format, path: impl Into<path::PathBuf>) -> &mut Self {
+        self.outputs.push((format, path.into()));
+        self
+    }
+
+    /// Keep a snapshot of provenance-relevant values, like the compiler's version, the
+    /// enabled features and git's `HEAD`, and emit a `cargo:warning` for each value
+    /// which changed since the previous build of the package.
+    ///
+    /// The snapshot is kept in Cargo's target-directory. Defaults to `false`.
+    pub fn set_drift_warnings(&mut self, enabled: bool) -> &mut Self {
+        self.drift_warnings = enabled;
+        self
+    }
+
+    /// Kill `rustc -V` and `rustdoc -V` if they do not finish within `timeout`, e.g. on
+    /// heavily loaded machines, instead of stalling the build. A timeout is an error
+    /// naming the executable, unless the command is retried; see
+    /// `set_subprocess_retries()`.
+    ///
+    /// Defaults to waiting indefinitely.
+    pub fn set_subprocess_timeout(&mut self, timeout: time::Duration) -> &mut Self {
+        self.subprocess_timeout = Some(timeout);
+        self
+    }
+
+    /// How often a command which did not finish within the timeout set by
+    /// `set_subprocess_timeout()` is re-run before giving up. Defaults to `0`.
+    pub fn set_subprocess_retries(&mut self, retries: u32) -> &mut Self {
+        self.subprocess_retries = retries;
+        self
+    }
+
+    /// Sign the generated files after they have been written: `built.rs` and every
+    /// other output, e.g. the JSON-, TOML- and YAML-reports and the SBOM. Each
+    /// signature is written next to the file it signs, see `Signer::signature_path()`.
+    ///
+    /// The path to the signature of `built.rs` is recorded as `BUILT_SIGNATURE`,
+    /// so the build-time information can be verified independently of the binary.
+    pub fn set_signer(&mut self, signer: Option<Signer>) -> &mut Self {
+        self.signer = signer;
+        self
+    }
+}
+
+/// The features `built` was compiled with.
+static BUILT_FEATURES: [(&str, bool); 13] = [
+    ("audit", cfg!(feature = "audit")),
+    ("cargo-lock", cfg!(feature = "cargo-lock")),
+    ("cargo-metadata", cfg!(feature = "cargo-metadata")),
+    ("chrono", cfg!(feature = "chrono")),
+    ("dependency-tree", cfg!(feature = "dependency-tree")),
+    ("git-cli", cfg!(feature = "git-cli")),
+    ("git-minimal", cfg!(feature = "git-minimal")),
+    ("git2", cfg!(feature = "git2")),
+    ("jiff", cfg!(feature = "jiff")),
+    ("manifest", cfg!(feature = "manifest")),
+    ("opentelemetry", cfg!(feature = "opentelemetry")),
+    ("semver", cfg!(feature = "semver")),
+    ("time", cfg!(feature = "time")),
+];
+
+/// The collectors which may contribute to `built.rs`.
+static COLLECTORS: [&str; 13] = [
+    "environment",
+    "toolchain",
+    "build-host",
+    "git",
+    "git-checkout",
+    "dependencies",
+    "sbom",
+    "audit",
+    "manifest",
+    "workspace",
+    "licenses",
+    "time",
+    "signature",
+];
+
+/// Writes which version of `built`, with which features, produced the file and
+/// which of the collectors ran.
+fn write_provenance(collected: &[&str], mut w: impl crate::report::Backend) -> io::Result<()> {
+    write_str_variable!(
+        w,
+        "BUILT_VERSION",
+        env!("CARGO_PKG_VERSION"),
+        "The version of `built` which produced this file."
+    );
+
+    let features = BUILT_FEATURES
+        .iter()
+        .filter_map(|(feature, enabled)| enabled.then_some(*feature))
+        .collect::<Vec<_>>();
+    write_variable!(
+        w,
+        "BUILT_FEATURES",
+        format_args!("[&str; {}]", features.len()),
+        util::ArrayDisplay(&features, |t, f| write!(f, "\"{t}\"")),
+        "The features of `built` which were enabled when this file was produced."
+    );
+
+    debug_assert!(
+        collected.iter().all(|c| COLLECTORS.contains(c)),
+        "every collector must be listed in `COLLECTORS`"
+    );
+    let collectors = COLLECTORS
+        .iter()
+        .map(|collector| {
+            let state = if collected.contains(collector) {
+                "enabled"
+            } else {
+                "disabled"
+            };
+            format!("{collector}={state}")
+        })
+        .collect::<Vec<_>>();
+    write_variable!(
+        w,
+        "BUILT_COLLECTORS",
+        format_args!("[&str; {}]", collectors.len()),
+        util::ArrayDisplay(&collectors, |t, f| write!(f, "\"{t}\"")),
+        "The collectors which contributed to this file as `name=enabled`, and those \
+        which did not, due to features or options, as `name=disabled`."
+    );
+    Ok(())
+}
+
+/// Turns the content of `built.rs` into pure `const`-items, dropping the
+/// attributes.
+fn build_info(built: &str) -> String {
+    let mut out = String::with_capacity(built.len());
+    for line in built.lines() {
+        if line == "#[allow(dead_code)]" || line == "#[used]" {
+            continue;
+        }
+        match line.strip_prefix("pub static ") {
+            Some(item) => {
+                out.push_str("pub const ");
+                out.push_str(item);
+            }
+            None => out.push_str(line),
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// The directory containing the manifest at `manifest_path`.
+#[cfg(any(
+    feature = "cargo-lock",
+    feature = "cargo-metadata",
+    feature = "git2",
+    feature = "git-cli",
+    feature = "git-minimal",
+    feature = "manifest"
+))]
+pub(crate) fn manifest_dir(manifest_path: &path::Path) -> &path::Path {
+    match manifest_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => path::Path::new("."),
+    }
+}
+
+#[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
+pub(crate) fn fmt_option<T: fmt::Display>(o: Option<T>) -> String {
+    match o {
+        Some(v) => format!("Some({v})"),
+        None => "None".to_owned(),
+    }
+}
+
 /// Writes rust-code describing the crate at `manifest_location` to a new file named `dst`.
 ///
 /// # Errors
@@ -357,10 +1647,179 @@ pub(crate) fn fmt_option_str<S: fmt::Display>(o: Option<S>) -> String {
 /// be written to. This should not be a concern if the filename points to
 /// `OUR_DIR`.
 pub fn write_built_file_with_opts(
-    #[cfg(any(feature = "cargo-lock", feature = "git2"))] manifest_location: Option<&path::Path>,
+    #[cfg(any(
+        feature = "cargo-lock",
+        feature = "cargo-metadata",
+        feature = "git2",
+        feature = "git-cli",
+        feature = "git-minimal",
+        feature = "manifest"
+    ))]
+    manifest_location: Option<&path::Path>,
+    dst: &path::Path,
+) -> io::Result<()> {
+    write_built_file_with_options(
+        &Options::default(),
+        #[cfg(any(
+            feature = "cargo-lock",
+            feature = "cargo-metadata",
+            feature = "git2",
+            feature = "git-cli",
+            feature = "git-minimal",
+            feature = "manifest"
+        ))]
+        manifest_location,
+        dst,
+    )
+}
+
+/// Writes rust-code describing the crate at `manifest_location` to a new file named `dst`,
+/// as controlled by `options`.
+///
+/// # Errors
+/// Same as `write_built_file_with_opts()`. Furthermore, an error is returned if
+/// signing any of the files fails.
+pub fn write_built_file_with_options(
+    options: &Options,
+    #[cfg(any(
+        feature = "cargo-lock",
+        feature = "cargo-metadata",
+        feature = "git2",
+        feature = "git-cli",
+        feature = "git-minimal",
+        feature = "manifest"
+    ))]
+    manifest_location: Option<&path::Path>,
     dst: &path::Path,
 ) -> io::Result<()> {
-    let mut built_file = fs::File::create(dst)?;
+    let generated = generate(
+        options,
+        #[cfg(any(
+            feature = "cargo-lock",
+            feature = "cargo-metadata",
+            feature = "git2",
+            feature = "git-cli",
+            feature = "git-minimal",
+            feature = "manifest"
+        ))]
+        manifest_location,
+        dst,
+        false,
+    )?;
+    let built = generated.to_string();
+    fs::write(dst, &built)?;
+
+    let out_dir = dst.parent().unwrap_or(path::Path::new("."));
+    let mut written = vec![dst.to_owned()];
+    let mut write = |path: &path::Path, content: &[u8]| {
+        let path = out_dir.join(path);
+        fs::write(&path, content)?;
+        written.push(path);
+        io::Result::Ok(())
+    };
+    if let Some(path) = &options.build_info_output {
+        write(path, build_info(&built).as_bytes())?;
+    }
+    if let Some(path) = &options.json_output {
+        write(
+            path,
+            report::render(Format::Json, generated.constants())?.as_bytes(),
+        )?;
+    }
+    for (format, path) in &options.outputs {
+        write(
+            path,
+            report::render(*format, generated.constants())?.as_bytes(),
+        )?;
+    }
+    if let Some(path) = &options.json_schema_output {
+        write(path, json::schema()?.as_bytes())?;
+    }
+    if let Some(path) = &options.override_replay_output {
+        let pkg_name = env::var("CARGO_PKG_NAME").unwrap_or_default();
+        write(path, overrides::replay(&built, &pkg_name).as_bytes())?;
+    }
+    if let Some(path) = &options.override_report_output {
+        let pkg_name = env::var("CARGO_PKG_NAME").unwrap_or_default();
+        let vars = env::vars().collect::<Vec<_>>();
+        let report = overrides::report(
+            &built,
+            &pkg_name,
+            vars.iter().map(|(k, v)| (k.as_str(), v.as_str())),
+        );
+        write(path, report.as_bytes())?;
+    }
+    if let Some(path) = &options.c_header_output {
+        write(path, c_header::render(&built).as_bytes())?;
+    }
+    if let Some(path) = &options.overview_output {
+        let pkg_name = env::var("CARGO_PKG_NAME").unwrap_or_default();
+        let overview = overview::render(&built, |name| {
+            overrides::is_overridden(&pkg_name, name, |var| env::var(var).ok())
+        });
+        write(path, overview.as_bytes())?;
+    }
+    if options.drift_warnings {
+        drift::check(&built, out_dir)?;
+    }
+
+    // The SBOM is written while `built.rs` is generated, if the dependencies are known
+    #[cfg(feature = "cargo-lock")]
+    if let Some((path, _)) = options
+        .sbom_output
+        .as_ref()
+        .filter(|_| manifest_location.is_some() || options.manifest_path.is_some())
+    {
+        written.push(out_dir.join(path));
+    }
+
+    if let Some(signer) = &options.signer {
+        for path in &written {
+            signer.sign(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Generates the content of `built.rs`, which is to be written to `dst`. Files other
+/// than `built.rs` are only written if `dry_run` is `false`.
+pub(crate) fn generate(
+    options: &Options,
+    #[cfg(any(
+        feature = "cargo-lock",
+        feature = "cargo-metadata",
+        feature = "git2",
+        feature = "git-cli",
+        feature = "git-minimal",
+        feature = "manifest"
+    ))]
+    manifest_location: Option<&path::Path>,
+    dst: &path::Path,
+    #[cfg_attr(not(feature = "cargo-lock"), allow(unused_variables))] dry_run: bool,
+) -> io::Result<report::Generated> {
+    #[cfg(any(
+        feature = "cargo-lock",
+        feature = "cargo-metadata",
+        feature = "git2",
+        feature = "git-cli",
+        feature = "git-minimal",
+        feature = "manifest"
+    ))]
+    let manifest_location = options
+        .manifest_path
+        .as_deref()
+        .map(manifest_dir)
+        .or(manifest_location);
+
+    if options.deterministic {
+        deterministic::check(
+            env::var("SOURCE_DATE_EPOCH").ok().as_deref(),
+            options.collection_stats,
+            &options.captured_env_vars,
+        )?;
+    }
+
+    let mut built_file = report::Generated::default();
     built_file.write_all(
         r#"//
 // EVERYTHING BELOW THIS POINT WAS AUTO-GENERATED DURING COMPILATION. DO NOT MODIFY.
@@ -369,27 +1828,301 @@ pub fn write_built_file_with_opts(
         .as_ref(),
     )?;
 
-    let envmap = environment::EnvironmentMap::new();
-    envmap.write_ci(&built_file)?;
-    envmap.write_env(&built_file)?;
-    envmap.write_features(&built_file)?;
-    envmap.write_compiler_version(&built_file)?;
-    envmap.write_cfg(&built_file)?;
+    let mut timings = Vec::new();
+    #[cfg_attr(
+        not(any(feature = "git2", feature = "git-cli", feature = "git-minimal")),
+        allow(unused_mut)
+    )]
+    let mut failed = Vec::new();
 
-    #[cfg(feature = "git2")]
-    {
-        if let Some(manifest_location) = manifest_location {
-            git::write_git_version(manifest_location, &built_file)?;
-        }
+    let start = time::Instant::now();
+    let envmap = environment::EnvironmentMap::collect(options)?;
+    overview::write_section(&mut built_file, "environment")?;
+    envmap.write_ci(&mut built_file)?;
+    envmap.write_env(options, &mut built_file)?;
+    envmap.write_features(options, &mut built_file)?;
+    envmap.write_compiler_version(options, &mut built_file)?;
+    envmap.write_cfg(&mut built_file)?;
+    envmap.write_captured_env(options, &mut built_file)?;
+    timings.push(("environment", start.elapsed()));
+
+    let mut collected = vec!["environment"];
+
+    #[cfg(feature = "manifest")]
+    if let (Some(manifest_location), true) = (manifest_location, options.toolchain_file) {
+        collected.push("toolchain");
+        overview::write_section(&mut built_file, "toolchain")?;
+        let start = time::Instant::now();
+        toolchain::write_toolchain(&options.manifest_path(manifest_location), &mut built_file)?;
+        timings.push(("toolchain", start.elapsed()));
+    }
+
+    if options.build_host_info {
+        collected.push("build-host");
+        overview::write_section(&mut built_file, "build-host")?;
+        let start = time::Instant::now();
+        envmap.write_build_host(options, &mut built_file)?;
+        timings.push(("build-host", start.elapsed()));
     }
 
+    #[cfg(any(feature = "git2", feature = "git-cli", feature = "git-minimal"))]
+    let git_head = match manifest_location {
+        Some(manifest_location) => {
+            let ci = envmap.detect_ci();
+            if ci.is_none() || options.git_on_ci != GitOnCi::Skip {
+                collected.push("git");
+            }
+            overview::write_section(&mut built_file, "git")?;
+            let start = time::Instant::now();
+            let git_head =
+                git::write_git_version(manifest_location, options, ci.as_ref(), &mut built_file)?;
+            if collected.contains(&"git") {
+                timings.push(("git", start.elapsed()));
+                if git_head.is_none() {
+                    failed.push("git");
+                }
+            }
+            if !options.git_checkouts.is_empty() {
+                if collected.contains(&"git") {
+                    collected.push("git-checkout");
+                }
+                overview::write_section(&mut built_file, "git-checkout")?;
+                let start = time::Instant::now();
+                for (prefix, path) in &options.git_checkouts {
+                    git::write_git_checkout(
+                        &manifest_location.join(path),
+                        prefix,
+                        options,
+                        ci.as_ref(),
+                        &mut built_file,
+                    )?;
+                }
+                if collected.contains(&"git-checkout") {
+                    timings.push(("git-checkout", start.elapsed()));
+                }
+            }
+            git_head
+        }
+        None => None,
+    };
+    #[cfg(not(any(feature = "git2", feature = "git-cli", feature = "git-minimal")))]
+    let git_head: Option<(String, bool)> = None;
+
     #[cfg(feature = "cargo-lock")]
     if let Some(manifest_location) = manifest_location {
-        dependencies::write_dependencies(manifest_location, &built_file)?;
+        collected.push("dependencies");
+        if options.sbom_output.is_some() || options.embed_sbom {
+            collected.push("sbom");
+        }
+        overview::write_section(&mut built_file, "dependencies")?;
+        let start = time::Instant::now();
+        dependencies::write_dependencies(manifest_location, options, &mut built_file)?;
+        dependencies::write_packages(manifest_location, &mut built_file)?;
+        timings.push(("dependencies", start.elapsed()));
+        let start = time::Instant::now();
+        dependencies::write_sbom(
+            manifest_location,
+            options,
+            (!dry_run).then(|| dst.parent().unwrap_or(path::Path::new("."))),
+            &mut built_file,
+        )?;
+        if collected.contains(&"sbom") {
+            timings.push(("sbom", start.elapsed()));
+        }
+    }
+
+    #[cfg(feature = "audit")]
+    if let (Some(manifest_location), Some(advisory_db)) = (manifest_location, &options.advisory_db)
+    {
+        collected.push("audit");
+        overview::write_section(&mut built_file, "audit")?;
+        let start = time::Instant::now();
+        audit::write_vulnerabilities(manifest_location, advisory_db, &mut built_file)?;
+        timings.push(("audit", start.elapsed()));
+    }
+
+    #[cfg(feature = "manifest")]
+    if let Some(manifest_location) = manifest_location {
+        collected.push("manifest");
+        overview::write_section(&mut built_file, "manifest")?;
+        let start = time::Instant::now();
+        manifest::write_manifest(manifest_location, options, &mut built_file)?;
+        timings.push(("manifest", start.elapsed()));
+
+        collected.push("workspace");
+        overview::write_section(&mut built_file, "workspace")?;
+        let start = time::Instant::now();
+        manifest::write_workspace(manifest_location, options, &mut built_file)?;
+        timings.push(("workspace", start.elapsed()));
     }
 
-    #[cfg(feature = "chrono")]
-    krono::write_time(&built_file)?;
+    #[cfg(feature = "cargo-metadata")]
+    if let Some(manifest_location) = manifest_location {
+        collected.push("licenses");
+        overview::write_section(&mut built_file, "licenses")?;
+        let start = time::Instant::now();
+        let metadata = metadata::load(&options.manifest_path(manifest_location))?;
+        metadata::write_dependency_kinds(&metadata, &mut built_file)?;
+        licenses::write_licenses(&metadata, &mut built_file)?;
+        timings.push(("licenses", start.elapsed()));
+    }
+
+    #[cfg(any(feature = "chrono", feature = "time", feature = "jiff"))]
+    {
+        collected.push("time");
+        overview::write_section(&mut built_file, "time")?;
+        let start = time::Instant::now();
+        let source_date_epoch = options.source_date_epoch_policy();
+        #[cfg(feature = "chrono")]
+        krono::write_time(source_date_epoch, &mut built_file)?;
+        #[cfg(all(feature = "time", not(feature = "chrono")))]
+        time_crate::write_time(source_date_epoch, &mut built_file)?;
+        #[cfg(not(any(feature = "chrono", feature = "time")))]
+        jiff_crate::write_time(source_date_epoch, &mut built_file)?;
+        timings.push(("time", start.elapsed()));
+    }
+
+    if options.signer.is_some() {
+        collected.push("signature");
+    }
+    overview::write_section(&mut built_file, "built")?;
+    write_provenance(&collected, &mut built_file)?;
+
+    let mut banner = format!(
+        "{} {}",
+        env::var("CARGO_PKG_NAME").unwrap_or_default(),
+        env::var("CARGO_PKG_VERSION").unwrap_or_default()
+    );
+    if let Some((commit, dirty)) = git_head {
+        banner.push(' ');
+        banner.push_str(&commit);
+        if dirty {
+            banner.push_str(" dirty");
+        }
+    }
+    banner.push('\0');
+    writeln!(built_file, "#[used]")?;
+    write_variable!(
+        built_file,
+        "BUILT_BANNER",
+        format_args!("[u8; {}]", banner.len()),
+        util::ArrayDisplay(banner.as_bytes(), |b, f| write!(f, "{b}")),
+        "The package's name and version, HEAD's short commit hash and whether the \
+        repository was dirty, as a NUL-terminated string. It is kept by the compiler, \
+        but may still be removed from the binary by the linker or by stripping."
+    );
+
+    write_variable!(
+        built_file,
+        "BUILT_SCHEMA_VERSION",
+        "u32",
+        json::SCHEMA_VERSION,
+        "The version of the JSON-schema describing the information in this file."
+    );
+
+    let signature = options
+        .signer
+        .as_ref()
+        .map(|signer| signer.signature_path(dst));
+    write_variable!(
+        built_file,
+        "BUILT_SIGNATURE",
+        "Option<&str>",
+        fmt_option_str(
+            signature
+                .as_ref()
+                .map(|sig| sig.to_string_lossy().escape_default().to_string())
+        ),
+        "The path to the signature of this file, if it was signed during compilation."
+    );
+
+    if options.deterministic {
+        deterministic::apply(built_file.constants_mut())?;
+    }
+
+    overrides::apply(
+        built_file.constants_mut(),
+        &env::var("CARGO_PKG_NAME").unwrap_or_default(),
+        |var| env::var(var).ok(),
+    )?;
+
+    baseline::check(
+        &built_file.to_string(),
+        &options.baseline,
+        options.baseline_deviation,
+    )?;
+
+    if options.collection_stats {
+        // The signature is only made once the file is complete
+        let collectors = COLLECTORS
+            .iter()
+            .copied()
+            .filter(|collector| *collector != "signature")
+            .collect::<Vec<_>>();
+        let pkg_name = env::var("CARGO_PKG_NAME").unwrap_or_default();
+        stats::render(
+            &built_file.to_string(),
+            &collectors,
+            &timings,
+            &failed,
+            |name| overrides::is_overridden(&pkg_name, name, |var| env::var(var).ok()),
+            &mut built_file,
+        )?;
+    }
+
+    overview::write_section(&mut built_file, "derived")?;
+    tags::render(&built_file.to_string(), &mut built_file)?;
+
+    let full_version_parts = options
+        .full_version_parts
+        .as_ref()
+        .map_or(full_version::DEFAULT_PARTS.to_vec(), |parts| {
+            parts.iter().map(String::as_str).collect()
+        });
+    full_version::render(
+        &built_file.to_string(),
+        &full_version_parts,
+        &mut built_file,
+    )?;
+
+    if options.build_info_struct {
+        let info_struct = info_struct::render(&built_file.to_string(), options.serde);
+        built_file.write_all(info_struct.as_bytes())?;
+    }
+
+    if options.accessors {
+        let accessors = accessors::render(&built_file.to_string());
+        built_file.write_all(accessors.as_bytes())?;
+    }
+
+    if options.emit_all {
+        let all = json::items(&built_file.to_string())
+            .map(|item| {
+                let value = if item.datatype == "&str" {
+                    json::unquote(item.value)
+                } else {
+                    None
+                };
+                (
+                    item.name.to_owned(),
+                    value.unwrap_or_else(|| item.value.to_owned()),
+                )
+            })
+            .collect::<Vec<_>>();
+        write_variable!(
+            built_file,
+            "ALL",
+            format_args!("[(&str, &str); {}]", all.len()),
+            util::ArrayDisplay(&all, |(name, value), f| write!(
+                f,
+                r#"("{}", "{}")"#,
+                name.escape_default(),
+                value.escape_default()
+            )),
+            "The names and values of all of the above constants. String-values are \
+            recorded as they are, all other values as Rust-expressions."
+        );
+    }
 
     built_file.write_all(
         r#"//
@@ -398,11 +2131,11 @@ pub fn write_built_file_with_opts(
 "#
         .as_ref(),
     )?;
-    Ok(())
+    Ok(built_file)
 }
 
-/// A shorthand for calling `write_built_file_with_opts()` with `CARGO_MANIFEST_DIR` and
-/// `[OUT_DIR]/built.rs`.
+/// A shorthand for calling `write_built_file_with_options()` with `CARGO_MANIFEST_PATH`
+/// (or `CARGO_MANIFEST_DIR`) and `[OUT_DIR]/built.rs`.
 ///
 /// # Errors
 /// Same as `write_built_file_with_opts()`.
@@ -411,8 +2144,20 @@ pub fn write_built_file_with_opts(
 /// If `CARGO_MANIFEST_DIR` or `OUT_DIR` are not set.
 pub fn write_built_file() -> io::Result<()> {
     let dst = path::Path::new(&env::var("OUT_DIR").expect("OUT_DIR not set")).join("built.rs");
-    write_built_file_with_opts(
-        #[cfg(any(feature = "cargo-lock", feature = "git2"))]
+    let options = Options {
+        manifest_path: env::var_os("CARGO_MANIFEST_PATH").map(Into::into),
+        ..Options::default()
+    };
+    write_built_file_with_options(
+        &options,
+        #[cfg(any(
+            feature = "cargo-lock",
+            feature = "cargo-metadata",
+            feature = "git2",
+            feature = "git-cli",
+            feature = "git-minimal",
+            feature = "manifest"
+        ))]
         Some(
             env::var("CARGO_MANIFEST_DIR")
                 .expect("CARGO_MANIFEST_DIR")
@@ -0,0 +1,105 @@
+use crate::util::ArrayDisplay;
+use crate::{write_str_variable, write_variable};
+use std::{collections, io};
+
+/// The license-expression used if a package does not declare one.
+static NOASSERTION: &str = "NOASSERTION";
+
+/// Retrieves `(name, version, license)` of all dependencies of the workspace,
+/// excluding the workspace-members.
+fn dependency_licenses(metadata: &cargo_metadata::Metadata) -> Vec<(String, String, String)> {
+    let mut licenses = metadata
+        .packages
+        .iter()
+        .filter(|package| !metadata.workspace_members.contains(&package.id))
+        .map(|package| {
+            (
+                package.name.to_string(),
+                package.version.to_string(),
+                package
+                    .license
+                    .clone()
+                    .unwrap_or_else(|| NOASSERTION.to_owned()),
+            )
+        })
+        .collect::<Vec<_>>();
+    licenses.sort_unstable();
+    licenses.dedup();
+    licenses
+}
+
+/// The distinct license-expressions and the number of packages using them, most
+/// common first.
+fn license_summary(licenses: &[(String, String, String)]) -> Vec<(&str, usize)> {
+    let mut counts = collections::BTreeMap::new();
+    for (_, _, license) in licenses {
+        *counts.entry(license.as_str()).or_insert(0) += 1;
+    }
+    let mut summary = counts.into_iter().collect::<Vec<_>>();
+    summary.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+    summary
+}
+
+pub fn write_licenses(
+    metadata: &cargo_metadata::Metadata,
+    mut w: impl crate::report::Backend,
+) -> io::Result<()> {
+    let licenses = dependency_licenses(metadata);
+    write_variable!(
+        w,
+        "DEPENDENCY_LICENSES",
+        format_args!("[(&str, &str, &str); {}]", licenses.len()),
+        ArrayDisplay(&licenses, |(name, version, license), fmt| write!(
+            fmt,
+            r#"("{}", "{}", "{}")"#,
+            name.escape_default(),
+            version.escape_default(),
+            license.escape_default()
+        )),
+        "The dependencies' names, versions and SPDX license-expressions, as \
+        reported by `cargo metadata`. Packages which do not declare a license-expression \
+        are reported as `NOASSERTION`."
+    );
+    write_str_variable!(
+        w,
+        "LICENSE_SUMMARY_STR",
+        license_summary(&licenses)
+            .iter()
+            .map(|(license, count)| format!("{license} ({count})"))
+            .collect::<Vec<_>>()
+            .join(", "),
+        "The distinct license-expressions of all dependencies and the number of \
+        dependencies using them, as a comma-separated string."
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn license_summary() {
+        let licenses = [
+            ("a", "0.1.0", "MIT"),
+            ("b", "0.1.0", "MIT OR Apache-2.0"),
+            ("c", "0.1.0", "MIT"),
+            ("d", "0.1.0", "Apache-2.0"),
+        ]
+        .map(|(n, v, l)| (n.to_owned(), v.to_owned(), l.to_owned()));
+        assert_eq!(
+            super::license_summary(&licenses),
+            [("MIT", 2), ("Apache-2.0", 1), ("MIT OR Apache-2.0", 1)]
+        );
+    }
+
+    #[test]
+    fn dependency_licenses() {
+        let metadata =
+            crate::metadata::load(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml").as_ref())
+                .unwrap();
+        let licenses = super::dependency_licenses(&metadata);
+        assert!(licenses.iter().all(|(name, _, _)| name != "built"));
+        assert!(licenses
+            .iter()
+            .any(|(name, _, license)| name == "tempfile" && !license.is_empty()));
+    }
+}
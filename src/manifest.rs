@@ -0,0 +1,831 @@
+use crate::util::ArrayDisplay;
+use crate::{fmt_option_str, write_str_variable, write_variable};
+use std::{collections, fs, io, path, sync};
+
+/// The information parsed from a package's `Cargo.toml` which is not available
+/// through the environment.
+#[derive(Debug, Default)]
+pub(crate) struct Manifest {
+    pub name: Option<String>,
+    /// The package's version; `None` if it is inherited from the workspace.
+    pub version: Option<String>,
+    /// The package's edition; `None` if it is inherited from the workspace.
+    pub edition: Option<String>,
+    pub features: Vec<String>,
+    pub metadata: Option<toml::Table>,
+    pub lib: Option<String>,
+    pub bins: Vec<String>,
+    pub workspace: bool,
+    pub workspace_members: Vec<String>,
+    pub workspace_exclude: Vec<String>,
+    pub workspace_package: Option<toml::Table>,
+    pub profile_overrides: Vec<(String, String, String, String)>,
+    /// The `[package]`-table.
+    package: toml::Table,
+    /// The `[profile]`-table.
+    profiles: toml::Table,
+}
+
+impl Manifest {
+    /// Loads the manifest at `manifest_path`. Manifests are parsed only once, even
+    /// if they are requested by multiple collectors.
+    pub fn load(manifest_path: &path::Path) -> io::Result<sync::Arc<Self>> {
+        static CACHE: sync::OnceLock<
+            sync::Mutex<collections::HashMap<path::PathBuf, sync::Arc<Manifest>>>,
+        > = sync::OnceLock::new();
+
+        let mut cache = CACHE
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(sync::PoisonError::into_inner);
+        if let Some(manifest) = cache.get(manifest_path) {
+            return Ok(sync::Arc::clone(manifest));
+        }
+        let content = fs::read_to_string(manifest_path)?;
+        let manifest_dir = crate::manifest_dir(manifest_path);
+        let manifest = sync::Arc::new(Self::parse(&content, manifest_dir)?);
+        cache.insert(manifest_path.to_owned(), sync::Arc::clone(&manifest));
+        Ok(manifest)
+    }
+
+    /// Parses the content of a `Cargo.toml`; `manifest_dir` is used to discover
+    /// the targets Cargo infers from the directory layout.
+    fn parse(content: &str, manifest_dir: &path::Path) -> io::Result<Self> {
+        let doc: toml::Table = content.parse().map_err(io::Error::other)?;
+        let package = doc.get("package").and_then(toml::Value::as_table);
+        let name = package
+            .and_then(|p| p.get("name"))
+            .and_then(toml::Value::as_str);
+        let auto_targets = |key| {
+            package
+                .and_then(|p| p.get(key))
+                .and_then(toml::Value::as_bool)
+                .unwrap_or(true)
+        };
+
+        let edition = match package.and_then(|p| p.get("edition")) {
+            Some(edition) => edition.as_str().map(ToOwned::to_owned),
+            None => Some("2015".to_owned()),
+        };
+
+        let lib = match doc.get("lib").and_then(toml::Value::as_table) {
+            Some(lib) => lib
+                .get("name")
+                .and_then(toml::Value::as_str)
+                .or(name)
+                .map(|n| n.replace('-', "_")),
+            None if auto_targets("autolib") && manifest_dir.join("src/lib.rs").is_file() => {
+                name.map(|n| n.replace('-', "_"))
+            }
+            None => None,
+        };
+
+        let mut bins = doc
+            .get("bin")
+            .and_then(toml::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|bin| bin.get("name")?.as_str().map(ToOwned::to_owned))
+            .collect::<Vec<_>>();
+        if auto_targets("autobins") {
+            if manifest_dir.join("src/main.rs").is_file() {
+                bins.extend(name.map(ToOwned::to_owned));
+            }
+            if let Ok(entries) = fs::read_dir(manifest_dir.join("src/bin")) {
+                for entry in entries.filter_map(Result::ok) {
+                    let path = entry.path();
+                    let bin = if path.extension().is_some_and(|ext| ext == "rs") {
+                        path.file_stem()
+                    } else if path.join("main.rs").is_file() {
+                        path.file_name()
+                    } else {
+                        None
+                    };
+                    bins.extend(bin.and_then(|b| b.to_str()).map(ToOwned::to_owned));
+                }
+            }
+        }
+        bins.sort_unstable();
+        bins.dedup();
+
+        let workspace = doc.get("workspace").and_then(toml::Value::as_table);
+        let strings = |key| {
+            workspace
+                .and_then(|ws| ws.get(key))
+                .and_then(toml::Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str().map(ToOwned::to_owned))
+                .collect::<Vec<_>>()
+        };
+
+        Ok(Self {
+            name: name.map(ToOwned::to_owned),
+            version: match package.and_then(|p| p.get("version")) {
+                Some(version) => version.as_str().map(ToOwned::to_owned),
+                None => Some("0.0.0".to_owned()),
+            },
+            edition,
+            features: declared_features(&doc),
+            metadata: package
+                .and_then(|p| p.get("metadata"))
+                .and_then(toml::Value::as_table)
+                .cloned(),
+            lib,
+            bins,
+            workspace: workspace.is_some(),
+            workspace_members: strings("members"),
+            workspace_exclude: strings("exclude"),
+            workspace_package: workspace
+                .and_then(|ws| ws.get("package")?.as_table())
+                .cloned(),
+            profile_overrides: profile_overrides(&doc),
+            package: package.cloned().unwrap_or_default(),
+            profiles: doc
+                .get("profile")
+                .and_then(toml::Value::as_table)
+                .cloned()
+                .unwrap_or_default(),
+        })
+    }
+
+    /// The value of `key` in the `[workspace.package]`-table, which members inherit by
+    /// means of `key.workspace = true`.
+    fn inherited(&self, key: &str) -> Option<String> {
+        self.workspace_package
+            .as_ref()?
+            .get(key)?
+            .as_str()
+            .map(ToOwned::to_owned)
+    }
+}
+
+/// A package's manifest and the manifest of the workspace it is part of, from which
+/// values inherited by means of `workspace = true` and the profiles are resolved.
+#[derive(Debug, Default)]
+pub(crate) struct DeclaredManifest {
+    package: sync::Arc<Manifest>,
+    workspace: sync::Arc<Manifest>,
+}
+
+impl DeclaredManifest {
+    /// Loads the manifest at `manifest_path` and the manifest of its workspace's root.
+    /// Manifests which can't be loaded are empty.
+    pub fn load(manifest_path: &path::Path) -> Self {
+        let Ok(package) = Manifest::load(manifest_path) else {
+            return Self::default();
+        };
+        let workspace = find_workspace_root(manifest_path)
+            .and_then(|root| Manifest::load(&root))
+            .unwrap_or_else(|_| sync::Arc::clone(&package));
+        Self { package, workspace }
+    }
+
+    /// A `DeclaredManifest` from the content of the package's manifest and the one of
+    /// the workspace's root.
+    #[cfg(test)]
+    pub fn parse(package: &str, workspace: &str) -> Self {
+        let parse =
+            |content| sync::Arc::new(Manifest::parse(content, path::Path::new("")).unwrap());
+        Self {
+            package: parse(package),
+            workspace: parse(workspace),
+        }
+    }
+
+    /// The value of `key` in the `[package]`-table; if the value is inherited, the
+    /// value in the workspace's `[workspace.package]`-table.
+    fn value(&self, key: &str) -> Option<&toml::Value> {
+        let value = self.package.package.get(key)?;
+        if value.get("workspace").and_then(toml::Value::as_bool) == Some(true) {
+            self.workspace.workspace_package.as_ref()?.get(key)
+        } else {
+            Some(value)
+        }
+    }
+
+    /// The value of `key` in the `[package]`-table, if it is a string.
+    pub fn declared_str(&self, key: &str) -> Option<String> {
+        self.value(key)?.as_str().map(ToOwned::to_owned)
+    }
+
+    /// The strings in the value of `key` in the `[package]`-table, if it is an array.
+    pub fn declared_array(&self, key: &str) -> Vec<String> {
+        self.value(key)
+            .and_then(toml::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str().map(ToOwned::to_owned))
+            .collect()
+    }
+
+    /// The value of `key` in the `[profile.<profile>]`-table of the workspace's
+    /// manifest, following `inherits` and the built-in profiles' inheritance. Strings
+    /// are returned as they are, other values as TOML.
+    pub fn profile_value(&self, profile: &str, key: &str) -> Option<String> {
+        let mut profile = profile.to_owned();
+        // Cargo rejects cyclic inheritance, a limit suffices to not loop forever
+        for _ in 0..16 {
+            let table = self.workspace.profiles.get(&profile);
+            match table.and_then(|t| t.get(key)) {
+                Some(toml::Value::String(value)) => return Some(value.clone()),
+                Some(value) => return Some(value.to_string()),
+                None => {}
+            }
+            let inherits = table
+                .and_then(|t| t.get("inherits"))
+                .and_then(toml::Value::as_str);
+            profile = match (inherits, profile.as_str()) {
+                (Some(inherits), _) => inherits.to_owned(),
+                (None, "test") => "dev".to_owned(),
+                (None, "bench") => "release".to_owned(),
+                (None, _) => return None,
+            };
+        }
+        None
+    }
+}
+
+/// The path to the manifest of the workspace which the package at `manifest_path`
+/// is part of; this is the package's own manifest if it is the workspace's root or
+/// not part of a workspace.
+///
+/// Like Cargo, the closest manifest containing a `[workspace]`-table is considered
+/// the root.
+pub(crate) fn find_workspace_root(manifest_path: &path::Path) -> io::Result<path::PathBuf> {
+    if Manifest::load(manifest_path)?.workspace {
+        return Ok(manifest_path.to_owned());
+    }
+    let root = crate::manifest_dir(manifest_path)
+        .ancestors()
+        .skip(1)
+        .map(|dir| dir.join("Cargo.toml"))
+        .filter(|path| path.is_file())
+        .find(|path| Manifest::load(path).is_ok_and(|m| m.workspace));
+    Ok(root.unwrap_or_else(|| manifest_path.to_owned()))
+}
+
+/// The directories matching the `workspace.members`-entry `pattern`, relative to
+/// `root_dir`, which contain a `Cargo.toml`.
+fn expand_member(root_dir: &path::Path, pattern: &str) -> Vec<path::PathBuf> {
+    let mut dirs = vec![root_dir.to_owned()];
+    for segment in pattern.split(['/', '\\']).filter(|s| !s.is_empty()) {
+        if !segment.contains(['*', '?']) {
+            for dir in &mut dirs {
+                dir.push(segment);
+            }
+            continue;
+        }
+        dirs = dirs
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flatten()
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| crate::util::glob_match(segment, name))
+            })
+            .map(|entry| entry.path())
+            .collect();
+    }
+    dirs.retain(|dir| dir.join("Cargo.toml").is_file());
+    dirs
+}
+
+/// The `(name, version)` of the packages which are members of the workspace whose
+/// root is the manifest at `root_path`, sorted by name.
+fn workspace_members(root_path: &path::Path) -> io::Result<Vec<(String, String)>> {
+    let root = Manifest::load(root_path)?;
+    let root_dir = crate::manifest_dir(root_path);
+    let mut dirs = root
+        .workspace_members
+        .iter()
+        .flat_map(|pattern| expand_member(root_dir, pattern))
+        .filter(|dir| {
+            !root
+                .workspace_exclude
+                .iter()
+                .any(|exclude| dir.starts_with(root_dir.join(exclude)))
+        })
+        .collect::<Vec<_>>();
+    dirs.sort_unstable();
+    dirs.dedup();
+
+    let mut members = Vec::new();
+    if let Some(name) = &root.name {
+        members.push((name.clone(), root.version.clone()));
+    }
+    for dir in dirs.iter().filter(|dir| **dir != root_dir) {
+        let member = Manifest::load(&dir.join("Cargo.toml"))?;
+        if let Some(name) = &member.name {
+            members.push((name.clone(), member.version.clone()));
+        }
+    }
+    let mut members = members
+        .into_iter()
+        .map(|(name, version)| {
+            let version = version
+                .or_else(|| root.inherited("version"))
+                .unwrap_or_default();
+            (name, version)
+        })
+        .collect::<Vec<_>>();
+    members.sort_unstable();
+    Ok(members)
+}
+
+/// The settings in the `[profile.<name>.package.<spec>]`-tables, as `(profile, spec,
+/// setting, value)`, the value as TOML.
+fn profile_overrides(doc: &toml::Table) -> Vec<(String, String, String, String)> {
+    let mut overrides = Vec::new();
+    let profiles = doc.get("profile").and_then(toml::Value::as_table);
+    for (profile, table) in profiles.into_iter().flatten() {
+        let packages = table.get("package").and_then(toml::Value::as_table);
+        for (spec, settings) in packages.into_iter().flatten() {
+            for (setting, value) in settings.as_table().into_iter().flatten() {
+                overrides.push((
+                    profile.clone(),
+                    spec.clone(),
+                    setting.clone(),
+                    value.to_string(),
+                ));
+            }
+        }
+    }
+    overrides.sort_unstable();
+    overrides
+}
+
+/// The keys of the `[features]`-table and the implicit features of optional
+/// dependencies which are not referred to by `dep:`.
+fn declared_features(doc: &toml::Table) -> Vec<String> {
+    let explicit = doc.get("features").and_then(toml::Value::as_table);
+    let dep_refs = explicit
+        .into_iter()
+        .flat_map(|features| features.values())
+        .filter_map(toml::Value::as_array)
+        .flatten()
+        .filter_map(|v| v.as_str()?.strip_prefix("dep:"))
+        .collect::<collections::HashSet<_>>();
+
+    let targets = doc
+        .get("target")
+        .and_then(toml::Value::as_table)
+        .into_iter()
+        .flat_map(|targets| targets.values());
+    let dep_tables = std::iter::once(doc)
+        .chain(targets.filter_map(toml::Value::as_table))
+        .flat_map(|table| {
+            ["dependencies", "build-dependencies"]
+                .into_iter()
+                .filter_map(|key| table.get(key)?.as_table())
+        });
+    let optional_deps = dep_tables
+        .flat_map(|deps| deps.iter())
+        .filter(|(_, dep)| {
+            dep.get("optional")
+                .and_then(toml::Value::as_bool)
+                .unwrap_or(false)
+        })
+        .map(|(name, _)| name.as_str())
+        .filter(|name| !dep_refs.contains(name));
+
+    let mut features = explicit
+        .into_iter()
+        .flat_map(|features| features.keys().map(String::as_str))
+        .chain(optional_deps)
+        .map(ToOwned::to_owned)
+        .collect::<Vec<_>>();
+    features.sort_unstable();
+    features.dedup();
+    features
+}
+
+pub fn write_manifest(
+    manifest_location: &path::Path,
+    options: &crate::Options,
+    mut w: impl crate::report::Backend,
+) -> io::Result<()> {
+    let manifest_path = options.manifest_path(manifest_location);
+    let manifest = Manifest::load(&manifest_path)?;
+    if options.manifest_rerun_if_changed {
+        crate::self_check::rerun_if_changed(&manifest_path);
+    }
+
+    let root_path = find_workspace_root(&manifest_path)?;
+    let root = Manifest::load(&root_path)?;
+    let edition = manifest
+        .edition
+        .clone()
+        .or_else(|| root.inherited("edition"));
+    write_variable!(
+        w,
+        "PKG_EDITION",
+        "Option<&str>",
+        fmt_option_str(edition.map(|e| e.escape_default().to_string())),
+        "The edition of the package, as declared in `Cargo.toml`."
+    );
+
+    write_variable!(
+        w,
+        "PKG_DECLARED_FEATURES",
+        format_args!("[&str; {}]", manifest.features.len()),
+        ArrayDisplay(&manifest.features, |t, f| write!(
+            f,
+            "\"{}\"",
+            t.escape_default()
+        )),
+        "The features declared in `Cargo.toml`, including those implied by optional dependencies."
+    );
+
+    write_str_variable!(
+        w,
+        "PKG_METADATA",
+        manifest
+            .metadata
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default(),
+        "The `[package.metadata]`-table of `Cargo.toml`, as TOML."
+    );
+
+    write_variable!(
+        w,
+        "LIB_TARGET",
+        "Option<&str>",
+        fmt_option_str(manifest.lib.as_ref().map(|l| l.escape_default())),
+        "The name of the package's library-target, if any."
+    );
+    write_variable!(
+        w,
+        "BIN_TARGETS",
+        format_args!("[&str; {}]", manifest.bins.len()),
+        ArrayDisplay(&manifest.bins, |t, f| write!(
+            f,
+            "\"{}\"",
+            t.escape_default()
+        )),
+        "The names of the package's binary-targets."
+    );
+
+    write_variable!(
+        w,
+        "PROFILE_OVERRIDES",
+        format_args!(
+            "[(&str, &str, &str, &str); {}]",
+            root.profile_overrides.len()
+        ),
+        ArrayDisplay(
+            &root.profile_overrides,
+            |(profile, spec, setting, value), f| write!(
+                f,
+                r#"("{}", "{}", "{}", "{}")"#,
+                profile.escape_default(),
+                spec.escape_default(),
+                setting.escape_default(),
+                value.escape_default()
+            )
+        ),
+        "The settings of the `[profile.<name>.package.<spec>]`-tables in the workspace's \
+        `Cargo.toml`, as `(profile, package, setting, value)`, the value as TOML."
+    );
+
+    Ok(())
+}
+
+/// Writes the root and the members of the workspace the package is built in.
+pub fn write_workspace(
+    manifest_location: &path::Path,
+    options: &crate::Options,
+    mut w: impl crate::report::Backend,
+) -> io::Result<()> {
+    let root_path = find_workspace_root(&options.manifest_path(manifest_location))?;
+    let root = Manifest::load(&root_path)?;
+    write_variable!(
+        w,
+        "WORKSPACE_ROOT",
+        "Option<&str>",
+        fmt_option_str(root.workspace.then(|| {
+            crate::manifest_dir(&root_path)
+                .display()
+                .to_string()
+                .escape_default()
+                .to_string()
+        })),
+        "The directory of the workspace the package was built in, if any."
+    );
+    let members = if root.workspace {
+        workspace_members(&root_path)?
+    } else {
+        Vec::new()
+    };
+    write_variable!(
+        w,
+        "WORKSPACE_MEMBERS",
+        format_args!("[(&str, &str); {}]", members.len()),
+        ArrayDisplay(&members, |(name, version), f| write!(
+            f,
+            r#"("{}", "{}")"#,
+            name.escape_default(),
+            version.escape_default()
+        )),
+        "The names and versions of the members of the workspace the package was built in."
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    static MANIFEST: &str = r#"
+[package]
+name = "foo-bar"
+edition = "2021"
+
+[package.metadata.about]
+homepage = "https://example.com"
+
+[dependencies]
+serde = { version = "1.0", optional = true }
+regex = { version = "1", optional = true }
+log = "0.4"
+
+[target.'cfg(unix)'.dependencies.nix]
+version = "0.29"
+optional = true
+
+[features]
+default = ["extra"]
+extra = ["dep:regex"]
+
+[[bin]]
+name = "tool"
+path = "src/tool.rs"
+
+[profile.dev.package.sha2]
+opt-level = 3
+
+[profile.dev.package."*"]
+debug = false
+"#;
+
+    #[test]
+    fn parse_manifest() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("src/bin/multi")).unwrap();
+        fs::write(root.path().join("src/lib.rs"), "").unwrap();
+        fs::write(root.path().join("src/main.rs"), "").unwrap();
+        fs::write(root.path().join("src/bin/single.rs"), "").unwrap();
+        fs::write(root.path().join("src/bin/multi/main.rs"), "").unwrap();
+
+        let manifest = super::Manifest::parse(MANIFEST, root.path()).unwrap();
+        assert_eq!(manifest.edition.as_deref(), Some("2021"));
+        assert_eq!(manifest.features, ["default", "extra", "nix", "serde"]);
+        assert_eq!(
+            manifest.metadata.unwrap()["about"]["homepage"].as_str(),
+            Some("https://example.com")
+        );
+        assert_eq!(manifest.lib.as_deref(), Some("foo_bar"));
+        assert_eq!(manifest.bins, ["foo-bar", "multi", "single", "tool"]);
+        assert!(!manifest.workspace);
+        assert_eq!(
+            manifest.profile_overrides,
+            [
+                ("dev", "*", "debug", "false"),
+                ("dev", "sha2", "opt-level", "3")
+            ]
+            .map(|(p, s, k, v)| (
+                p.to_owned(),
+                s.to_owned(),
+                k.to_owned(),
+                v.to_owned()
+            ))
+        );
+
+        let manifest =
+            super::Manifest::parse("[package]\nname = \"baz\"", &root.path().join("src")).unwrap();
+        assert_eq!(manifest.edition.as_deref(), Some("2015"));
+        assert!(manifest.features.is_empty());
+        assert!(manifest.lib.is_none());
+        assert!(manifest.bins.is_empty());
+        assert!(manifest.profile_overrides.is_empty());
+    }
+
+    #[test]
+    fn declared_str() {
+        let manifest = r#"
+[package]
+name = "foo"
+"rust-version" = "1.70" # MSRV
+description = """
+multi-line
+[dependencies]
+"""
+
+[dependencies]
+rust-version = "1.0"
+"#;
+        let manifest = super::DeclaredManifest::parse(manifest, "");
+        assert_eq!(
+            manifest.declared_str("rust-version").as_deref(),
+            Some("1.70")
+        );
+        assert_eq!(
+            manifest.declared_str("description").as_deref(),
+            Some("multi-line\n[dependencies]\n")
+        );
+        assert_eq!(
+            super::DeclaredManifest::parse("[package]\nrust-version.workspace = true\n", "")
+                .declared_str("rust-version"),
+            None
+        );
+    }
+
+    #[test]
+    fn declared_array() {
+        let manifest = r#"
+[package]
+name = "foo"
+keywords = ["build", "provenance"]
+categories = [
+    "development-tools::build-utils", # first, [really]
+    "development-tools",
+]
+"#;
+        let manifest = super::DeclaredManifest::parse(manifest, "");
+        assert_eq!(manifest.declared_array("keywords"), ["build", "provenance"]);
+        assert_eq!(
+            manifest.declared_array("categories"),
+            ["development-tools::build-utils", "development-tools"]
+        );
+        assert!(manifest.declared_array("name").is_empty());
+    }
+
+    #[test]
+    fn declared_inherited() {
+        let workspace = r#"
+[workspace]
+members = ["foo"]
+
+[workspace.package]
+documentation = "https://docs.example.com"
+keywords = ["build"]
+"#;
+        let manifest = super::DeclaredManifest::parse(
+            r#"
+[package]
+name = "foo"
+documentation.workspace = true
+keywords = { workspace = true } # inherited
+"#,
+            workspace,
+        );
+        assert_eq!(
+            manifest.declared_str("documentation").as_deref(),
+            Some("https://docs.example.com")
+        );
+        assert_eq!(manifest.declared_array("keywords"), ["build"]);
+    }
+
+    #[test]
+    fn declared_manifest_load() {
+        let root = tempfile::tempdir().unwrap();
+        let pkg = root.path().join("crates/foo");
+        fs::create_dir_all(&pkg).unwrap();
+        fs::write(
+            root.path().join("Cargo.toml"),
+            "[workspace.package]\nlicense-file = \"LICENSE\"\n",
+        )
+        .unwrap();
+        fs::write(
+            pkg.join("Cargo.toml"),
+            "[package]\nlicense-file.workspace = true\n",
+        )
+        .unwrap();
+        let manifest = super::DeclaredManifest::load(&pkg.join("Cargo.toml"));
+        assert_eq!(
+            manifest.declared_str("license-file").as_deref(),
+            Some("LICENSE")
+        );
+        let manifest = super::DeclaredManifest::load(&root.path().join("missing/Cargo.toml"));
+        assert_eq!(manifest.declared_str("license-file"), None);
+    }
+
+    #[test]
+    fn profile_value() {
+        let profiles = r#"
+[profile.release]
+lto = true
+codegen-units = 1 # for size
+
+[profile.dist]
+inherits = "release"
+strip = "symbols"
+"#;
+        let manifest = super::DeclaredManifest::parse(profiles, profiles);
+        assert_eq!(
+            manifest.profile_value("dist", "lto").as_deref(),
+            Some("true")
+        );
+        assert_eq!(
+            manifest.profile_value("dist", "strip").as_deref(),
+            Some("symbols")
+        );
+        assert_eq!(
+            manifest.profile_value("bench", "codegen-units").as_deref(),
+            Some("1")
+        );
+        assert_eq!(manifest.profile_value("test", "lto"), None);
+
+        // Like Cargo, the profiles of workspace-members are ignored
+        let member = super::DeclaredManifest::parse(profiles, "[workspace]\n");
+        assert_eq!(member.profile_value("release", "lto"), None);
+    }
+
+    #[test]
+    fn find_workspace_root() {
+        let root = tempfile::tempdir().unwrap();
+        let pkg = root.path().join("crates/pkg");
+        fs::create_dir_all(&pkg).unwrap();
+        fs::write(pkg.join("Cargo.toml"), MANIFEST).unwrap();
+        assert_eq!(
+            super::find_workspace_root(&pkg.join("Cargo.toml")).unwrap(),
+            pkg.join("Cargo.toml")
+        );
+
+        let other = tempfile::tempdir().unwrap();
+        let pkg = other.path().join("crates/pkg");
+        fs::create_dir_all(&pkg).unwrap();
+        fs::write(pkg.join("Cargo.toml"), MANIFEST).unwrap();
+        fs::write(
+            other.path().join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            super::find_workspace_root(&pkg.join("Cargo.toml")).unwrap(),
+            other.path().join("Cargo.toml")
+        );
+    }
+
+    #[test]
+    fn workspace_members() {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(
+            root.path().join("Cargo.toml"),
+            r#"
+[workspace]
+members = ["crates/*", "tools/cli"]
+exclude = ["crates/scratch"]
+
+[workspace.package]
+version = "2.0.0"
+edition = "2021"
+"#,
+        )
+        .unwrap();
+        for (dir, manifest) in [
+            (
+                "crates/core",
+                "[package]\nname = \"core\"\nversion = \"1.0.0\"\n",
+            ),
+            (
+                "crates/util",
+                "[package]\nname = \"util\"\nversion.workspace = true\n",
+            ),
+            ("crates/scratch", "[package]\nname = \"scratch\"\n"),
+            ("tools/cli", "[package]\nname = \"cli\"\n"),
+        ] {
+            fs::create_dir_all(root.path().join(dir)).unwrap();
+            fs::write(root.path().join(dir).join("Cargo.toml"), manifest).unwrap();
+        }
+        fs::create_dir_all(root.path().join("crates/docs")).unwrap();
+
+        assert_eq!(
+            super::workspace_members(&root.path().join("Cargo.toml")).unwrap(),
+            [("cli", "0.0.0"), ("core", "1.0.0"), ("util", "2.0.0")]
+                .map(|(n, v)| (n.to_owned(), v.to_owned()))
+        );
+
+        let root_manifest = super::Manifest::load(&root.path().join("Cargo.toml")).unwrap();
+        assert_eq!(root_manifest.inherited("edition").as_deref(), Some("2021"));
+        assert_eq!(root_manifest.inherited("license"), None);
+    }
+
+    #[test]
+    fn load_cached() {
+        let root = tempfile::tempdir().unwrap();
+        let manifest_path = root.path().join("Cargo.toml");
+        fs::write(&manifest_path, MANIFEST).unwrap();
+        let manifest = super::Manifest::load(&manifest_path).unwrap();
+        fs::remove_file(&manifest_path).unwrap();
+        assert!(std::sync::Arc::ptr_eq(
+            &manifest,
+            &super::Manifest::load(&manifest_path).unwrap()
+        ));
+    }
+}
@@ -0,0 +1,203 @@
+use crate::util::TupleArrayDisplay;
+use crate::{write_str_variable, write_variable};
+use cargo_metadata::{DependencyKind, PackageId};
+use std::{collections, env, fs, io, path};
+
+/// The features of the package at `manifest_path` which are enabled for the current
+/// build, as indicated by `CARGO_FEATURE_*`.
+fn enabled_features(manifest_path: &path::Path) -> io::Result<Vec<String>> {
+    let metadata = cargo_metadata::MetadataCommand::new()
+        .manifest_path(manifest_path)
+        .no_deps()
+        .exec()
+        .map_err(io::Error::other)?;
+    let manifest_path = fs::canonicalize(manifest_path)?;
+    let package = metadata
+        .packages
+        .iter()
+        .find(|package| fs::canonicalize(&package.manifest_path).is_ok_and(|p| p == manifest_path))
+        .ok_or_else(|| io::Error::other("the manifest does not declare a package"))?;
+    Ok(package
+        .features
+        .keys()
+        .filter(|feature| {
+            let var = format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"));
+            env::var_os(var).is_some()
+        })
+        .cloned()
+        .collect())
+}
+
+/// Runs `cargo metadata` for the manifest at `manifest_path`, resolving the
+/// dependencies for the features enabled in the current build.
+pub fn load(manifest_path: &path::Path) -> io::Result<cargo_metadata::Metadata> {
+    let mut cmd = cargo_metadata::MetadataCommand::new();
+    cmd.manifest_path(manifest_path)
+        .features(cargo_metadata::CargoOpt::NoDefaultFeatures)
+        .features(cargo_metadata::CargoOpt::SomeFeatures(enabled_features(
+            manifest_path,
+        )?));
+    // Don't require packages for other platforms to be available
+    if let Ok(target) = env::var("TARGET") {
+        cmd.other_options(vec!["--filter-platform".to_owned(), target]);
+    }
+    cmd.exec().map_err(io::Error::other)
+}
+
+/// The dependencies of the root-package, by the kind of dependency they are needed as.
+#[derive(Debug, Default, PartialEq)]
+struct DependencyKinds {
+    runtime: Vec<(String, String)>,
+    build: Vec<(String, String)>,
+    dev: Vec<(String, String)>,
+}
+
+type Nodes<'a> = collections::HashMap<&'a PackageId, &'a cargo_metadata::Node>;
+
+/// The packages `id` directly depends upon by any of `kinds`.
+fn direct_deps<'a>(
+    nodes: &Nodes<'a>,
+    id: &PackageId,
+    kinds: &[DependencyKind],
+) -> Vec<&'a PackageId> {
+    nodes
+        .get(id)
+        .into_iter()
+        .flat_map(|node| &node.deps)
+        .filter(|dep| dep.dep_kinds.iter().any(|info| kinds.contains(&info.kind)))
+        .map(|dep| &dep.pkg)
+        .collect()
+}
+
+/// All packages reachable from `start` by any of `kinds`, including `start`.
+fn reachable<'a>(
+    nodes: &Nodes<'a>,
+    start: Vec<&'a PackageId>,
+    kinds: &[DependencyKind],
+) -> collections::HashSet<&'a PackageId> {
+    let mut seen = collections::HashSet::new();
+    let mut stack = start;
+    while let Some(id) = stack.pop() {
+        if seen.insert(id) {
+            stack.extend(direct_deps(nodes, id, kinds));
+        }
+    }
+    seen
+}
+
+/// Classifies the packages the root-package depends upon.
+///
+/// Runtime-dependencies are reachable by means of normal dependencies alone. Build
+/// dependencies are required to build the root-package or any runtime-dependency,
+/// but are not runtime-dependencies themselves. Dev-dependencies are only required
+/// for tests, examples and benchmarks.
+fn dependency_kinds(metadata: &cargo_metadata::Metadata) -> io::Result<DependencyKinds> {
+    use DependencyKind::{Build, Development, Normal};
+
+    let resolve = metadata
+        .resolve
+        .as_ref()
+        .ok_or_else(|| io::Error::other("`cargo metadata` did not resolve dependencies"))?;
+    let root = resolve
+        .root
+        .as_ref()
+        .ok_or_else(|| io::Error::other("the manifest does not declare a package"))?;
+    let nodes = resolve
+        .nodes
+        .iter()
+        .map(|node| (&node.id, node))
+        .collect::<Nodes<'_>>();
+
+    let runtime = reachable(&nodes, direct_deps(&nodes, root, &[Normal]), &[Normal]);
+    let build_roots = std::iter::once(root)
+        .chain(runtime.iter().copied())
+        .flat_map(|id| direct_deps(&nodes, id, &[Build]))
+        .collect();
+    let build = reachable(&nodes, build_roots, &[Normal, Build]);
+    let dev = reachable(
+        &nodes,
+        direct_deps(&nodes, root, &[Development]),
+        &[Normal, Build],
+    );
+
+    let names = |ids: collections::HashSet<&PackageId>| {
+        let mut names = metadata
+            .packages
+            .iter()
+            .filter(|package| ids.contains(&package.id))
+            .map(|package| (package.name.to_string(), package.version.to_string()))
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+        names
+    };
+    Ok(DependencyKinds {
+        build: names(build.difference(&runtime).copied().collect()),
+        dev: names(
+            dev.iter()
+                .copied()
+                .filter(|id| !runtime.contains(id) && !build.contains(id) && *id != root)
+                .collect(),
+        ),
+        runtime: names(runtime),
+    })
+}
+
+pub fn write_dependency_kinds(
+    metadata: &cargo_metadata::Metadata,
+    mut w: impl crate::report::Backend,
+) -> io::Result<()> {
+    let kinds = dependency_kinds(metadata)?;
+    for (name, deps, doc) in [
+        (
+            "RUNTIME_DEPENDENCIES",
+            &kinds.runtime,
+            "The dependencies required at runtime, as resolved by `cargo metadata`.",
+        ),
+        (
+            "BUILD_DEPENDENCIES",
+            &kinds.build,
+            "The dependencies only required to build the package, e.g. by build-scripts \
+            and procedural macros, as resolved by `cargo metadata`.",
+        ),
+        (
+            "DEV_DEPENDENCIES",
+            &kinds.dev,
+            "The dependencies only required for tests, examples and benchmarks, as \
+            resolved by `cargo metadata`.",
+        ),
+    ] {
+        write_variable!(
+            w,
+            name,
+            format_args!("[(&str, &str); {}]", deps.len()),
+            TupleArrayDisplay(deps),
+            doc
+        );
+        write_str_variable!(
+            w,
+            format_args!("{name}_STR"),
+            deps.iter()
+                .map(|(n, v)| format!("{n} {v}"))
+                .collect::<Vec<_>>()
+                .join(", "),
+            "The dependencies as above, as a comma-separated string."
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn dependency_kinds() {
+        let metadata =
+            super::load(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml").as_ref()).unwrap();
+        let kinds = super::dependency_kinds(&metadata).unwrap();
+        let contains = |deps: &[(String, String)], name| deps.iter().any(|(n, _)| n == name);
+        assert!(contains(&kinds.dev, "tempfile"));
+        assert!(!contains(&kinds.runtime, "tempfile"));
+        assert!(!contains(&kinds.runtime, "built"));
+        assert!(!contains(&kinds.dev, "built"));
+    }
+}
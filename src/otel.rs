@@ -0,0 +1,64 @@
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::Resource;
+
+/// The keys of `BUILD_TAGS` which correspond to an OpenTelemetry semantic convention,
+/// and the attributes they are recorded as.
+static SEMANTIC_CONVENTIONS: [(&str, &str); 4] = [
+    ("pkg.name", "service.name"),
+    ("pkg.version", "service.version"),
+    ("pkg.repository", "vcs.repository.url.full"),
+    ("git.commit", "vcs.ref.head.revision"),
+];
+
+/// Creates an OpenTelemetry-`Resource` from the generated `BUILD_TAGS`.
+///
+/// Tags which correspond to a semantic convention are recorded as such, e.g.
+/// `pkg.version` as `service.version` and `git.commit` as `vcs.ref.head.revision`.
+/// A branch in `git.ref` is recorded as `vcs.ref.head.name`. All tags are also
+/// recorded as they are, prefixed by `built.`, e.g. `built.rustc.version`.
+///
+/// ```
+/// # mod built_info {
+/// #    pub static BUILD_TAGS: [(&str, &str); 2] = [("pkg.name", "foo"), ("pkg.version", "1.2.3")];
+/// # }
+/// let resource = built::otel_resource(&built_info::BUILD_TAGS);
+/// assert_eq!(
+///     resource.get(&"service.version".into()),
+///     Some("1.2.3".into())
+/// );
+/// ```
+pub fn otel_resource(tags: &[(&str, &str)]) -> Resource {
+    let mut attributes = Vec::with_capacity(tags.len() * 2);
+    for (key, value) in tags {
+        if let Some((_, attribute)) = SEMANTIC_CONVENTIONS.iter().find(|(k, _)| k == key) {
+            attributes.push(KeyValue::new(*attribute, value.to_string()));
+        }
+        if *key == "git.ref" {
+            if let Some(branch) = value.strip_prefix("refs/heads/") {
+                attributes.push(KeyValue::new("vcs.ref.head.name", branch.to_owned()));
+            }
+        }
+        attributes.push(KeyValue::new(format!("built.{key}"), value.to_string()));
+    }
+    Resource::builder_empty()
+        .with_attributes(attributes)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn otel_resource() {
+        let resource = super::otel_resource(&[
+            ("pkg.name", "foo"),
+            ("git.ref", "refs/heads/main"),
+            ("rustc.version", "rustc 1.85.0"),
+        ]);
+        let get = |key: &'static str| resource.get(&key.into()).map(|v| v.to_string());
+        assert_eq!(get("service.name").as_deref(), Some("foo"));
+        assert_eq!(get("built.pkg.name").as_deref(), Some("foo"));
+        assert_eq!(get("vcs.ref.head.name").as_deref(), Some("main"));
+        assert_eq!(get("built.rustc.version").as_deref(), Some("rustc 1.85.0"));
+        assert_eq!(get("service.version"), None);
+    }
+}
@@ -0,0 +1,295 @@
+use std::{collections, fmt::Write, io};
+
+/// The constants which are derived from the other constants, and therefore can't be
+/// overridden.
+static DERIVED: [&str; 4] = [
+    "BUILD_TAGS",
+    "FULL_VERSION",
+    "ALL",
+    "BUILT_COLLECTION_STATS",
+];
+
+/// The name of the environment-variable which overrides the constant `name` of the
+/// package `pkg_name`, e.g. `BUILT_OVERRIDE_my_crate_GIT_VERSION`.
+fn var_name(pkg_name: &str, name: &str) -> String {
+    format!("BUILT_OVERRIDE_{}_{name}", pkg_name.replace('-', "_"))
+}
+
+/// Whether the constant `name` of the package `pkg_name` is overridden, i.e. whether
+/// `lookup` returns an override for it.
+pub fn is_overridden(pkg_name: &str, name: &str, lookup: impl Fn(&str) -> Option<String>) -> bool {
+    !DERIVED.contains(&name) && lookup(&var_name(pkg_name, name)).is_some()
+}
+
+/// The type `datatype` of an overridden constant; the length of an array is adjusted
+/// to the one of the overriding `value`.
+fn override_type(datatype: &str, value: &crate::json::Value) -> String {
+    let array = datatype
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .and_then(|inner| inner.rsplit_once(';'));
+    match (array, value) {
+        (Some((item, _)), crate::json::Value::List(items)) => {
+            format!("[{item}; {}]", items.len())
+        }
+        _ => datatype.to_owned(),
+    }
+}
+
+/// Replaces the values of the `constants` for which `lookup` returns an override. The
+/// override of a `&str` is the string itself, the override of any other constant is the
+/// value as a Rust-expression, e.g. `Some(true)`. The override of an array may have a
+/// different length than the original value.
+///
+/// If any override is set or any other `cargo:rerun-if-*` was emitted, Cargo is told to
+/// re-run the build-script if any of the variables changes. Otherwise, this would keep
+/// Cargo from re-running the build-script if any file in the package changes.
+pub(crate) fn apply<'a>(
+    constants: impl IntoIterator<Item = &'a mut crate::report::Constant>,
+    pkg_name: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> io::Result<()> {
+    let mut vars = Vec::new();
+    let mut any_set = false;
+    for constant in constants {
+        if crate::json::COMPILE_TIME_CONSTANTS.contains(&constant.name.as_str()) {
+            continue;
+        }
+        let var = var_name(pkg_name, &constant.name);
+        let value = lookup(&var);
+        vars.push(var);
+        let Some(value) = value else {
+            continue;
+        };
+        any_set = true;
+        if constant.datatype == "&str" {
+            constant.value = format!("\"{}\"", value.escape_default());
+        } else {
+            let value = value.trim();
+            let parsed = crate::json::Value::parse(&constant.name, value).map_err(|e| {
+                io::Error::other(format!(
+                    "invalid value of `{}`: {e}",
+                    var_name(pkg_name, &constant.name)
+                ))
+            })?;
+            constant.datatype = override_type(&constant.datatype, &parsed);
+            constant.value = value.to_owned();
+        }
+    }
+    if any_set || crate::self_check::rerun_emitted() {
+        for var in &vars {
+            crate::self_check::rerun_if_env_changed(var);
+        }
+    }
+    Ok(())
+}
+
+/// Quotes `s` for a POSIX-shell.
+pub(crate) fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Renders the `BUILT_OVERRIDE_*`-assignments which reproduce the constants in the
+/// content of a `built.rs`, as a POSIX-shell script.
+pub fn replay(built: &str, pkg_name: &str) -> String {
+    let mut out = String::from(
+        "# EVERYTHING IN THIS FILE WAS AUTO-GENERATED DURING COMPILATION. DO NOT MODIFY.\n\
+        # Source this file before building to reproduce the information recorded by `built`.\n",
+    );
+    for item in crate::json::items(built).filter(|item| !DERIVED.contains(&item.name)) {
+        let value = if item.datatype == "&str" {
+            crate::json::unquote(item.value)
+        } else {
+            None
+        };
+        let _ = writeln!(
+            out,
+            "export {}={}",
+            var_name(pkg_name, item.name),
+            shell_quote(value.as_deref().unwrap_or(item.value))
+        );
+    }
+    out
+}
+
+/// Renders a JSON-document reporting which constants in the content of a `built.rs`
+/// were overridden, which of the `BUILT_OVERRIDE_*`-variables in `vars` did not
+/// override any constant, and which collectors were disabled, so their constants have
+/// default values.
+pub fn report<'a>(
+    built: &str,
+    pkg_name: &str,
+    vars: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> String {
+    let vars = vars.into_iter().collect::<collections::BTreeMap<_, _>>();
+    let items = crate::json::items(built).collect::<Vec<_>>();
+    let overridden = items
+        .iter()
+        .filter(|item| {
+            is_overridden(pkg_name, item.name, |var| {
+                vars.get(var).map(|_| String::new())
+            })
+        })
+        .map(|item| item.name)
+        .collect::<Vec<_>>();
+
+    // Variables of other packages may share the prefix, e.g. those of `foo-bar` with
+    // `foo`, but their remainder isn't the name of a constant
+    let prefix = var_name(pkg_name, "");
+    let unused = vars
+        .keys()
+        .filter(|var| {
+            var.strip_prefix(&prefix).is_some_and(|name| {
+                !name.is_empty()
+                    && name
+                        .bytes()
+                        .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_')
+                    && !overridden.contains(&name)
+            })
+        })
+        .copied()
+        .collect::<Vec<_>>();
+
+    let disabled = items
+        .iter()
+        .find(|item| item.name == "BUILT_COLLECTORS")
+        .and_then(|item| crate::json::Value::parse(item.name, item.value).ok())
+        .map(|collectors| match collectors {
+            crate::json::Value::List(collectors) => collectors
+                .into_iter()
+                .filter_map(|c| match c {
+                    crate::json::Value::Str(c) => {
+                        c.strip_suffix("=disabled").map(ToOwned::to_owned)
+                    }
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        })
+        .unwrap_or_default();
+
+    let list = |items: &[&str]| {
+        crate::util::ArrayDisplay(items, |s, f| write!(f, "{}", crate::util::JsonStr(s)))
+            .to_string()
+    };
+    format!(
+        "{{\n  \"version\": 1,\n  \"overridden\": {},\n  \"unused\": {},\n  \"disabled_collectors\": {}\n}}\n",
+        list(&overridden),
+        list(&unused),
+        list(&disabled.iter().map(String::as_str).collect::<Vec<_>>())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::{Backend, Constant, Generated};
+    use crate::{write_str_variable, write_variable};
+    use std::io;
+
+    fn generated() -> io::Result<Generated> {
+        let mut w = Generated::default();
+        write_str_variable!(w, "PKG_VERSION", "1.2.3", "The full version.");
+        write_variable!(w, "GIT_DIRTY", "Option<bool>", "Some(true)", "");
+        write_variable!(w, "GIT_VERSION", "Option<&str>", "None", "");
+        write_variable!(w, "FEATURES", "[&str; 1]", r#"["DEFAULT"]"#, "");
+        write_variable!(w, "ALL", "[(&str, &str); 0]", "[]", "");
+        Ok(w)
+    }
+
+    #[test]
+    fn apply() {
+        let lookup = |var: &str| match var {
+            "BUILT_OVERRIDE_foo_bar_PKG_VERSION" => Some("it's \"4.5.6\"".to_owned()),
+            "BUILT_OVERRIDE_foo_bar_GIT_VERSION" => Some(" Some(\"v1\") ".to_owned()),
+            "BUILT_OVERRIDE_foo_bar_FEATURES" => Some("[\"A\", \"B\"]".to_owned()),
+            _ => None,
+        };
+        let mut built = generated().unwrap();
+        super::apply(built.constants_mut(), "foo-bar", lookup).unwrap();
+        assert_eq!(
+            built
+                .constants()
+                .map(|c| format!("{}: {} = {}", c.name, c.datatype, c.value))
+                .collect::<Vec<_>>(),
+            [
+                r#"PKG_VERSION: &str = "it\'s \"4.5.6\"""#,
+                "GIT_DIRTY: Option<bool> = Some(true)",
+                r#"GIT_VERSION: Option<&str> = Some("v1")"#,
+                r#"FEATURES: [&str; 2] = ["A", "B"]"#,
+                "ALL: [(&str, &str); 0] = []",
+            ]
+        );
+        assert_eq!(built.constants().next().unwrap().doc, "The full version.");
+
+        let mut built = generated().unwrap();
+        let err = super::apply(built.constants_mut(), "foo", |var| {
+            (var == "BUILT_OVERRIDE_foo_GIT_DIRTY").then(|| "std::process::exit(1)".to_owned())
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("BUILT_OVERRIDE_foo_GIT_DIRTY"));
+
+        let mut kind = [Constant {
+            name: "OPT_LEVEL_KIND".to_owned(),
+            datatype: "OptLevel".to_owned(),
+            value: "OptLevel::O0".to_owned(),
+            doc: String::new(),
+        }];
+        super::apply(&mut kind, "foo", |_| Some("OptLevel::O3".to_owned())).unwrap();
+        assert_eq!(kind[0].value, "OptLevel::O0");
+    }
+
+    #[test]
+    fn report() {
+        let mut built = generated().unwrap();
+        built
+            .constant(Constant {
+                name: "BUILT_COLLECTORS".to_owned(),
+                datatype: "[&str; 2]".to_owned(),
+                value: r#"["environment=enabled", "git=disabled"]"#.to_owned(),
+                doc: String::new(),
+            })
+            .unwrap();
+        let vars = [
+            ("BUILT_OVERRIDE_foo_GIT_VERSION", "None"),
+            ("BUILT_OVERRIDE_foo_ALL", ""),
+            ("BUILT_OVERRIDE_foo_GIT_VERSIOM", "None"),
+            ("BUILT_OVERRIDE_foo_bar_PKG_VERSION", "1.0.0"),
+            ("PATH", "/bin"),
+        ];
+        assert_eq!(
+            super::report(&built.to_string(), "foo", vars),
+            r#"{
+  "version": 1,
+  "overridden": ["GIT_VERSION"],
+  "unused": ["BUILT_OVERRIDE_foo_ALL", "BUILT_OVERRIDE_foo_GIT_VERSIOM"],
+  "disabled_collectors": ["git"]
+}
+"#
+        );
+    }
+
+    #[test]
+    fn replay() {
+        let built = generated().unwrap().to_string();
+        let replay = super::replay(&built, "foo-bar");
+        assert!(replay.ends_with(
+            r#"export BUILT_OVERRIDE_foo_bar_PKG_VERSION='1.2.3'
+export BUILT_OVERRIDE_foo_bar_GIT_DIRTY='Some(true)'
+export BUILT_OVERRIDE_foo_bar_GIT_VERSION='None'
+export BUILT_OVERRIDE_foo_bar_FEATURES='["DEFAULT"]'
+"#
+        ));
+        assert_eq!(super::shell_quote("it's"), r"'it'\''s'");
+
+        let lookup = |var: &str| {
+            replay
+                .lines()
+                .filter_map(|line| line.strip_prefix("export ")?.split_once('='))
+                .find(|(name, _)| *name == var)
+                .map(|(_, value)| value.trim_matches('\'').to_owned())
+        };
+        let mut replayed = generated().unwrap();
+        super::apply(replayed.constants_mut(), "foo-bar", lookup).unwrap();
+        assert_eq!(replayed.to_string(), built);
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt::Write;
+
+/// The prefix of the comments in `built.rs` which mark the start of the constants
+/// written by a collector.
+static SECTION: &str = "// Collected by ";
+
+/// The features required by the collectors, besides those which are always available.
+static FEATURES: [(&str, &str); 9] = [
+    ("toolchain", "`manifest`"),
+    ("git", "`git2`, `git-cli` or `git-minimal`"),
+    ("git-checkout", "`git2`, `git-cli` or `git-minimal`"),
+    ("dependencies", "`cargo-lock`"),
+    ("audit", "`audit`"),
+    ("manifest", "`manifest`"),
+    ("workspace", "`manifest`"),
+    ("licenses", "`cargo-metadata`"),
+    ("time", "`chrono`, `time` or `jiff`"),
+];
+
+/// Writes the comment marking the start of the constants written by `collector`.
+pub fn write_section(mut w: impl std::io::Write, collector: &str) -> std::io::Result<()> {
+    writeln!(w, "{SECTION}`{collector}`")
+}
+
+/// Escapes `s` for use in a cell of a Markdown-table.
+fn cell(s: &str) -> String {
+    s.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+/// The collectors which wrote the constants in the content of a `built.rs`, in the
+/// same order as `json::items(built)`.
+pub fn collectors(built: &str) -> impl Iterator<Item = &str> {
+    // Every line declaring a constant is an item on its own, so these line up with
+    // `json::items(built)`
+    let mut collector = "";
+    built.lines().filter_map(move |line| {
+        if let Some(name) = line.strip_prefix(SECTION) {
+            collector = name.trim_matches('`');
+            return None;
+        }
+        crate::json::items(line).next().map(|_| collector)
+    })
+}
+
+/// Renders an overview of the constants in the content of a `built.rs` as Markdown:
+/// their type, description, the collector and feature they originate from and whether
+/// they were overridden, i.e. `overridden` returns `true` for their name.
+pub fn render(built: &str, overridden: impl Fn(&str) -> bool) -> String {
+    let mut out = String::from(
+        "Information gathered by [`built`](https://docs.rs/built) during compilation.\n\n\
+        | Constant | Type | Description | Collector | Overridden |\n\
+        |----------|------|-------------|-----------|------------|\n",
+    );
+    for (item, collector) in crate::json::items(built).zip(collectors(built)) {
+        let origin = match FEATURES.iter().find(|(c, _)| *c == collector) {
+            Some((_, feature)) => format!("{collector} ({feature})"),
+            None => collector.to_owned(),
+        };
+        let _ = writeln!(
+            out,
+            "| `{}` | `{}` | {} | {} | {} |",
+            item.name,
+            cell(item.datatype),
+            cell(item.doc.as_deref().unwrap_or_default()),
+            cell(&origin),
+            if overridden(item.name) { "yes" } else { "" }
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    static BUILT: &str = r##"//
+// Collected by `environment`
+#[doc=r#"The full version."#]
+#[allow(dead_code)]
+pub static PKG_VERSION: &str = "1.2.3";
+// Collected by `git`
+#[doc=r#"Whether the repository | was dirty."#]
+#[allow(dead_code)]
+pub static GIT_DIRTY: Option<bool> = Some(true);
+"##;
+
+    #[test]
+    fn render() {
+        assert_eq!(
+            super::render(BUILT, |name| name == "GIT_DIRTY"),
+            "Information gathered by [`built`](https://docs.rs/built) during compilation.
+
+| Constant | Type | Description | Collector | Overridden |
+|----------|------|-------------|-----------|------------|
+| `PKG_VERSION` | `&str` | The full version. | environment |  |
+| `GIT_DIRTY` | `Option<bool>` | Whether the repository \\| was dirty. | git (`git2`, `git-cli` or `git-minimal`) | yes |
+"
+        );
+    }
+}
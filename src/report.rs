@@ -0,0 +1,502 @@
+use crate::json::{self, Value};
+use crate::util::{DocStr, JsonStr};
+use std::{fmt, fmt::Write, io};
+
+/// The formats, besides Rust, in which the generated information can be written.
+///
+/// See `Options::add_output()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A JSON-object, keyed by the names of the constants; same as
+    /// `Options::set_json_output()`.
+    Json,
+    /// A TOML-document, keyed by the names of the constants. As TOML has no notion
+    /// of `null`, `None` is written as an empty inline table, `{}`.
+    Toml,
+    /// A YAML-document, keyed by the names of the constants.
+    Yaml,
+    /// `NAME='value'`-assignments, which can be sourced by a POSIX-shell. Strings are
+    /// written as they are, `None` as an empty string and all other values as JSON.
+    Env,
+}
+
+/// A constant, as emitted by `write_variable!()`. The value is a Rust-expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Constant {
+    pub name: String,
+    pub datatype: String,
+    pub value: String,
+    pub doc: String,
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "#[doc={}]\n#[allow(dead_code)]\npub static {}: {} = {};",
+            DocStr(&self.doc),
+            self.name,
+            self.datatype,
+            self.value
+        )
+    }
+}
+
+/// Where `write_variable!()` emits constants to. By default, constants are written as
+/// Rust-code; a backend may also keep them, to render them in one of the `Format`s.
+pub(crate) trait Backend: io::Write {
+    fn constant(&mut self, constant: Constant) -> io::Result<()> {
+        write!(self, "{constant}")
+    }
+}
+
+impl Backend for Vec<u8> {}
+
+impl<B: Backend + ?Sized> Backend for &mut B {
+    fn constant(&mut self, constant: Constant) -> io::Result<()> {
+        (**self).constant(constant)
+    }
+}
+
+/// Part of the content of a `built.rs`.
+#[derive(Debug)]
+enum Entry {
+    Code(Vec<u8>),
+    Constant(Constant),
+}
+
+/// The content of a `built.rs` as it is generated, keeping the constants apart from
+/// the remaining code.
+#[derive(Debug, Default)]
+pub(crate) struct Generated(Vec<Entry>);
+
+impl Generated {
+    /// The constants, in the order in which they were emitted.
+    pub fn constants(&self) -> impl Iterator<Item = &Constant> {
+        self.0.iter().filter_map(|entry| match entry {
+            Entry::Constant(constant) => Some(constant),
+            Entry::Code(_) => None,
+        })
+    }
+
+    pub fn constants_mut(&mut self) -> impl Iterator<Item = &mut Constant> {
+        self.0.iter_mut().filter_map(|entry| match entry {
+            Entry::Constant(constant) => Some(constant),
+            Entry::Code(_) => None,
+        })
+    }
+}
+
+impl io::Write for Generated {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.0.last_mut() {
+            Some(Entry::Code(code)) => code.extend_from_slice(buf),
+            _ => self.0.push(Entry::Code(buf.to_vec())),
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Backend for Generated {
+    fn constant(&mut self, constant: Constant) -> io::Result<()> {
+        self.0.push(Entry::Constant(constant));
+        Ok(())
+    }
+}
+
+impl fmt::Display for Generated {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.0 {
+            match entry {
+                Entry::Code(code) => f.write_str(&String::from_utf8_lossy(code))?,
+                Entry::Constant(constant) => write!(f, "{constant}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders the constants emitted while generating `built.rs` in one of the `Format`s.
+trait Renderer {
+    fn begin(&self, out: &mut String);
+    fn constant(
+        &self,
+        out: &mut String,
+        first: bool,
+        name: &str,
+        doc: Option<&str>,
+        value: &Value,
+    ) -> io::Result<()>;
+    fn end(&self, _out: &mut String) {}
+}
+
+/// The header of formats which support comments.
+static BANNER: &str =
+    "# EVERYTHING IN THIS FILE WAS AUTO-GENERATED DURING COMPILATION. DO NOT MODIFY.\n";
+
+struct Json;
+
+impl Renderer for Json {
+    fn begin(&self, out: &mut String) {
+        out.push('{');
+    }
+
+    fn constant(
+        &self,
+        out: &mut String,
+        first: bool,
+        name: &str,
+        _doc: Option<&str>,
+        value: &Value,
+    ) -> io::Result<()> {
+        let _ = write!(
+            out,
+            "{}\n  {}: {value}",
+            if first { "" } else { "," },
+            JsonStr(name)
+        );
+        Ok(())
+    }
+
+    fn end(&self, out: &mut String) {
+        out.push_str("\n}\n");
+    }
+}
+
+struct Toml;
+
+impl Toml {
+    /// Renders `value`, with `None`s, which TOML has no notion of, as empty tables.
+    fn value(name: &str, value: &Value, out: &mut String) -> io::Result<()> {
+        match value {
+            Value::Null => out.push_str("{}"),
+            Value::Integer(i) if i64::try_from(*i).is_err() => {
+                return Err(io::Error::other(format!(
+                    "`{name}` is out of range for TOML"
+                )))
+            }
+            Value::List(items) => {
+                out.push('[');
+                for (idx, item) in items.iter().enumerate() {
+                    if idx != 0 {
+                        out.push_str(", ");
+                    }
+                    Self::value(name, item, out)?;
+                }
+                out.push(']');
+            }
+            value => {
+                let _ = write!(out, "{value}");
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Renderer for Toml {
+    fn begin(&self, out: &mut String) {
+        out.push_str(BANNER);
+    }
+
+    fn constant(
+        &self,
+        out: &mut String,
+        _first: bool,
+        name: &str,
+        doc: Option<&str>,
+        value: &Value,
+    ) -> io::Result<()> {
+        if let Some(doc) = doc {
+            let _ = writeln!(out, "# {}", doc.replace('\n', " "));
+        }
+        let _ = write!(out, "{name} = ");
+        Self::value(name, value, out)?;
+        out.push('\n');
+        Ok(())
+    }
+}
+
+struct Yaml;
+
+impl Renderer for Yaml {
+    fn begin(&self, out: &mut String) {
+        out.push_str(BANNER);
+        out.push_str("---\n");
+    }
+
+    fn constant(
+        &self,
+        out: &mut String,
+        _first: bool,
+        name: &str,
+        doc: Option<&str>,
+        value: &Value,
+    ) -> io::Result<()> {
+        if let Some(doc) = doc {
+            let _ = writeln!(out, "# {}", doc.replace('\n', " "));
+        }
+        let _ = writeln!(out, "{name}: {value}");
+        Ok(())
+    }
+}
+
+struct Env;
+
+impl Renderer for Env {
+    fn begin(&self, out: &mut String) {
+        out.push_str(BANNER);
+    }
+
+    fn constant(
+        &self,
+        out: &mut String,
+        _first: bool,
+        name: &str,
+        doc: Option<&str>,
+        value: &Value,
+    ) -> io::Result<()> {
+        if let Some(doc) = doc {
+            let _ = writeln!(out, "# {}", doc.replace('\n', " "));
+        }
+        let value = match value {
+            Value::Null => String::new(),
+            Value::Str(s) => s.clone(),
+            value => value.to_string(),
+        };
+        let _ = writeln!(out, "{name}={}", crate::overrides::shell_quote(&value));
+        Ok(())
+    }
+}
+
+/// Renders `constants` in `format`.
+pub(crate) fn render<'a>(
+    format: Format,
+    constants: impl IntoIterator<Item = &'a Constant>,
+) -> io::Result<String> {
+    let renderer: &dyn Renderer = match format {
+        Format::Json => &Json,
+        Format::Toml => &Toml,
+        Format::Yaml => &Yaml,
+        Format::Env => &Env,
+    };
+    let mut out = String::new();
+    renderer.begin(&mut out);
+    for (idx, constant) in constants
+        .into_iter()
+        .filter(|constant| !json::COMPILE_TIME_CONSTANTS.contains(&constant.name.as_str()))
+        .enumerate()
+    {
+        let value = Value::parse(&constant.name, &constant.value)?;
+        let doc = Some(constant.doc.as_str()).filter(|doc| !doc.is_empty());
+        renderer.constant(&mut out, idx == 0, &constant.name, doc, &value)?;
+    }
+    renderer.end(&mut out);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Constant, Format, Generated};
+    use crate::{write_str_variable, write_variable};
+    use std::io::{self, Write};
+
+    fn generated() -> io::Result<Generated> {
+        let mut w = Generated::default();
+        write!(
+            w,
+            "//\n// EVERYTHING BELOW THIS POINT WAS AUTO-GENERATED\n//\n"
+        )?;
+        write_str_variable!(
+            w,
+            "PKG_NAME",
+            "foo\"bar\u{e9}\\",
+            r#"The "name" of the package."#
+        );
+        write_variable!(w, "NUM_JOBS", "u32", 8, "");
+        write_variable!(w, "GIT_DIRTY", "Option<bool>", "Some(true)", "");
+        write_variable!(w, "GIT_VERSION", "Option<&str>", "None", "");
+        write_variable!(
+            w,
+            "PKG_SEMVER",
+            "(u64, u64, u64, &str, &str)",
+            r#"(1, 2, 3, "rc1", "")"#,
+            ""
+        );
+        write_variable!(
+            w,
+            "DEPENDENCIES",
+            "[(&str, &str); 2]",
+            r#"[("a", "0.1.0"), ("b", "0.2.0")]"#,
+            ""
+        );
+        write_variable!(w, "FEATURES", "[&str; 0]", "[]", "");
+        write_variable!(
+            w,
+            "DUPLICATE_DEPENDENCIES",
+            "[(&str, &[&str]); 1]",
+            r#"[("a", &["0.1.0", "0.2.0"])]"#,
+            ""
+        );
+        write!(
+            w,
+            "//\n// EVERYTHING ABOVE THIS POINT WAS AUTO-GENERATED\n//\n"
+        )?;
+        Ok(w)
+    }
+
+    fn constant(name: &str, datatype: &str, value: &str) -> Constant {
+        Constant {
+            name: name.to_owned(),
+            datatype: datatype.to_owned(),
+            value: value.to_owned(),
+            doc: String::new(),
+        }
+    }
+
+    #[test]
+    fn generated_as_rust() {
+        let built = generated().unwrap();
+        assert_eq!(built.constants().count(), 8);
+        let rust = built.to_string();
+        assert!(rust.starts_with(
+            r##"//
+// EVERYTHING BELOW THIS POINT WAS AUTO-GENERATED
+//
+#[doc=r#"The "name" of the package."#]
+#[allow(dead_code)]
+pub static PKG_NAME: &str = "foo\"bar\u{e9}\\";
+"##
+        ));
+        assert!(rust.ends_with(
+            r##"pub static FEATURES: [&str; 0] = [];
+#[doc=r#""#]
+#[allow(dead_code)]
+pub static DUPLICATE_DEPENDENCIES: [(&str, &[&str]); 1] = [("a", &["0.1.0", "0.2.0"])];
+//
+// EVERYTHING ABOVE THIS POINT WAS AUTO-GENERATED
+//
+"##
+        ));
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            super::render(Format::Json, generated().unwrap().constants()).unwrap(),
+            r#"{
+  "PKG_NAME": "foo\"baré\\",
+  "NUM_JOBS": 8,
+  "GIT_DIRTY": true,
+  "GIT_VERSION": null,
+  "PKG_SEMVER": [1, 2, 3, "rc1", ""],
+  "DEPENDENCIES": [["a", "0.1.0"], ["b", "0.2.0"]],
+  "FEATURES": [],
+  "DUPLICATE_DEPENDENCIES": [["a", ["0.1.0", "0.2.0"]]]
+}
+"#
+        );
+        assert!(super::render(Format::Json, [&constant("FOO", "&str", "Foo")]).is_err());
+        assert_eq!(
+            super::render(
+                Format::Json,
+                [&constant("OPT_LEVEL_KIND", "OptLevel", "OptLevel::O0")]
+            )
+            .unwrap(),
+            "{\n}\n"
+        );
+    }
+
+    #[test]
+    fn toml() {
+        let toml = super::render(Format::Toml, generated().unwrap().constants()).unwrap();
+        assert_eq!(
+            toml,
+            r#"# EVERYTHING IN THIS FILE WAS AUTO-GENERATED DURING COMPILATION. DO NOT MODIFY.
+# The "name" of the package.
+PKG_NAME = "foo\"baré\\"
+NUM_JOBS = 8
+GIT_DIRTY = true
+GIT_VERSION = {}
+PKG_SEMVER = [1, 2, 3, "rc1", ""]
+DEPENDENCIES = [["a", "0.1.0"], ["b", "0.2.0"]]
+FEATURES = []
+DUPLICATE_DEPENDENCIES = [["a", ["0.1.0", "0.2.0"]]]
+"#
+        );
+        assert_eq!(
+            super::render(
+                Format::Toml,
+                [&constant(
+                    "FOO",
+                    "[(&str, Option<&str>); 2]",
+                    r#"[("a", None), ("b", Some("c"))]"#
+                )]
+            )
+            .unwrap()
+            .lines()
+            .last(),
+            Some(r#"FOO = [["a", {}], ["b", "c"]]"#)
+        );
+        assert!(super::render(
+            Format::Toml,
+            [&constant("FOO", "u64", "18446744073709551615")]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn yaml() {
+        assert_eq!(
+            super::render(Format::Yaml, generated().unwrap().constants()).unwrap(),
+            r#"# EVERYTHING IN THIS FILE WAS AUTO-GENERATED DURING COMPILATION. DO NOT MODIFY.
+---
+# The "name" of the package.
+PKG_NAME: "foo\"baré\\"
+NUM_JOBS: 8
+GIT_DIRTY: true
+GIT_VERSION: null
+PKG_SEMVER: [1, 2, 3, "rc1", ""]
+DEPENDENCIES: [["a", "0.1.0"], ["b", "0.2.0"]]
+FEATURES: []
+DUPLICATE_DEPENDENCIES: [["a", ["0.1.0", "0.2.0"]]]
+"#
+        );
+    }
+
+    #[test]
+    fn multiline_doc() {
+        let constant = Constant {
+            doc: "The URL of the remote `origin\nFOO = 1`.".to_owned(),
+            ..constant("GIT_REMOTE_URL", "Option<&str>", "None")
+        };
+        for format in [Format::Toml, Format::Yaml, Format::Env] {
+            let rendered = super::render(format, [&constant]).unwrap();
+            assert!(
+                rendered.contains("\n# The URL of the remote `origin FOO = 1`.\nGIT_REMOTE_URL"),
+                "{rendered}"
+            );
+        }
+    }
+
+    #[test]
+    fn env() {
+        assert_eq!(
+            super::render(Format::Env, generated().unwrap().constants()).unwrap(),
+            r#"# EVERYTHING IN THIS FILE WAS AUTO-GENERATED DURING COMPILATION. DO NOT MODIFY.
+# The "name" of the package.
+PKG_NAME='foo"baré\'
+NUM_JOBS='8'
+GIT_DIRTY='true'
+GIT_VERSION=''
+PKG_SEMVER='[1, 2, 3, "rc1", ""]'
+DEPENDENCIES='[["a", "0.1.0"], ["b", "0.2.0"]]'
+FEATURES='[]'
+DUPLICATE_DEPENDENCIES='[["a", ["0.1.0", "0.2.0"]]]'
+"#
+        );
+    }
+}
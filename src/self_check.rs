@@ -0,0 +1,176 @@
+use std::{cell, env, fmt, io, path};
+
+thread_local! {
+    /// The warnings emitted while a `self_check()` is in progress on this thread.
+    static CAPTURED: cell::RefCell<Option<Vec<String>>> = const { cell::RefCell::new(None) };
+    /// Whether any `cargo:rerun-if-*` was emitted on this thread.
+    static RERUN_EMITTED: cell::Cell<bool> = const { cell::Cell::new(false) };
+}
+
+/// Emits a `cargo:warning`, or records it if a `self_check()` is in progress.
+pub(crate) fn warn(msg: impl fmt::Display) {
+    let msg = msg.to_string();
+    let unhandled = CAPTURED.with_borrow_mut(|captured| match captured {
+        Some(warnings) => {
+            warnings.push(msg);
+            None
+        }
+        None => Some(msg),
+    });
+    if let Some(msg) = unhandled {
+        println!("cargo:warning={msg}");
+    }
+}
+
+/// Emits `cargo:rerun-if-changed`, unless a `self_check()` is in progress.
+#[cfg(any(
+    feature = "git2",
+    feature = "git-cli",
+    feature = "git-minimal",
+    feature = "manifest"
+))]
+pub(crate) fn rerun_if_changed(path: &path::Path) {
+    if CAPTURED.with_borrow(Option::is_none) {
+        println!("cargo:rerun-if-changed={}", path.display());
+        RERUN_EMITTED.set(true);
+    }
+}
+
+/// Emits `cargo:rerun-if-env-changed`, unless a `self_check()` is in progress.
+pub(crate) fn rerun_if_env_changed(name: &str) {
+    if CAPTURED.with_borrow(Option::is_none) {
+        println!("cargo:rerun-if-env-changed={name}");
+        RERUN_EMITTED.set(true);
+    }
+}
+
+/// Whether any `cargo:rerun-if-*` was emitted, in which case Cargo no longer re-runs
+/// the build-script if any file in the package changes.
+pub(crate) fn rerun_emitted() -> bool {
+    RERUN_EMITTED.get()
+}
+
+/// Restores the previous state of `CAPTURED`, even if collecting panics.
+struct Capture(Option<Option<Vec<String>>>);
+
+impl Capture {
+    fn start() -> Self {
+        Self(Some(CAPTURED.replace(Some(Vec::new()))))
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        CAPTURED
+            .replace(self.0.take().flatten())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for Capture {
+    fn drop(&mut self) {
+        if let Some(previous) = self.0.take() {
+            CAPTURED.set(previous);
+        }
+    }
+}
+
+/// The result of `self_check()`.
+#[derive(Debug, Clone, Default)]
+pub struct SelfCheck {
+    /// The `(name, type, value)` of every constant which would be written to
+    /// `built.rs`; `value` is the Rust-expression as it would appear in the file.
+    pub values: Vec<(String, String, String)>,
+    /// The warnings which would be emitted to Cargo.
+    pub warnings: Vec<String>,
+}
+
+impl SelfCheck {
+    /// The value of the constant `name` as it would be written to `built.rs`, if any.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(n, _, _)| n == name)
+            .map(|(_, _, value)| value.as_str())
+    }
+}
+
+/// Runs all enabled collectors as `write_built_file_with_options()` would, without
+/// writing any files or emitting any `cargo:`-instructions.
+///
+/// This is intended to be called from a build-script, e.g. to check the
+/// configuration of `built` or to inspect the values which would be collected.
+///
+/// ```rust,no_run
+/// let check = built::self_check(
+///     &built::Options::default(),
+///     # #[cfg(any(feature = "cargo-lock", feature = "cargo-metadata", feature = "git2", feature = "git-cli", feature = "git-minimal", feature = "manifest"))]
+///     Some(std::path::Path::new(".")),
+/// )
+/// .expect("Failed to collect build-time information");
+/// for warning in &check.warnings {
+///     println!("cargo:warning={warning}");
+/// }
+/// ```
+///
+/// # Errors
+/// Same as `write_built_file_with_options()`.
+pub fn self_check(
+    options: &crate::Options,
+    #[cfg(any(
+        feature = "cargo-lock",
+        feature = "cargo-metadata",
+        feature = "git2",
+        feature = "git-cli",
+        feature = "git-minimal",
+        feature = "manifest"
+    ))]
+    manifest_location: Option<&path::Path>,
+) -> io::Result<SelfCheck> {
+    let dst = env::var_os("OUT_DIR")
+        .map_or_else(Default::default, path::PathBuf::from)
+        .join("built.rs");
+    let capture = Capture::start();
+    let built = crate::generate(
+        options,
+        #[cfg(any(
+            feature = "cargo-lock",
+            feature = "cargo-metadata",
+            feature = "git2",
+            feature = "git-cli",
+            feature = "git-minimal",
+            feature = "manifest"
+        ))]
+        manifest_location,
+        &dst,
+        true,
+    );
+    let warnings = capture.finish();
+    Ok(SelfCheck {
+        values: built?
+            .constants()
+            .map(|constant| {
+                (
+                    constant.name.clone(),
+                    constant.datatype.clone(),
+                    constant.value.clone(),
+                )
+            })
+            .collect(),
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn capture() {
+        let capture = super::Capture::start();
+        super::warn("foo");
+        let inner = super::Capture::start();
+        super::warn("bar");
+        assert_eq!(inner.finish(), ["bar"]);
+        super::warn("baz");
+        assert_eq!(capture.finish(), ["foo", "baz"]);
+        assert!(super::CAPTURED.with_borrow(Option::is_none));
+    }
+}
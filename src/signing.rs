@@ -0,0 +1,107 @@
+use std::{ffi, io, path, process};
+
+/// The means by which the files written by `built` get signed.
+///
+/// See `Options::set_signer()`.
+#[derive(Debug, Clone)]
+pub enum Signer {
+    /// Sign using `ssh-keygen -Y sign` and the given private key.
+    Ssh(path::PathBuf),
+    /// Sign using `minisign -S` and the given secret key.
+    Minisign(path::PathBuf),
+    /// Run a user-provided command. The path to the file to be signed and the path
+    /// the signature is expected to be written to are appended as the last two arguments.
+    Command(ffi::OsString, Vec<ffi::OsString>),
+}
+
+impl Signer {
+    /// The path of the signature for the file at `path`.
+    #[must_use]
+    pub fn signature_path(&self, path: &path::Path) -> path::PathBuf {
+        let mut sig = path.as_os_str().to_owned();
+        sig.push(match self {
+            // Both `ssh-keygen` and `minisign` can't be told otherwise
+            Self::Ssh(_) | Self::Command(..) => ".sig",
+            Self::Minisign(_) => ".minisig",
+        });
+        sig.into()
+    }
+
+    /// Signs the file at `path`, returning the path to the signature.
+    ///
+    /// # Errors
+    /// If the signing command can't be executed or fails.
+    pub fn sign(&self, path: &path::Path) -> io::Result<path::PathBuf> {
+        let sig = self.signature_path(path);
+        let mut cmd = match self {
+            Self::Ssh(key) => {
+                let mut cmd = process::Command::new("ssh-keygen");
+                cmd.args(["-Y", "sign", "-n", "file", "-f"])
+                    .arg(key)
+                    .arg(path);
+                cmd
+            }
+            Self::Minisign(key) => {
+                let mut cmd = process::Command::new("minisign");
+                cmd.args(["-S", "-s"]).arg(key).arg("-m").arg(path);
+                cmd
+            }
+            Self::Command(program, args) => {
+                let mut cmd = process::Command::new(program);
+                cmd.args(args).arg(path).arg(&sig);
+                cmd
+            }
+        };
+        let output = cmd.stdin(process::Stdio::null()).output().map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!(
+                    "failed to execute `{}`: {e}",
+                    cmd.get_program().to_string_lossy()
+                ),
+            )
+        })?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "`{}` failed to sign `{}`: {}",
+                cmd.get_program().to_string_lossy(),
+                path.display(),
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            )));
+        }
+        Ok(sig)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Signer;
+    use std::fs;
+
+    #[test]
+    fn signature_path() {
+        let p = std::path::Path::new("out/built.rs");
+        assert_eq!(
+            Signer::Ssh("key".into()).signature_path(p),
+            std::path::Path::new("out/built.rs.sig")
+        );
+        assert_eq!(
+            Signer::Minisign("key".into()).signature_path(p),
+            std::path::Path::new("out/built.rs.minisig")
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn sign_with_command() {
+        let root = tempfile::tempdir().unwrap();
+        let file = root.path().join("built.rs");
+        fs::write(&file, "signme").unwrap();
+
+        // A "signature" that is just a copy of the file
+        let sig = Signer::Command("cp".into(), vec![]).sign(&file).unwrap();
+        assert_eq!(fs::read_to_string(sig).unwrap(), "signme");
+
+        assert!(Signer::Command("false".into(), vec![]).sign(&file).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use std::io;
+
+/// How a `SOURCE_DATE_EPOCH` which is defined but not a valid UTC-timestamp is
+/// handled. A `SOURCE_DATE_EPOCH` before the Unix epoch is always an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SourceDateEpochPolicy {
+    /// Emit a `cargo:warning` and use the current time instead.
+    #[default]
+    Lenient,
+    /// Fail the build, e.g. to not silently defeat a reproducible build.
+    Strict,
+}
+
+/// The timestamp `SOURCE_DATE_EPOCH` is set to, converted by means of
+/// `from_timestamp`, or `None` if it is not set. Fails if it is before the Unix epoch.
+pub(crate) fn get<T>(
+    policy: SourceDateEpochPolicy,
+    from_timestamp: impl FnOnce(i64) -> Option<T>,
+) -> io::Result<Option<T>> {
+    parse(
+        std::env::var("SOURCE_DATE_EPOCH").ok(),
+        policy,
+        from_timestamp,
+    )
+}
+
+fn parse<T>(
+    val: Option<String>,
+    policy: SourceDateEpochPolicy,
+    from_timestamp: impl FnOnce(i64) -> Option<T>,
+) -> io::Result<Option<T>> {
+    let Some(val) = val else {
+        return Ok(None);
+    };
+    let msg = match val.parse::<i64>() {
+        Ok(ts) if ts < 0 => {
+            return Err(io::Error::other(
+                "SOURCE_DATE_EPOCH is before the Unix epoch",
+            ))
+        }
+        Ok(ts) => match from_timestamp(ts) {
+            Some(now) => return Ok(Some(now)),
+            None => "SOURCE_DATE_EPOCH can't be represented as a UTC-time",
+        },
+        Err(_) => "SOURCE_DATE_EPOCH defined, but not a i64",
+    };
+    match policy {
+        SourceDateEpochPolicy::Lenient => {
+            crate::self_check::warn(msg);
+            Ok(None)
+        }
+        SourceDateEpochPolicy::Strict => Err(io::Error::other(msg)),
+    }
+}
+
+/// Formats `secs` since the Unix epoch as an RFC 3339 UTC-timestamp.
+#[cfg(feature = "cargo-lock")]
+pub(crate) fn rfc3339(secs: u64) -> String {
+    // Howard Hinnant's `civil_from_days`
+    let days = secs / 86400;
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs % 86400 / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SourceDateEpochPolicy::{Lenient, Strict};
+
+    #[test]
+    fn parse() {
+        let from_timestamp = |ts: i64| u64::try_from(ts).ok();
+        for policy in [Lenient, Strict] {
+            assert_eq!(super::parse(None, policy, from_timestamp).unwrap(), None);
+            assert_eq!(
+                super::parse(Some("1716639359".to_owned()), policy, from_timestamp).unwrap(),
+                Some(1716639359)
+            );
+        }
+        assert_eq!(
+            super::parse(Some("yesterday".to_owned()), Lenient, from_timestamp).unwrap(),
+            None
+        );
+        assert!(super::parse(Some("yesterday".to_owned()), Strict, from_timestamp).is_err());
+        let never = |_| None::<u64>;
+        assert_eq!(
+            super::parse(Some("1716639359".to_owned()), Lenient, never).unwrap(),
+            None
+        );
+        assert!(super::parse(Some("1716639359".to_owned()), Strict, never).is_err());
+        for policy in [Lenient, Strict] {
+            assert!(super::parse(Some("-1".to_owned()), policy, from_timestamp).is_err());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "cargo-lock")]
+    fn rfc3339() {
+        assert_eq!(super::rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(super::rfc3339(1_709_251_199), "2024-02-29T23:59:59Z");
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{util::ArrayDisplay, write_variable};
+use std::{io, time};
+
+/// The status of `collector`: `skipped` if it did not run, `overridden` if any of the
+/// constants it wrote were overridden, `failed` if it could not gather its
+/// information and `ok` otherwise; followed by the time it took if it ran.
+fn status(
+    collector: &str,
+    items: &[(&str, &str)],
+    timings: &[(&str, time::Duration)],
+    failed: &[&str],
+    overridden: &impl Fn(&str) -> bool,
+) -> String {
+    let Some((_, duration)) = timings.iter().find(|(c, _)| *c == collector) else {
+        return "skipped".to_owned();
+    };
+    let status = if items
+        .iter()
+        .any(|(name, c)| *c == collector && overridden(name))
+    {
+        "overridden"
+    } else if failed.contains(&collector) {
+        "failed"
+    } else {
+        "ok"
+    };
+    format!("{status} {:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Renders `BUILT_COLLECTION_STATS`, the status of each of `collectors` given how long
+/// they took (`timings`), which of them `failed` and which constants in the content of
+/// a `built.rs` were overridden, i.e. `overridden` returns `true` for their name.
+pub fn render(
+    built: &str,
+    collectors: &[&str],
+    timings: &[(&str, time::Duration)],
+    failed: &[&str],
+    overridden: impl Fn(&str) -> bool,
+    mut w: impl crate::report::Backend,
+) -> io::Result<()> {
+    let items = crate::json::items(built)
+        .map(|item| item.name)
+        .zip(crate::overview::collectors(built))
+        .collect::<Vec<_>>();
+    let stats = collectors
+        .iter()
+        .map(|collector| {
+            let status = status(collector, &items, timings, failed, &overridden);
+            (*collector, status)
+        })
+        .collect::<Vec<_>>();
+    write_variable!(
+        w,
+        "BUILT_COLLECTION_STATS",
+        format_args!("[(&str, &str); {}]", stats.len()),
+        ArrayDisplay(&stats, |(collector, status), f| write!(
+            f,
+            r#"("{collector}", "{status}")"#
+        )),
+        "The status of each collector while producing this file (`ok`, `failed`, \
+        `skipped` or `overridden`) and the time it took, e.g. `ok 1.2ms`."
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time;
+
+    #[test]
+    fn render() {
+        let built = r#"// Collected by `environment`
+#[allow(dead_code)]
+pub static PKG_VERSION: &str = "1.2.3";
+// Collected by `git`
+#[allow(dead_code)]
+pub static GIT_VERSION: Option<&str> = None;
+// Collected by `time`
+#[allow(dead_code)]
+pub static BUILT_TIME_UTC: &str = "Tue, 14 Feb 2017 05:21:41 GMT";
+"#;
+        let timings = [
+            ("environment", time::Duration::from_micros(1300)),
+            ("git", time::Duration::from_millis(12)),
+            ("time", time::Duration::ZERO),
+        ];
+        let mut stats = Vec::new();
+        super::render(
+            built,
+            &["environment", "git", "audit", "time"],
+            &timings,
+            &["git"],
+            |name| name == "BUILT_TIME_UTC",
+            &mut stats,
+        )
+        .unwrap();
+        assert!(String::from_utf8(stats).unwrap().ends_with(
+            r#"pub static BUILT_COLLECTION_STATS: [(&str, &str); 4] = [("environment", "ok 1.3ms"), ("git", "failed 12.0ms"), ("audit", "skipped"), ("time", "overridden 0.0ms")];
+"#
+        ));
+    }
+}
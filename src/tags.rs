@@ -0,0 +1,92 @@
+use crate::{util::ArrayDisplay, write_variable};
+use std::io;
+
+/// The keys of `BUILD_TAGS` and the constants providing their values. Keys are
+/// lowercase, dot-separated and never change their meaning once added.
+static TAGS: [(&str, &str); 18] = [
+    ("pkg.name", "PKG_NAME"),
+    ("pkg.version", "PKG_VERSION"),
+    ("pkg.repository", "PKG_REPOSITORY"),
+    ("git.version", "GIT_VERSION"),
+    ("git.commit", "GIT_COMMIT_HASH"),
+    ("git.ref", "GIT_HEAD_REF"),
+    ("git.dirty", "GIT_DIRTY"),
+    ("rustc.version", "RUSTC_VERSION"),
+    ("cargo.version", "CARGO_VERSION"),
+    ("build.target", "TARGET"),
+    ("build.host", "HOST"),
+    ("build.profile", "PROFILE"),
+    ("build.opt_level", "OPT_LEVEL"),
+    ("build.panic", "PANIC_STRATEGY"),
+    ("build.features", "FEATURES_STR"),
+    ("build.time", "BUILT_TIME_UTC"),
+    ("ci.platform", "CI_PLATFORM"),
+    ("built.version", "BUILT_VERSION"),
+];
+
+/// The value of a tag for a constant of `datatype` and `value`; `None` if the value
+/// is `None` or an empty string.
+pub(crate) fn tag_value(datatype: &str, value: &str) -> Option<String> {
+    if let Some(inner) = datatype
+        .strip_prefix("Option<")
+        .and_then(|s| s.strip_suffix('>'))
+    {
+        let value = value.strip_prefix("Some(")?.strip_suffix(')')?;
+        return tag_value(inner, value);
+    }
+    match datatype {
+        "&str" => crate::json::unquote(value).filter(|s| !s.is_empty()),
+        _ => Some(value.to_owned()),
+    }
+}
+
+/// Renders `BUILD_TAGS`, derived from the constants in the content of a `built.rs`.
+pub fn render(built: &str, mut w: impl crate::report::Backend) -> io::Result<()> {
+    let items = crate::json::items(built).collect::<Vec<_>>();
+    let tags = TAGS
+        .iter()
+        .filter_map(|(key, name)| {
+            let item = items.iter().find(|item| item.name == *name)?;
+            Some((*key, tag_value(item.datatype, item.value)?))
+        })
+        .collect::<Vec<_>>();
+    write_variable!(
+        w,
+        "BUILD_TAGS",
+        format_args!("[(&str, &str); {}]", tags.len()),
+        ArrayDisplay(&tags, |(key, value), f| write!(
+            f,
+            r#"("{key}", "{}")"#,
+            value.escape_default()
+        )),
+        "Key/value-pairs for attaching to telemetry, e.g. as OpenTelemetry \
+        resource-attributes. Keys are lowercase and dot-separated, like `pkg.version`, \
+        `git.commit` or `rustc.version`, and are stable across versions of `built`. \
+        Values which are unknown or empty are omitted."
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn render() {
+        let built = r#"#[allow(dead_code)]
+pub static PKG_VERSION: &str = "1.2.3";
+#[allow(dead_code)]
+pub static PKG_REPOSITORY: &str = "";
+#[allow(dead_code)]
+pub static GIT_VERSION: Option<&str> = None;
+#[allow(dead_code)]
+pub static GIT_COMMIT_HASH: Option<&str> = Some("ca2af4f");
+#[allow(dead_code)]
+pub static GIT_DIRTY: Option<bool> = Some(false);
+"#;
+        let mut tags = Vec::new();
+        super::render(built, &mut tags).unwrap();
+        assert!(String::from_utf8(tags).unwrap().ends_with(
+            r#"pub static BUILD_TAGS: [(&str, &str); 3] = [("pkg.version", "1.2.3"), ("git.commit", "ca2af4f"), ("git.dirty", "false")];
+"#
+        ));
+    }
+}
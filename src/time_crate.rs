@@ -0,0 +1,73 @@
+#[cfg(not(feature = "chrono"))]
+use crate::{write_str_variable, write_variable};
+#[cfg(not(feature = "chrono"))]
+use std::io;
+use time::format_description::well_known::Rfc2822;
+#[cfg(any(test, not(feature = "chrono")))]
+use time::format_description::well_known::Rfc3339;
+
+/// Parse a time-string as formatted by `built` into a `time::OffsetDateTime`.
+///
+/// ```
+/// pub mod build_info {
+///     pub static BUILT_TIME_UTC: &'static str = "Tue, 14 Feb 2017 05:21:41 GMT";
+/// }
+///
+/// assert_eq!(built::util::parse_rfc2822(&build_info::BUILT_TIME_UTC).year(), 2017);
+/// ```
+///
+/// # Panics
+/// If the string can't be parsed. This should never happen with input provided
+/// by `built`.
+#[must_use]
+pub fn parse_rfc2822(s: &str) -> time::OffsetDateTime {
+    time::OffsetDateTime::parse(s, &Rfc2822)
+        .unwrap()
+        .to_offset(time::UtcOffset::UTC)
+}
+
+/// Writes the same constants as `krono::write_time()`, by means of the `time`-crate.
+#[cfg(not(feature = "chrono"))]
+pub fn write_time(
+    policy: crate::SourceDateEpochPolicy,
+    mut w: impl crate::report::Backend,
+) -> io::Result<()> {
+    let now = crate::source_date_epoch::get(policy, |ts| {
+        time::OffsetDateTime::from_unix_timestamp(ts).ok()
+    })?
+    .unwrap_or_else(|| {
+        time::OffsetDateTime::now_utc()
+            .replace_nanosecond(0)
+            .unwrap()
+    });
+    write_str_variable!(
+        w,
+        "BUILT_TIME_UTC",
+        now.format(&Rfc2822).map_err(io::Error::other)?,
+        "The build time in RFC2822, UTC."
+    );
+    write_str_variable!(
+        w,
+        "BUILT_TIME_RFC3339",
+        now.format(&Rfc3339).map_err(io::Error::other)?,
+        "The build time in RFC3339, UTC."
+    );
+    write_variable!(
+        w,
+        "BUILT_TIME_UNIX",
+        "u64",
+        u64::try_from(now.unix_timestamp()).map_err(io::Error::other)?,
+        "The build time as seconds since the Unix epoch."
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn parse_rfc2822() {
+        let t = super::parse_rfc2822("Sat, 25 May 2024 12:15:59 +0000");
+        assert_eq!(t.unix_timestamp(), 1716639359);
+        assert_eq!(t.format(&super::Rfc3339).unwrap(), "2024-05-25T12:15:59Z");
+    }
+}
@@ -0,0 +1,175 @@
+use crate::util::ArrayDisplay;
+use crate::{fmt_option_str, write_variable};
+use std::{fs, io, path};
+
+/// The toolchain pinned by a `rust-toolchain`- or `rust-toolchain.toml`-file.
+#[derive(Debug, Default, PartialEq)]
+struct Toolchain {
+    channel: Option<String>,
+    components: Vec<String>,
+    targets: Vec<String>,
+}
+
+impl Toolchain {
+    /// Parses the content of a toolchain-file, either in the legacy format, which
+    /// only names the channel, or in the TOML-format.
+    fn parse(content: &str) -> io::Result<Self> {
+        let lines = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect::<Vec<_>>();
+        if let [channel] = lines.as_slice() {
+            if !channel.contains(['=', '[']) {
+                return Ok(Self {
+                    channel: Some((*channel).to_owned()),
+                    ..Self::default()
+                });
+            }
+        }
+
+        let doc: toml::Table = content.parse().map_err(io::Error::other)?;
+        let toolchain = doc.get("toolchain").and_then(toml::Value::as_table);
+        let strings = |key| {
+            toolchain
+                .and_then(|t| t.get(key))
+                .and_then(toml::Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str().map(ToOwned::to_owned))
+                .collect()
+        };
+        Ok(Self {
+            channel: toolchain
+                .and_then(|t| t.get("channel"))
+                .and_then(toml::Value::as_str)
+                .map(ToOwned::to_owned),
+            components: strings("components"),
+            targets: strings("targets"),
+        })
+    }
+}
+
+/// Finds the toolchain-file which applies to the package at `manifest_path`, the same
+/// way `rustup` does: the closest directory containing a `rust-toolchain` or
+/// `rust-toolchain.toml`, the former taking precedence.
+fn find(manifest_path: &path::Path) -> Option<path::PathBuf> {
+    manifest_path.ancestors().skip(1).find_map(|dir| {
+        ["rust-toolchain", "rust-toolchain.toml"]
+            .into_iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    })
+}
+
+/// Writes `TOOLCHAIN_CHANNEL`, `TOOLCHAIN_COMPONENTS` and `TOOLCHAIN_TARGETS`, as
+/// pinned by the toolchain-file which applies to the package at `manifest_path`.
+pub fn write_toolchain(
+    manifest_path: &path::Path,
+    mut w: impl crate::report::Backend,
+) -> io::Result<()> {
+    let toolchain = match find(manifest_path) {
+        Some(path) => Toolchain::parse(&fs::read_to_string(&path)?)
+            .map_err(|e| io::Error::other(format!("failed to parse `{}`: {e}", path.display())))?,
+        None => Toolchain::default(),
+    };
+
+    write_variable!(
+        w,
+        "TOOLCHAIN_CHANNEL",
+        "Option<&str>",
+        fmt_option_str(toolchain.channel.map(|c| c.escape_default().to_string())),
+        "The channel pinned by `rust-toolchain.toml` or `rust-toolchain`, if any."
+    );
+    write_variable!(
+        w,
+        "TOOLCHAIN_COMPONENTS",
+        format_args!("[&str; {}]", toolchain.components.len()),
+        ArrayDisplay(&toolchain.components, |t, f| write!(
+            f,
+            "\"{}\"",
+            t.escape_default()
+        )),
+        "The components required by `rust-toolchain.toml`."
+    );
+    write_variable!(
+        w,
+        "TOOLCHAIN_TARGETS",
+        format_args!("[&str; {}]", toolchain.targets.len()),
+        ArrayDisplay(&toolchain.targets, |t, f| write!(
+            f,
+            "\"{}\"",
+            t.escape_default()
+        )),
+        "The targets required by `rust-toolchain.toml`."
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Toolchain;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Toolchain::parse("nightly-2020-07-10\n").unwrap(),
+            Toolchain {
+                channel: Some("nightly-2020-07-10".to_owned()),
+                ..Toolchain::default()
+            }
+        );
+        assert_eq!(
+            Toolchain::parse(
+                r#"
+# Pinned for reproducibility
+[toolchain]
+"channel" = "1.85.0" # stable
+components = [ "rustfmt", "clippy" ] # [not, a, component]
+targets = [
+    "wasm32-unknown-unknown",
+    "thumbv7em-none-eabihf",
+]
+profile = "minimal"
+"#
+            )
+            .unwrap(),
+            Toolchain {
+                channel: Some("1.85.0".to_owned()),
+                components: vec!["rustfmt".to_owned(), "clippy".to_owned()],
+                targets: vec![
+                    "wasm32-unknown-unknown".to_owned(),
+                    "thumbv7em-none-eabihf".to_owned()
+                ],
+            }
+        );
+        assert_eq!(
+            Toolchain::parse("[toolchain]\nchannel = \"stable\"\n").unwrap(),
+            Toolchain {
+                channel: Some("stable".to_owned()),
+                ..Toolchain::default()
+            }
+        );
+        assert!(Toolchain::parse("[toolchain]\nchannel = stable\n").is_err());
+    }
+
+    #[test]
+    fn find() {
+        let root = tempfile::tempdir().unwrap();
+        let pkg = root.path().join("pkg");
+        std::fs::create_dir(&pkg).unwrap();
+        let manifest = pkg.join("Cargo.toml");
+        assert_eq!(super::find(&manifest), None);
+
+        std::fs::write(root.path().join("rust-toolchain.toml"), "").unwrap();
+        assert_eq!(
+            super::find(&manifest),
+            Some(root.path().join("rust-toolchain.toml"))
+        );
+        std::fs::write(root.path().join("rust-toolchain"), "").unwrap();
+        assert_eq!(
+            super::find(&manifest),
+            Some(root.path().join("rust-toolchain"))
+        );
+    }
+}
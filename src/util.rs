@@ -4,11 +4,20 @@ use std::fmt;
 use std::fmt::Write;
 
 #[cfg(feature = "git2")]
-pub use crate::git::{get_repo_description, get_repo_head};
+pub use crate::git::{
+    get_repo_description, get_repo_description_with_options, get_repo_head,
+    get_repo_head_with_options, RepoOptions,
+};
 
 #[cfg(feature = "chrono")]
 pub use crate::krono::strptime;
 
+#[cfg(feature = "time")]
+pub use crate::time_crate::parse_rfc2822;
+
+#[cfg(feature = "jiff")]
+pub use crate::jiff_crate::strptime_jiff;
+
 /// Parses version-strings with `semver::Version::parse()`.
 ///
 /// This function is only available if `built` was compiled with the
@@ -45,6 +54,91 @@ where
     name_and_versions.into_iter().map(parse_version)
 }
 
+/// Looks up the version of dependency `name`, as serialized by `built`.
+///
+/// This function is only available if `built` was compiled with the
+/// `semver` feature.
+///
+/// If the dependency occurs more than once (e.g. in `DEPENDENCIES`), the
+/// highest version is returned.
+///
+/// ```
+/// pub mod build_info {
+///     pub static DIRECT_DEPENDENCIES: [(&'static str, &'static str); 2] = [("built", "0.7.5"), ("serde", "1.0.188")];
+/// }
+///
+/// assert_eq!(built::util::dependency_version(&build_info::DIRECT_DEPENDENCIES, "serde"),
+///            Some(semver::Version::new(1, 0, 188)));
+/// assert_eq!(built::util::dependency_version(&build_info::DIRECT_DEPENDENCIES, "toml"), None);
+/// ```
+///
+/// # Panics
+/// Same as `parse_versions()`.
+#[cfg(feature = "semver")]
+pub fn dependency_version<'a, T>(name_and_versions: T, name: &str) -> Option<semver::Version>
+where
+    T: IntoIterator<Item = &'a (&'a str, &'a str)>,
+{
+    parse_versions(name_and_versions)
+        .filter_map(|(n, ver)| (n == name).then_some(ver))
+        .max()
+}
+
+/// Checks if dependency `name` is present in a version matching `req`.
+///
+/// This function is only available if `built` was compiled with the
+/// `semver` feature.
+///
+/// ```
+/// pub mod build_info {
+///     pub static DIRECT_DEPENDENCIES: [(&'static str, &'static str); 1] = [("serde", "1.0.188")];
+/// }
+///
+/// let req = semver::VersionReq::parse("^1.0.100").unwrap();
+/// assert!(built::util::dependency_matches(&build_info::DIRECT_DEPENDENCIES, "serde", &req));
+/// ```
+///
+/// # Panics
+/// Same as `parse_versions()`.
+#[cfg(feature = "semver")]
+pub fn dependency_matches<'a, T>(name_and_versions: T, name: &str, req: &semver::VersionReq) -> bool
+where
+    T: IntoIterator<Item = &'a (&'a str, &'a str)>,
+{
+    parse_versions(name_and_versions).any(|(n, ver)| n == name && req.matches(&ver))
+}
+
+/// Constructs a `semver::Version` from the tuple serialized by `built` as `PKG_SEMVER`.
+///
+/// This function is only available if `built` was compiled with the
+/// `semver` feature.
+///
+/// ```
+/// pub mod build_info {
+///     pub static PKG_SEMVER: (u64, u64, u64, &str, &str) = (1, 2, 3, "rc1", "");
+/// }
+///
+/// let version = built::util::semver_version(&build_info::PKG_SEMVER);
+/// assert_eq!(version, semver::Version::parse("1.2.3-rc1").unwrap());
+/// ```
+///
+/// # Panics
+/// If the pre-release or build-metadata can't be parsed. This should never
+/// happen with values provided by Cargo and `built`.
+#[cfg(feature = "semver")]
+#[must_use]
+pub fn semver_version(
+    &(major, minor, patch, pre, build): &(u64, u64, u64, &str, &str),
+) -> semver::Version {
+    semver::Version {
+        major,
+        minor,
+        patch,
+        pre: semver::Prerelease::new(pre).unwrap(),
+        build: semver::BuildMetadata::new(build).unwrap(),
+    }
+}
+
 /// Detect execution on various Continuous Integration platforms.
 ///
 /// CI-platforms are detected by the presence of known environment variables.
@@ -54,6 +148,9 @@ where
 ///
 /// Since some platforms have fairly generic environment variables to begin with
 /// (e.g. `TASK_ID`), this function may have false positives.
+///
+/// Use `CIPlatform::detect_from_iter()` to detect the platform from variables other
+/// than those of the current process.
 #[must_use]
 pub fn detect_ci() -> Option<super::CIPlatform> {
     crate::environment::EnvironmentMap::new().detect_ci()
@@ -79,10 +176,65 @@ where
     }
 }
 
-#[cfg(feature = "cargo-lock")]
+/// Displays a documentation-string as a Rust string-literal. A raw string-literal is
+/// used, with as many `#` as needed to delimit it; if the string contains control
+/// characters like newlines, an escaped string-literal is used instead.
+pub(crate) struct DocStr<'a>(pub &'a str);
+
+impl fmt::Display for DocStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.chars().any(char::is_control) {
+            return write!(f, "\"{}\"", self.0.escape_default());
+        }
+        let hashes = self
+            .0
+            .split('"')
+            .skip(1)
+            .map(|s| s.bytes().take_while(|b| *b == b'#').count() + 1)
+            .max()
+            .unwrap_or(1);
+        let hashes = "#".repeat(hashes);
+        write!(f, "r{hashes}\"{}\"{hashes}", self.0)
+    }
+}
+
+/// Displays a string as a quoted JSON-string.
+pub(crate) struct JsonStr<'a>(pub &'a str);
+
+impl fmt::Display for JsonStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", u32::from(c))?,
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+#[cfg(any(
+    feature = "cargo-lock",
+    feature = "cargo-metadata",
+    feature = "git2",
+    feature = "git-cli",
+    feature = "git-minimal"
+))]
 pub(crate) struct TupleArrayDisplay<'a, T>(pub &'a [(T, T)]);
 
-#[cfg(feature = "cargo-lock")]
+#[cfg(any(
+    feature = "cargo-lock",
+    feature = "cargo-metadata",
+    feature = "git2",
+    feature = "git-cli",
+    feature = "git-minimal"
+))]
 impl<T> fmt::Display for TupleArrayDisplay<'_, T>
 where
     T: AsRef<str>,
@@ -100,3 +252,52 @@ where
         )
     }
 }
+
+/// Whether `name` matches the glob-`pattern`, where `*` matches any number of
+/// characters and `?` matches exactly one character.
+pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
+    let (pattern, name) = (pattern.as_bytes(), name.as_bytes());
+    let (mut p, mut n) = (0, 0);
+    // The position of the last `*` in `pattern` and the position in `name` it matched up to
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, n));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn glob_match() {
+        use super::glob_match;
+
+        assert!(glob_match("windows*", "windows_x86_64_msvc"));
+        assert!(glob_match("windows*", "windows"));
+        assert!(!glob_match("windows*", "winapi"));
+        assert!(glob_match("*-sys", "libgit2-sys"));
+        assert!(glob_match("lib?", "libc"));
+        assert!(!glob_match("lib?", "libgit2-sys"));
+        assert!(glob_match("*a*b*", "xaxxbxab"));
+        assert!(!glob_match("*a*b", "xaxxbxa"));
+        assert!(glob_match("serde", "serde"));
+        assert!(!glob_match("serde", "serde_derive"));
+    }
+}
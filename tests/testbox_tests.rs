@@ -4,6 +4,7 @@ use std::io;
 use std::io::Write;
 use std::path;
 use std::process;
+use std::thread;
 
 struct Project {
     root: tempfile::TempDir,
@@ -88,9 +89,7 @@ fn main() {
     }
 
     fn run(root: &std::path::Path, extra_args: &[&str]) {
-        let cargo_result = process::Command::new("cargo")
-            .current_dir(root)
-            .arg("run")
+        let cargo_result = cargo(root, "run")
             .args(extra_args)
             .output()
             .expect("cargo failed");
@@ -103,9 +102,7 @@ fn main() {
     }
 
     fn build(root: &std::path::Path, extra_args: &[&str]) {
-        let cargo_result = process::Command::new("cargo")
-            .current_dir(root)
-            .arg("build")
+        let cargo_result = cargo(root, "build")
             .args(extra_args)
             .output()
             .expect("cargo failed");
@@ -130,6 +127,33 @@ fn main() {
     }
 }
 
+/// The target-directory of the projects of the currently running test.
+///
+/// Each test builds its projects in a directory of its own below
+/// `CARGO_TARGET_TMPDIR`, which outlives the temporary projects, so dependencies like
+/// `built` and `git2` are only compiled when a test runs for the first time. As the
+/// projects' `OUT_DIR`s are kept as well, build-scripts must not rely on it being empty.
+fn target_dir() -> path::PathBuf {
+    // libtest names each thread after the test it runs; tests which run on the main
+    // thread run one after another, so they can share a directory
+    let name = thread::current()
+        .name()
+        .unwrap_or("main")
+        .replace("::", "-");
+    path::Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("testbox")
+        .join(name)
+}
+
+/// A `cargo`-invocation of `subcommand` on the project in `root`.
+fn cargo(root: &path::Path, subcommand: &str) -> process::Command {
+    let mut cmd = process::Command::new("cargo");
+    cmd.current_dir(root)
+        .env("CARGO_TARGET_DIR", target_dir())
+        .arg(subcommand);
+    cmd
+}
+
 /// Tries to find built's Cargo.toml, panics if it ends up in /
 fn get_built_root() -> path::PathBuf {
     env::current_exe()
@@ -236,6 +260,13 @@ description = "xobtset"
 homepage = "localhost"
 repository = "https://dev.example.com/sources/testbox/"
 license = "MIT"
+rust-version = "1.70"
+documentation = "https://docs.example.com/testbox/"
+readme = "README.md"
+keywords = ["build", "testbox"]
+categories = [
+    "development-tools::build-utils",
+]
 
 [dependencies]
 built = {{ path = "{built_root}", default_features=false }}
@@ -276,11 +307,19 @@ fn main() {
     assert_eq!(built_info::PKG_VERSION_PATCH, "3");
     assert_eq!(built_info::PKG_VERSION_PRE, "rc1");
     assert_eq!(built_info::PKG_AUTHORS, "Joe:Bob");
+    assert_eq!(built_info::PKG_AUTHORS_LIST, ["Joe", "Bob"]);
     assert_eq!(built_info::PKG_NAME, "minimal_testbox");
     assert_eq!(built_info::PKG_DESCRIPTION, "xobtset");
     assert_eq!(built_info::PKG_HOMEPAGE, "localhost");
     assert_eq!(built_info::PKG_LICENSE, "MIT");
     assert_eq!(built_info::PKG_REPOSITORY, "https://dev.example.com/sources/testbox/");
+    assert_eq!(built_info::PKG_RUST_VERSION, "1.70");
+    assert_eq!(built_info::PKG_README, "README.md");
+    assert_eq!(built_info::PKG_LICENSE_FILE, "");
+    // Not available through the environment, and the `manifest`-feature is disabled
+    assert_eq!(built_info::PKG_DOCUMENTATION, "");
+    assert!(built_info::PKG_KEYWORDS.is_empty());
+    assert!(built_info::PKG_CATEGORIES.is_empty());
     assert!(built_info::NUM_JOBS > 0);
     assert!(built_info::OPT_LEVEL == "0");
     assert!(built_info::DEBUG);
@@ -295,6 +334,7 @@ fn main() {
                "default, megaawesome, superawesome");
     assert_ne!(built_info::RUSTC_VERSION, "");
     assert_ne!(built_info::RUSTDOC_VERSION, "");
+    assert!(built_info::CARGO_VERSION.starts_with("cargo "));
     assert_ne!(built_info::HOST, "");
     assert_ne!(built_info::TARGET, "");
     assert_ne!(built_info::RUSTC, "");
@@ -306,6 +346,8 @@ fn main() {
     assert_ne!(built_info::CFG_POINTER_WIDTH, "");
     // For CFG_ENV, empty string is a possible value.
     let _: &'static str = built_info::CFG_ENV;
+    assert_eq!(built_info::LINKER, None);
+    assert_eq!(built_info::CPU_DISPATCH_LEVEL.is_some(), built_info::CFG_TARGET_ARCH == "x86_64");
     println!("builttestsuccess");
 }"#,
     );
@@ -318,7 +360,16 @@ fn simple_workspace() {
     let mut p = Project::new();
     let built_root = get_built_root();
 
-    p.add_file("Cargo.toml", "[workspace]\nmembers = ['foobar']");
+    p.add_file(
+        "Cargo.toml",
+        r#"[workspace]
+members = ["foobar"]
+
+[workspace.package]
+edition = "2021"
+documentation = "https://docs.example.com/foobar/"
+"#,
+    );
     p.add_file(
         "foobar/Cargo.toml",
         format!(
@@ -326,9 +377,11 @@ fn simple_workspace() {
 name = "foobar"
 version = "5.6.7"
 build = "build.rs"
+edition.workspace = true
+documentation.workspace = true
 
 [build-dependencies]
-built = {{ path = "{}" }}"#,
+built = {{ path = "{}", features = ["manifest"] }}"#,
             built_root.display().to_string().escape_default()
         ),
     );
@@ -344,6 +397,10 @@ mod built_info {
 }
 fn main() {
     assert_eq!(built_info::PKG_VERSION, "5.6.7");
+    assert!(built_info::WORKSPACE_ROOT.is_some());
+    assert_eq!(built_info::WORKSPACE_MEMBERS, [("foobar", "5.6.7")]);
+    assert_eq!(built_info::PKG_EDITION, Some("2021"));
+    assert_eq!(built_info::PKG_DOCUMENTATION, "https://docs.example.com/foobar/");
     println!("builttestsuccess");
 }
 "#,
@@ -370,17 +427,29 @@ description = "xobtset"
 homepage = "localhost"
 repository = "https://dev.example.com/sources/testbox/"
 license = "MIT"
+"keywords" = ["build", "testbox"] # [not, a, keyword]
+categories = [
+    "development-tools::build-utils",
+]
 
 [dependencies]
-built = {{ path = "{built_root}", features=["cargo-lock", "dependency-tree", "git2", "chrono", "semver"] }}
+built = {{ path = "{built_root}", features=["cargo-lock", "cargo-metadata", "dependency-tree", "git2", "chrono", "manifest", "semver"] }}
+serde = {{ version = "1", features = ["derive"] }}
 
 [build-dependencies]
-built = {{ path = "{built_root}", features=["cargo-lock", "dependency-tree", "git2", "chrono", "semver"] }}
+built = {{ path = "{built_root}", features=["cargo-lock", "cargo-metadata", "dependency-tree", "git2", "chrono", "manifest", "semver"] }}
 
 [features]
 default = ["SuperAwesome", "MegaAwesome"]
 SuperAwesome = []
-MegaAwesome = []"#,
+MegaAwesome = []
+
+[profile.dev]
+codegen-units = 4
+lto = "off"
+
+[profile.dev.package.serde]
+opt-level = 1"#,
             built_root = built_root.display().to_string().escape_default()
         ),
     );
@@ -389,14 +458,81 @@ MegaAwesome = []"#,
         "build.rs",
         r#"
 use std::env;
+use std::fs;
 use std::path;
 extern crate built;
 
 fn main() {
     // Teleport to a CI-platform, should get detected
     env::set_var("CONTINUOUS_INTEGRATION", "1");
-
-    built::write_built_file().unwrap();
+    env::set_var("BUILD_NUMBER", "42");
+    // Leak a feature which is not declared by the manifest, should get rejected
+    env::set_var("CARGO_FEATURE_INJECTED", "1");
+
+    let mut options = built::Options::default();
+    options
+        .set_sbom_output("sbom.cdx.json", built::SbomFormat::CycloneDx)
+        .set_embed_sbom(true)
+        .set_build_info_output("build_info.rs")
+        .set_json_output("built.json")
+        .set_json_schema_output("built.schema.json")
+        .set_c_header_output("built.h")
+        .set_overview_output("built.md")
+        .set_override_replay_output("built.env")
+        .set_override_report_output("built_overrides_report.json")
+        .add_output(built::Format::Toml, "built.toml")
+        .add_output(built::Format::Yaml, "built.yaml")
+        .add_output(built::Format::Env, "built.vars")
+        .set_drift_warnings(true)
+        .set_build_info_struct(true)
+        .set_serde(true)
+        .set_accessors(true)
+        .set_toolchain_file(true)
+        .set_build_host_info(true)
+        .set_validate_features(true)
+        .set_collection_stats(true)
+        .add_baseline("PKG_NAME", ["testbox"])
+        .add_baseline("CI_PLATFORM", ["*"])
+        .set_baseline_deviation(built::BaselineDeviation::Error)
+        .set_emit_all(true)
+        .set_subprocess_timeout(std::time::Duration::from_secs(60))
+        .set_subprocess_retries(1)
+        .set_captured_env_vars(["CARGO_PKG_NAME", "BUILT_TESTBOX_UNSET"]);
+    if cfg!(unix) {
+        // A "signature" that is just a copy of the file
+        options.set_signer(Some(built::Signer::Command("cp".into(), Vec::new())));
+    }
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let dst = path::Path::new(&env::var("OUT_DIR").unwrap()).join("built.rs");
+
+    // `OUT_DIR` is kept by the shared target-directory, if the test ran before
+    let _ = fs::remove_file(dst.with_file_name("sbom.cdx.json"));
+    let check = built::self_check(&options, Some(manifest_dir.as_ref())).unwrap();
+    assert_eq!(check.get("PKG_NAME"), Some("\"testbox\""));
+    assert!(check.get("DEPENDENCIES").is_some());
+    assert!(!dst.with_file_name("sbom.cdx.json").exists());
+
+    built::write_built_file_with_options(&options, Some(manifest_dir.as_ref()), &dst).unwrap();
+
+    // The schema is the same, regardless of the options
+    let mut defaults = built::Options::default();
+    defaults.set_json_schema_output("default.schema.json");
+    built::write_built_file_with_options(
+        &defaults,
+        Some(manifest_dir.as_ref()),
+        &dst.with_file_name("default.rs"),
+    )
+    .unwrap();
+    assert_eq!(
+        fs::read(dst.with_file_name("built.schema.json")).unwrap(),
+        fs::read(dst.with_file_name("default.schema.json")).unwrap()
+    );
+    if cfg!(unix) {
+        for name in ["built.rs", "built.json", "built.toml", "built.yaml", "sbom.cdx.json"] {
+            let signature = dst.with_file_name(format!("{name}.sig"));
+            assert_eq!(fs::read(dst.with_file_name(name)).unwrap(), fs::read(signature).unwrap());
+        }
+    }
 }"#,
     );
 
@@ -405,23 +541,139 @@ fn main() {
         r#"
 //! The testbox.
 
+#[macro_use]
 mod built_info {
+    #![doc = include_str!(concat!(env!("OUT_DIR"), "/built.md"))]
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
 }
 
+#[allow(dead_code)]
+mod build_info {
+    include!(concat!(env!("OUT_DIR"), "/build_info.rs"));
+}
+
+const _: () = assert!(build_info::PKG_VERSION_MAJOR.len() == 1);
+
 fn main() {
+    assert_eq!(build_info::PKG_VERSION, built_info::PKG_VERSION);
+    assert_eq!(build_info::FEATURES, built_info::FEATURES);
+    assert!(built_info::DEPENDENCIES_CHECKSUMS.iter().all(|(name, _, checksum)| *name != "testbox" && checksum.len() == 64));
+    assert!(built_info::DEPENDENCIES.iter().all(|(name, _)| *name != "testbox"));
+    assert_eq!(built_info::BUILT_BANNER, *b"testbox 1.2.3-rc1\0");
+    assert_eq!(built_info::BUILT_FEATURES, ["cargo-lock", "cargo-metadata", "chrono", "dependency-tree", "git2", "manifest", "semver"]);
+    assert!(built_info::BUILT_COLLECTORS.contains(&"sbom=enabled"));
+    assert!(built_info::BUILT_COLLECTORS.contains(&"toolchain=enabled"));
+    assert!(built_info::BUILT_COLLECTORS.contains(&"build-host=enabled"));
+    assert!(built_info::BUILT_COLLECTORS.contains(&"workspace=enabled"));
+    assert!(built_info::BUILT_COLLECTORS.contains(&"git-checkout=disabled"));
+    assert!(built_info::BUILT_COLLECTORS.contains(&if cfg!(unix) { "signature=enabled" } else { "signature=disabled" }));
+    assert_eq!((built_info::PKG_VERSION_MAJOR_NUMERIC, built_info::PKG_VERSION_MINOR_NUMERIC, built_info::PKG_VERSION_PATCH_NUMERIC), (Some(1), Some(2), Some(3)));
+    assert_eq!(built_info::parse_u32("4294967295"), Some(u32::MAX));
+    assert_eq!(built_info::parse_u32("4294967296"), None);
+    assert_eq!(built_info::parse_u32("1a"), None);
+    assert_eq!(built_info::GIT_COMMITS_SINCE_TAG, None);
+    assert_eq!(built_info::describe_distance("v1.2.0-5-g0f3a1b2"), Some(5));
+    assert_eq!(built_info::describe_distance("v1.2.0-rc1-12-g0f3a1b2"), Some(12));
+    assert_eq!(built_info::describe_distance("v1.2.0"), None);
+    assert_eq!(built_info::describe_distance("0f3a1b2"), None);
+    assert!(built_info::BUILD_TAGS.contains(&("build.panic", "unwind")));
+    assert_eq!(built::check_compatibility(&built_info::BUILD_TAGS, &built_info::BUILD_TAGS), []);
+    let stats = |collector| built_info::BUILT_COLLECTION_STATS.iter().find(|(c, _)| *c == collector).unwrap().1;
+    assert!(stats("environment").starts_with("ok "));
+    assert!(stats("git").starts_with("failed "));
+    assert_eq!(stats("audit"), "skipped");
+    assert!(built_info::CFG_ALL.contains(&("target_os", built_info::CFG_OS)));
+    assert_eq!(built_info::CFG_TARGET_FEATURES_STR, built_info::CFG_TARGET_FEATURES.join(", "));
+    assert_eq!(
+        built_info::CAPTURED_ENV,
+        [("CARGO_PKG_NAME", Some("testbox")), ("BUILT_TESTBOX_UNSET", None)]
+    );
+    assert!(built_info::RUNTIME_DEPENDENCIES.iter().any(|(name, _)| *name == "built"));
+    assert!(built_info::BUILD_DEPENDENCIES.iter().all(|(name, _)| *name != "built"));
+    assert_eq!(built_info::DEV_DEPENDENCIES, []);
+    assert!(built_info::DUPLICATE_DEPENDENCIES.iter().all(|(_, versions)| versions.len() > 1));
+    assert!(built_info::DEPENDENCY_SOURCES.contains(&("testbox", "1.2.3-rc1", "path")));
+    assert_eq!(built_info::REGISTRIES_USED, ["crates.io"]);
+    let json = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/built.json")).unwrap();
+    assert!(json.contains("\n  \"PKG_VERSION\": \"1.2.3-rc1\",\n"));
+    assert!(json.contains("\n  \"BUILT_SCHEMA_VERSION\": 1,\n"));
+    let schema = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/built.schema.json")).unwrap();
+    assert!(schema.contains("\n    \"PKG_VERSION\": {\"type\": \"string\", "));
+    for key in json.lines().filter_map(|line| Some(line.strip_prefix("  \"")?.split_once('"')?.0)) {
+        assert!(schema.contains(&format!("\n    \"{key}\": ")), "{key}");
+    }
+    let header = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/built.h")).unwrap();
+    assert!(header.contains("\n#define BUILT_PKG_VERSION \"1.2.3-rc1\"\n"));
+    let overview = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/built.md")).unwrap();
+    assert!(overview.contains("\n| `PKG_VERSION` | `&str` | The full version. | environment |  |\n"));
+    assert!(overview.contains("| `GIT_DIRTY` | `Option<bool>` |"));
+    assert!(!header.contains("BUILT_GIT_COMMIT_HASH"));
+    let replay = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/built.env")).unwrap();
+    assert!(replay.contains("\nexport BUILT_OVERRIDE_testbox_PKG_VERSION='1.2.3-rc1'\n"));
+    let report = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/built_overrides_report.json")).unwrap();
+    assert!(report.contains("\n  \"overridden\": [],\n  \"unused\": [],\n"));
+    let toml = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/built.toml")).unwrap();
+    assert!(toml.contains("\nPKG_VERSION = \"1.2.3-rc1\"\n"));
+    let yaml = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/built.yaml")).unwrap();
+    assert!(yaml.contains("\nGIT_COMMIT_HASH: null\n"));
+    assert!(yaml.contains("\nBUILT_BANNER:"));
+    let vars = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/built.vars")).unwrap();
+    assert!(vars.contains("\nPKG_VERSION='1.2.3-rc1'\n"));
+    assert!(vars.contains("\nGIT_COMMIT_HASH=''\n"));
+    assert!(built_info::SBOM_JSON.contains("\"bom-ref\": \"testbox@1.2.3-rc1\""));
+    assert_eq!(std::fs::read_to_string(concat!(env!("OUT_DIR"), "/sbom.cdx.json")).unwrap(), built_info::SBOM_JSON);
     assert_eq!(built_info::GIT_VERSION, None);
     assert_eq!(built_info::GIT_DIRTY, None);
     assert_eq!(built_info::GIT_COMMIT_HASH, None);
     assert_eq!(built_info::GIT_COMMIT_HASH_SHORT, None);
     assert_eq!(built_info::GIT_HEAD_REF, None);
+    assert_eq!(built_info::GIT_SPARSE_CHECKOUT, None);
+    assert_eq!(built_info::GIT_LFS_PRESENT, None);
+    assert_eq!(built_info::GIT_REMOTE_URL, None);
+    assert_eq!(built_info::GIT_OPERATION_IN_PROGRESS, None);
+    assert_eq!(built_info::GIT_SHALLOW, None);
+    assert_eq!(built_info::BUILD_INFO.pkg_version, built_info::PKG_VERSION);
+    assert_eq!(built_info::BUILD_INFO.dependencies.len(), built_info::DEPENDENCIES_COUNT);
+    fn assert_serialize<T: serde::Serialize>(_: &T) {}
+    assert_serialize(&built_info::BUILD_INFO);
+    assert!(built_info::ALL.contains(&("PKG_NAME", "testbox")));
+    assert!(built_info::BUILD_TAGS.contains(&("pkg.version", "1.2.3-rc1")));
+    assert_eq!(built_info::FULL_VERSION, "1.2.3-rc1 debug");
+    assert_eq!(built_info::TOOLCHAIN_CHANNEL, None);
+    assert!(built_info::BUILD_HOST_OS.is_some());
+    assert_eq!(concat!("v", full_version!()), "v1.2.3-rc1 debug");
+    assert!(built_info::BUILD_TAGS.iter().all(|(key, _)| !key.starts_with("git.")));
+    assert_eq!(built_info::pkg_name(), Some("testbox"));
+    assert_eq!(built_info::git_version(), None);
+    const _: Option<&str> = built_info::rustdoc_version();
+    assert!(built_info::ALL.contains(&("GIT_DIRTY", "None")));
+    assert_eq!(built_info::BUILT_FROM_FORK, None);
     assert!(built_info::CI_PLATFORM.is_some());
+    assert!(built_info::CI_BUILD_NUMBER.is_some());
+    assert!(built_info::CI_PLATFORM_KIND.is_some());
+    if built_info::CI_PLATFORM == Some("Generic CI") {
+        assert_eq!(built_info::CI_PLATFORM_KIND, Some(built_info::CiPlatform::Generic));
+    }
     assert_eq!(built_info::PKG_VERSION, "1.2.3-rc1");
     assert_eq!(built_info::PKG_VERSION_MAJOR, "1");
     assert_eq!(built_info::PKG_VERSION_MINOR, "2");
     assert_eq!(built_info::PKG_VERSION_PATCH, "3");
     assert_eq!(built_info::PKG_VERSION_PRE, "rc1");
+    assert_eq!(built_info::PKG_SEMVER, (1, 2, 3, "rc1", ""));
+    assert_eq!(built::util::semver_version(&built_info::PKG_SEMVER).to_string(), built_info::PKG_VERSION);
     assert_eq!(built_info::PKG_AUTHORS, "Joe:Bob:Harry:Potter");
+    assert_eq!(built_info::PKG_AUTHORS_LIST, ["Joe", "Bob", "Harry:Potter"]);
+    assert_eq!(built_info::PKG_KEYWORDS, ["build", "testbox"]);
+    assert_eq!(built_info::PKG_KEYWORDS_STR, "build, testbox");
+    assert_eq!(built_info::PKG_CATEGORIES, ["development-tools::build-utils"]);
+    assert_eq!(built_info::PKG_CATEGORIES_STR, "development-tools::build-utils");
+    assert_eq!(built_info::BIN_NAME, Some("testbox"));
+    assert_eq!(built_info::CRATE_NAME, "testbox");
+    assert_eq!(built_info::PROFILE_NAME, "dev");
+    assert_eq!(built_info::LTO, Some("off"));
+    assert_eq!(built_info::CODEGEN_UNITS, Some(4));
+    assert_eq!(built_info::PANIC_STRATEGY, Some("unwind"));
+    assert_eq!(built_info::STRIP, None);
     assert_eq!(built_info::PKG_NAME, "testbox");
     assert_eq!(built_info::PKG_DESCRIPTION, "xobtset");
     assert_eq!(built_info::PKG_HOMEPAGE, "localhost");
@@ -429,7 +681,10 @@ fn main() {
     assert_eq!(built_info::PKG_REPOSITORY, "https://dev.example.com/sources/testbox/");
     assert!(built_info::NUM_JOBS > 0);
     assert!(built_info::OPT_LEVEL == "0");
+    assert_eq!(built_info::OPT_LEVEL_NUMERIC, Some(0));
+    assert_eq!(built_info::OPT_LEVEL_KIND, built_info::OptLevel::O0);
     assert!(built_info::DEBUG);
+    assert_eq!(built_info::DEBUGINFO, "2");
     assert_eq!(built_info::PROFILE, "debug");
     assert_eq!(built_info::FEATURES,
                ["DEFAULT", "MEGAAWESOME", "SUPERAWESOME"]);
@@ -439,6 +694,7 @@ fn main() {
                ["default", "megaawesome", "superawesome"]);
     assert_eq!(built_info::FEATURES_LOWERCASE_STR,
                "default, megaawesome, superawesome");
+    assert_eq!(built_info::FEATURES_FOREIGN, ["INJECTED"]);
     assert_ne!(built_info::RUSTC_VERSION, "");
     assert_ne!(built_info::RUSTDOC_VERSION, "");
     assert_ne!(built_info::DEPENDENCIES_STR, "");
@@ -459,10 +715,25 @@ fn main() {
     assert!(built::util::parse_versions(built_info::DEPENDENCIES.iter())
         .any(|(name, ver)| name == "toml" && ver >= built::semver::Version::parse("0.1.0").unwrap()));
 
-    assert_eq!(built_info::DIRECT_DEPENDENCIES.len(), 1);
+    assert_eq!(built_info::DIRECT_DEPENDENCIES.len(), 2);
+    assert_eq!(built_info::DIRECT_DEPENDENCIES_COUNT, 2);
+    assert_eq!(built_info::DEPENDENCIES_COUNT, built_info::DEPENDENCIES.len());
+    assert_eq!(built_info::INDIRECT_DEPENDENCIES_COUNT, built_info::INDIRECT_DEPENDENCIES.len());
+    assert_eq!(built_info::DEPENDENCIES_PRUNED_COUNT, 0);
+    assert_eq!(built_info::INDIRECT_DEPENDENCIES_PRUNED_COUNT, 0);
     assert_eq!(built_info::DIRECT_DEPENDENCIES[0].0, "built");
+    assert!(built::util::dependency_version(&built_info::DIRECT_DEPENDENCIES, "built").is_some());
+    assert!(built_info::DEPENDENCY_LICENSES.iter().any(|&(name, _, license)| name == "built" && license == "MIT"));
+    assert!(built_info::LICENSE_SUMMARY_STR.contains("MIT"));
+    assert_eq!(built_info::PKG_EDITION, Some("2015"));
+    assert_eq!(built_info::PKG_DECLARED_FEATURES, ["MegaAwesome", "SuperAwesome", "default"]);
+    assert_eq!(built_info::BIN_TARGETS, ["testbox"]);
+    assert_eq!(built_info::PROFILE_OVERRIDES, [("dev", "serde", "opt-level", "1")]);
+    assert_eq!(built_info::WORKSPACE_ROOT, None);
+    assert_eq!(built_info::WORKSPACE_MEMBERS, []);
 
     assert!((built::chrono::offset::Utc::now() - built::util::strptime(built_info::BUILT_TIME_UTC)).num_days() <= 1);
+    assert_eq!(built::util::strptime(built_info::BUILT_TIME_UTC).timestamp(), built_info::BUILT_TIME_UNIX as i64);
     println!("builttestsuccess");
 }"#,
     );
@@ -497,9 +768,16 @@ built = {{ path = "{built_root}", features=["chrono"] }}"#,
     p.add_file(
         "build.rs",
         r#"
-use std::env;
+use std::{env, path};
 
 fn main() {
+    // An invalid timestamp fails the build, if requested
+    env::set_var("SOURCE_DATE_EPOCH", "yesterday");
+    let mut options = built::Options::default();
+    options.set_source_date_epoch(built::SourceDateEpochPolicy::Strict);
+    let dst = path::Path::new(&env::var("OUT_DIR").unwrap()).join("built.rs");
+    assert!(built::write_built_file_with_options(&options, &dst).is_err());
+
     // Set timestamp
     env::set_var("SOURCE_DATE_EPOCH", "1716639359");
 
@@ -517,6 +795,241 @@ mod built_info {
 fn main() {
     assert_eq!(built::util::strptime(built_info::BUILT_TIME_UTC).to_rfc2822(),
               "Sat, 25 May 2024 12:15:59 +0000");
+    assert_eq!(built_info::BUILT_TIME_UNIX, 1716639359);
+    assert_eq!(built_info::BUILT_TIME_RFC3339, "2024-05-25T12:15:59Z");
+    println!("builttestsuccess");
+}"#,
+    );
+    p.create_and_run(&[]);
+}
+
+#[test]
+fn source_date_epoch_time() {
+    let mut p = Project::new();
+    let built_root = get_built_root();
+
+    p.add_file(
+        "Cargo.toml",
+        format!(
+            r#"
+[package]
+name = "testbox"
+version = "1.2.3-rc1"
+build = "build.rs"
+
+[dependencies]
+built = {{ path = "{built_root}", features=["time"] }}
+
+[build-dependencies]
+built = {{ path = "{built_root}", features=["time"] }}"#,
+            built_root = built_root.display().to_string().escape_default()
+        ),
+    );
+
+    p.add_file(
+        "build.rs",
+        r#"
+use std::env;
+
+fn main() {
+    // Set timestamp
+    env::set_var("SOURCE_DATE_EPOCH", "1716639359");
+
+    built::write_built_file().unwrap();
+}"#,
+    );
+
+    p.add_file(
+        "src/main.rs",
+        r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert_eq!(built_info::BUILT_TIME_UTC, "Sat, 25 May 2024 12:15:59 +0000");
+    assert_eq!(built::util::parse_rfc2822(built_info::BUILT_TIME_UTC).unix_timestamp(),
+               1716639359);
+    assert_eq!(built_info::BUILT_TIME_UNIX, 1716639359);
+    assert_eq!(built_info::BUILT_TIME_RFC3339, "2024-05-25T12:15:59Z");
+    assert_eq!(built_info::BUILT_FEATURES, ["time"]);
+    println!("builttestsuccess");
+}"#,
+    );
+    p.create_and_run(&[]);
+}
+
+#[test]
+fn source_date_epoch_jiff() {
+    let mut p = Project::new();
+    let built_root = get_built_root();
+
+    p.add_file(
+        "Cargo.toml",
+        format!(
+            r#"
+[package]
+name = "testbox"
+version = "1.2.3-rc1"
+build = "build.rs"
+
+[dependencies]
+built = {{ path = "{built_root}", features=["jiff"] }}
+
+[build-dependencies]
+built = {{ path = "{built_root}", features=["jiff"] }}"#,
+            built_root = built_root.display().to_string().escape_default()
+        ),
+    );
+
+    p.add_file(
+        "build.rs",
+        r#"
+use std::env;
+
+fn main() {
+    // Set timestamp
+    env::set_var("SOURCE_DATE_EPOCH", "1716639359");
+
+    built::write_built_file().unwrap();
+}"#,
+    );
+
+    p.add_file(
+        "src/main.rs",
+        r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert_eq!(built_info::BUILT_TIME_UTC, "Sat, 25 May 2024 12:15:59 +0000");
+    let ts: built::jiff::Timestamp = built::util::strptime_jiff(built_info::BUILT_TIME_UTC);
+    assert_eq!(ts.as_second(), 1716639359);
+    assert_eq!(built_info::BUILT_TIME_UNIX, 1716639359);
+    assert_eq!(built_info::BUILT_TIME_RFC3339, "2024-05-25T12:15:59Z");
+    assert_eq!(built_info::BUILT_FEATURES, ["jiff"]);
+    println!("builttestsuccess");
+}"#,
+    );
+    p.create_and_run(&[]);
+}
+
+#[test]
+fn deterministic() {
+    let mut p = Project::new();
+    let built_root = get_built_root();
+
+    p.add_file(
+        "Cargo.toml",
+        format!(
+            r#"
+[package]
+name = "testbox"
+version = "1.2.3-rc1"
+build = "build.rs"
+
+[build-dependencies]
+built = {{ path = "{built_root}", features=["chrono"] }}"#,
+            built_root = built_root.display().to_string().escape_default()
+        ),
+    );
+
+    p.add_file(
+        "build.rs",
+        r#"
+use std::{env, path};
+
+fn main() {
+    let mut options = built::Options::default();
+    options.set_deterministic(true).set_build_host_info(true);
+    let dst = path::Path::new(&env::var("OUT_DIR").unwrap()).join("built.rs");
+
+    // `SOURCE_DATE_EPOCH` is required
+    env::remove_var("SOURCE_DATE_EPOCH");
+    assert!(built::write_built_file_with_options(&options, &dst).is_err());
+
+    env::set_var("SOURCE_DATE_EPOCH", "1716639359");
+    built::write_built_file_with_options(&options, &dst).unwrap();
+}"#,
+    );
+
+    p.add_file(
+        "src/main.rs",
+        r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert_eq!(built_info::BUILT_TIME_UNIX, 1716639359);
+    assert_eq!(built_info::NUM_JOBS, 1);
+    assert_eq!(built_info::HOST, "unknown");
+    assert!(built_info::RUSTC.starts_with("rustc"));
+    assert!(built_info::RUSTDOC.starts_with("rustdoc"));
+    assert_eq!(built_info::BUILD_HOST, None);
+    assert_eq!(built_info::BUILD_USER, None);
+    assert!(built_info::BUILD_TAGS.contains(&("build.host", "unknown")));
+    println!("builttestsuccess");
+}"#,
+    );
+    p.create_and_run(&[]);
+}
+
+#[test]
+fn packed_dependencies() {
+    let mut p = Project::new();
+    let built_root = get_built_root();
+
+    p.add_file(
+        "Cargo.toml",
+        format!(
+            r#"
+[package]
+name = "testbox"
+version = "1.2.3-rc1"
+build = "build.rs"
+
+[dependencies]
+built = {{ path = "{built_root}" }}
+
+[build-dependencies]
+built = {{ path = "{built_root}", features=["dependency-tree"] }}"#,
+            built_root = built_root.display().to_string().escape_default()
+        ),
+    );
+
+    p.add_file(
+        "build.rs",
+        r#"
+use std::{env, path};
+
+fn main() {
+    let mut options = built::Options::default();
+    options.set_packed_dependencies(true);
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let dst = path::Path::new(&env::var("OUT_DIR").unwrap()).join("built.rs");
+    built::write_built_file_with_options(&options, Some(manifest_dir.as_ref()), &dst).unwrap();
+}"#,
+    );
+
+    p.add_file(
+        "src/main.rs",
+        r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert_eq!(built_info::dependencies().len(), built_info::DEPENDENCIES_COUNT);
+    assert_eq!(built_info::direct_dependencies().map(|(name, _)| name).collect::<Vec<_>>(),
+               ["built"]);
+    assert_eq!(built_info::indirect_dependencies().len(),
+               built_info::INDIRECT_DEPENDENCIES_COUNT);
+    let deps = built_info::dependencies()
+        .map(|(name, version)| format!("{name} {version}"))
+        .collect::<Vec<_>>();
+    assert_eq!(deps.join(", "), built_info::DEPENDENCIES_STR);
     println!("builttestsuccess");
 }"#,
     );
@@ -558,6 +1071,10 @@ mod built_info {
 
 fn main() {
     assert_eq!(built_info::GIT_DIRTY, Some(false));
+    assert_eq!(built_info::GIT_SPARSE_CHECKOUT, Some(false));
+    assert_eq!(built_info::GIT_LFS_PRESENT, Some(false));
+    assert_eq!(built_info::GIT_OPERATION_IN_PROGRESS, None);
+    assert_eq!(built_info::GIT_SHALLOW, Some(false));
     println!("builttestsuccess");
 }
 "#,
@@ -609,6 +1126,61 @@ fn main() {
     Project::run(root.as_ref(), &[]);
 }
 
+#[test]
+#[cfg(feature = "git2")]
+fn git_checkout() {
+    // `root` isn't a git-repo, but the vendored checkout within it is
+    let mut p = Project::new();
+    p.bootstrap()
+        .add_file(
+            "build.rs",
+            r#"
+extern crate built;
+use std::{env, path};
+
+fn main() {
+    let mut options = built::Options::default();
+    options.add_git_checkout("VENDORED_FOO_", "vendor/foo");
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let dst = path::Path::new(&env::var("OUT_DIR").unwrap()).join("built.rs");
+    built::write_built_file_with_options(&options, Some(manifest_dir.as_ref()), &dst).unwrap();
+}"#,
+        )
+        .add_file("vendor/foo/lib.rs", "")
+        .add_file(
+            "src/main.rs",
+            r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert_eq!(built_info::GIT_COMMIT_HASH, None);
+    assert_eq!(built_info::VENDORED_FOO_GIT_DIRTY, Some(false));
+    assert!(built_info::VENDORED_FOO_GIT_COMMIT_HASH.is_some());
+    println!("builttestsuccess");
+}
+"#,
+        );
+    let root = p.create().expect("Creating the project failed");
+
+    let repo = git2::Repository::init(root.path().join("vendor/foo")).unwrap();
+    let sig = git2::Signature::now("foo", "bar").unwrap();
+    let mut idx = repo.index().unwrap();
+    idx.add_path(path::Path::new("lib.rs")).unwrap();
+    idx.write().unwrap();
+    repo.commit(
+        Some("HEAD"),
+        &sig,
+        &sig,
+        "Vendored",
+        &repo.find_tree(idx.write_tree().unwrap()).unwrap(),
+        &[],
+    )
+    .unwrap();
+    Project::run(root.as_ref(), &[]);
+}
+
 #[test]
 #[cfg(feature = "git2")]
 fn empty_git() {
@@ -664,9 +1236,7 @@ fn main() {
     assert!(rustc_exe.contains('\\'));
 
     let root = p.create().expect("Creating the project failed");
-    let cargo_result = process::Command::new("cargo")
-        .current_dir(&root)
-        .arg("run")
+    let cargo_result = cargo(root.path(), "run")
         .env("RUSTC", &rustc_exe)
         .output()
         .expect("cargo failed");
@@ -677,3 +1247,52 @@ fn main() {
         );
     }
 }
+
+#[cfg(target_os = "windows")]
+#[test]
+fn verbatim_paths() {
+    // Checkouts on network-shares and long paths may be referred to with a verbatim-prefix
+    // (`\\?\`), which should neither end up in the generated constants nor confuse git.
+
+    let mut p = Project::new();
+    p.bootstrap().add_file(
+        "src/main.rs",
+        r#"
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+fn main() {
+    assert!(!built_info::RUSTC.starts_with(r"\\?\"));
+    assert!(built_info::RUSTC.ends_with("rustc.exe"));
+    println!("builttestsuccess");
+}
+"#,
+    );
+    #[cfg(feature = "git2")]
+    p.init_git();
+
+    let rustc_exe_buf = String::from_utf8(
+        process::Command::new("where")
+            .arg("rustc")
+            .output()
+            .expect("Unable to locate absolute path to rustc using `where`")
+            .stdout,
+    )
+    .unwrap();
+    let rustc_exe = format!(r"\\?\{}", rustc_exe_buf.split("\r\n").next().unwrap());
+
+    let tempdir = p.create().expect("Creating the project failed");
+    let root = fs::canonicalize(&tempdir).unwrap();
+    assert!(root.to_string_lossy().starts_with(r"\\?\"));
+    let cargo_result = cargo(&root, "run")
+        .env("RUSTC", &rustc_exe)
+        .output()
+        .expect("cargo failed");
+    assert!(
+        cargo_result.status.success(),
+        "cargo failed with {}",
+        String::from_utf8_lossy(&cargo_result.stderr)
+    );
+    assert!(String::from_utf8_lossy(&cargo_result.stdout).contains("builttestsuccess"));
+}